@@ -0,0 +1,167 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use capstone::Capstone;
+
+use crate::analysis::decode::Decoded;
+use crate::analysis::traversal;
+use crate::analysis::Image;
+use crate::capstone::mode::CapstoneMode;
+
+/// Kind of control-flow edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    Fallthrough = 0,
+    Taken = 1,
+    Call = 2,
+    Indirect = 3,
+}
+
+/// An edge between two basic blocks, identified by their index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Edge {
+    pub kind: EdgeKind,
+    pub from: usize,
+    pub to: usize,
+}
+
+/// A straight-line sequence of instructions with a single entry and exit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasicBlock {
+    pub start: u64,
+    /// Address following the last instruction of the block.
+    pub end: u64,
+    pub instruction_count: usize,
+    /// Indices of the outgoing edges.
+    pub successors: Vec<usize>,
+    /// Indices of the incoming edges.
+    pub predecessors: Vec<usize>,
+}
+
+/// A control-flow graph recovered from an image.
+#[derive(Debug, Default)]
+pub struct ControlFlowGraph {
+    pub blocks: Vec<BasicBlock>,
+    pub edges: Vec<Edge>,
+}
+
+impl ControlFlowGraph {
+    /// Build the graph from already decoded instructions.
+    pub fn from_instructions(instructions: &BTreeMap<u64, Decoded>, entries: &[u64]) -> Self {
+        let leaders = find_leaders(instructions, entries);
+
+        // Split the instructions into blocks
+        let mut graph = ControlFlowGraph::default();
+        let mut last_insns: Vec<&Decoded> = Vec::new();
+        let mut previous: Option<&Decoded> = None;
+
+        for insn in instructions.values() {
+            let starts_block = match previous {
+                None => true,
+                Some(previous) => {
+                    leaders.contains(&insn.address)
+                        || previous.next() != insn.address
+                        || previous.flow.is_branch
+                        || previous.flow.is_terminator
+                }
+            };
+
+            if starts_block {
+                graph.blocks.push(BasicBlock {
+                    start: insn.address,
+                    end: insn.next(),
+                    instruction_count: 1,
+                    successors: Vec::new(),
+                    predecessors: Vec::new(),
+                });
+                last_insns.push(insn);
+            } else {
+                let block = graph.blocks.last_mut().unwrap();
+                block.end = insn.next();
+                block.instruction_count += 1;
+                *last_insns.last_mut().unwrap() = insn;
+            }
+
+            previous = Some(insn);
+        }
+
+        let block_at: HashMap<u64, usize> = graph
+            .blocks
+            .iter()
+            .enumerate()
+            .map(|(i, block)| (block.start, i))
+            .collect();
+
+        // Connect the blocks based on the last instruction of each block
+        let mut seen: HashSet<Edge> = HashSet::new();
+        for (from, last) in last_insns.iter().enumerate() {
+            let mut connect = |kind: EdgeKind, address: u64| {
+                if let Some(&to) = block_at.get(&address) {
+                    let edge = Edge { kind, from, to };
+                    if seen.insert(edge) {
+                        graph.add_edge(edge);
+                    }
+                }
+            };
+
+            let flow = &last.flow;
+            if flow.is_branch {
+                let kind = if flow.is_call {
+                    EdgeKind::Call
+                } else {
+                    EdgeKind::Taken
+                };
+                if let Some(target) = last.followable_target() {
+                    connect(kind, target);
+                }
+                for target in &last.indirect_targets {
+                    connect(EdgeKind::Indirect, *target);
+                }
+            }
+
+            if !flow.is_terminator {
+                connect(EdgeKind::Fallthrough, last.next());
+            }
+        }
+
+        graph
+    }
+
+    /// Add an edge and register it with the blocks it connects.
+    fn add_edge(&mut self, edge: Edge) {
+        let index = self.edges.len();
+        self.blocks[edge.from].successors.push(index);
+        self.blocks[edge.to].predecessors.push(index);
+        self.edges.push(edge);
+    }
+}
+
+/// Find the addresses that must start a basic block.
+fn find_leaders(instructions: &BTreeMap<u64, Decoded>, entries: &[u64]) -> BTreeSet<u64> {
+    let mut leaders: BTreeSet<u64> = entries.iter().copied().collect();
+
+    for insn in instructions.values() {
+        leaders.extend(insn.followable_target());
+        leaders.extend(insn.indirect_targets.iter().copied());
+        if insn.flow.is_branch {
+            leaders.insert(insn.next());
+        }
+    }
+
+    leaders
+}
+
+/// Recover the control-flow graph reachable from the given entry points.
+pub fn build(
+    capstone: &Capstone,
+    mode: &CapstoneMode,
+    image: &Image,
+    entries: &[u64],
+) -> ControlFlowGraph {
+    let instructions = traversal::traverse(capstone, mode, image, entries);
+    ControlFlowGraph::from_instructions(&instructions, entries)
+}
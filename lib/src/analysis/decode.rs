@@ -0,0 +1,171 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use capstone::{Capstone, Insn};
+
+use crate::analysis::flow::{self, BranchTarget, FlowInfo};
+use crate::analysis::{pcrel, Image};
+use crate::capstone::mode::CapstoneMode;
+
+/// The control-flow relevant parts of a decoded instruction.
+#[derive(Debug, Clone)]
+pub struct Decoded {
+    pub address: u64,
+    pub size: u64,
+    pub flow: FlowInfo,
+    /// Target of a direct branch.
+    pub target: Option<BranchTarget>,
+    /// Known targets of an indirect branch.
+    pub indirect_targets: Vec<u64>,
+}
+
+impl Decoded {
+    /// Address of the instruction following this one.
+    pub fn next(&self) -> u64 {
+        self.address + self.size
+    }
+
+    /// Returns the direct branch target if it can be followed in the current mode.
+    pub fn followable_target(&self) -> Option<u64> {
+        self.target
+            .filter(|target| !target.is_interworking)
+            .map(|target| target.address)
+    }
+}
+
+/// Check whether control-flow analysis is available for the given mode.
+pub fn is_supported(mode: &CapstoneMode) -> bool {
+    matches!(
+        mode,
+        CapstoneMode::ARM32 | CapstoneMode::Thumb | CapstoneMode::ARM64
+    )
+}
+
+/// Classify an instruction that has already been disassembled.
+pub fn classify(
+    capstone: &Capstone,
+    mode: &CapstoneMode,
+    image: &Image,
+    insn: &Insn,
+) -> Option<Decoded> {
+    let detail = capstone.insn_detail(insn).ok()?;
+    let arch_detail = detail.arch_detail();
+
+    let (flow, target) = match mode {
+        CapstoneMode::ARM32 | CapstoneMode::Thumb => {
+            let arch = arch_detail.arm()?;
+            (
+                flow::classify_arm(insn.id(), detail.groups(), arch),
+                flow::branch_target_arm(insn.id(), arch),
+            )
+        }
+        CapstoneMode::ARM64 => {
+            let arch = arch_detail.arm64()?;
+            (
+                flow::classify_arm64(insn.id(), detail.groups(), arch),
+                flow::branch_target_arm64(insn.id(), arch),
+            )
+        }
+        _ => return None,
+    };
+
+    // 'ldr pc, [pc, #imm]' jumps to a constant we may be able to read
+    let mut indirect_targets = Vec::new();
+    if flow.is_branch && flow.is_indirect && !flow.is_return {
+        let reference = pcrel::resolve(mode, capstone, &[insn], image)
+            .and_then(|references| references.into_iter().next().flatten());
+        if let Some(value) = reference.and_then(|reference| reference.value) {
+            // The low bit selects Thumb, we can only follow targets in the current state
+            let thumb = matches!(mode, CapstoneMode::Thumb);
+            if (value & 1 == 1) == thumb {
+                indirect_targets.push(value & !1);
+            }
+        }
+    }
+
+    Some(Decoded {
+        address: insn.address(),
+        size: insn.len() as u64,
+        flow,
+        target,
+        indirect_targets,
+    })
+}
+
+/// Classify each of the given instructions. The result is index-aligned with
+/// `instructions`.
+pub fn classify_all(
+    capstone: &Capstone,
+    mode: &CapstoneMode,
+    image: &Image,
+    instructions: &[&Insn],
+) -> Vec<Option<Decoded>> {
+    instructions
+        .iter()
+        .map(|insn| classify(capstone, mode, image, insn))
+        .collect()
+}
+
+/// Decode and classify the instruction at the given address.
+pub fn decode(
+    capstone: &Capstone,
+    mode: &CapstoneMode,
+    image: &Image,
+    address: u64,
+) -> Option<Decoded> {
+    let code = image.tail(address)?;
+    let instructions = capstone.disasm_count(code, address, 1).ok()?;
+    let insn = instructions.first()?;
+    classify(capstone, mode, image, insn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::testing::{disassemble, ARM64_CODE};
+
+    #[test]
+    fn classify_arm64() {
+        disassemble(
+            CapstoneMode::ARM64,
+            ARM64_CODE,
+            0x1000,
+            |cs, mode, insns, image| {
+                let decoded: Vec<Decoded> = insns
+                    .iter()
+                    .map(|insn| classify(cs, mode, image, insn).unwrap())
+                    .collect();
+
+                assert!(!decoded[0].flow.is_branch);
+                assert!(decoded[4].flow.is_call);
+                assert_eq!(decoded[4].followable_target(), Some(0xedc));
+                assert_eq!(decoded[4].next(), 0x1014);
+                assert!(decoded[7].flow.is_return);
+                assert!(decoded[7].flow.is_terminator);
+            },
+        );
+    }
+
+    #[test]
+    fn classify_arm32() {
+        // 'beq #0x10', 'bx lr'
+        let code = [0x02, 0x00, 0x00, 0x0a, 0x1e, 0xff, 0x2f, 0xe1];
+        disassemble(CapstoneMode::ARM32, &code, 0, |cs, mode, insns, image| {
+            let branch = classify(cs, mode, image, insns[0]).unwrap();
+            assert!(branch.flow.is_branch && branch.flow.is_conditional);
+            assert!(!branch.flow.is_terminator);
+            assert_eq!(branch.followable_target(), Some(0x10));
+
+            let ret = classify(cs, mode, image, insns[1]).unwrap();
+            assert!(ret.flow.is_return && ret.flow.is_terminator);
+        });
+    }
+
+    #[test]
+    fn unsupported_modes() {
+        assert!(is_supported(&CapstoneMode::Thumb));
+        assert!(!is_supported(&CapstoneMode::X86_64));
+    }
+}
@@ -0,0 +1,377 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use capstone::arch::arm::{ArmCC, ArmInsn, ArmInsnDetail, ArmOperandType, ArmReg, ArmShift};
+use capstone::arch::arm64::{Arm64CC, Arm64Insn, Arm64InsnDetail, Arm64OperandType, Arm64Reg};
+use capstone::arch::DetailsArchInsn;
+use capstone::{Insn, InsnDetail, InsnIdInt, RegId, RegIdInt};
+
+// Condition flag masks, in the order of the bits of the NZCV register
+pub const FLAG_N: u8 = 0b1000;
+pub const FLAG_Z: u8 = 0b0100;
+pub const FLAG_C: u8 = 0b0010;
+pub const FLAG_V: u8 = 0b0001;
+pub const FLAGS_ALL: u8 = FLAG_N | FLAG_Z | FLAG_C | FLAG_V;
+
+/// Condition flag masks with their Java constant names.
+pub const FLAGS: &[(&str, u8)] = &[
+    ("ARM_FLAG_N", FLAG_N),
+    ("ARM_FLAG_Z", FLAG_Z),
+    ("ARM_FLAG_C", FLAG_C),
+    ("ARM_FLAG_V", FLAG_V),
+];
+
+/// The `nzcv` system register operand of ARM64 `mrs` and `msr`.
+const ARM64_SYSREG_NZCV: u32 = 0xda10;
+
+// The MSR mask bits that select the flags byte of the CPSR or SPSR, and the APSR
+// special registers that include the flags
+const ARM_SYSREG_FLAGS_MASK: RegIdInt = 0x88;
+const ARM_SYSREG_APSR: RegIdInt = 256;
+const ARM_SYSREG_APSR_NZCVQ: RegIdInt = 258;
+const ARM_SYSREG_APSR_NZCVQG: RegIdInt = 259;
+
+/// The NZCV condition flags an instruction reads and may write, as `FLAG_*` masks.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FlagEffects {
+    pub read: u8,
+    pub written: u8,
+}
+
+/// Get the flags tested by an ARM condition code.
+pub fn condition_flags_arm(cc: ArmCC) -> u8 {
+    use ArmCC::*;
+
+    match cc {
+        ARM_CC_EQ | ARM_CC_NE => FLAG_Z,
+        ARM_CC_HS | ARM_CC_LO => FLAG_C,
+        ARM_CC_MI | ARM_CC_PL => FLAG_N,
+        ARM_CC_VS | ARM_CC_VC => FLAG_V,
+        ARM_CC_HI | ARM_CC_LS => FLAG_C | FLAG_Z,
+        ARM_CC_GE | ARM_CC_LT => FLAG_N | FLAG_V,
+        ARM_CC_GT | ARM_CC_LE => FLAG_N | FLAG_Z | FLAG_V,
+        ARM_CC_AL | ARM_CC_INVALID => 0,
+    }
+}
+
+/// Get the flags tested by an ARM64 condition code.
+pub fn condition_flags_arm64(cc: Arm64CC) -> u8 {
+    use Arm64CC::*;
+
+    match cc {
+        ARM64_CC_EQ | ARM64_CC_NE => FLAG_Z,
+        ARM64_CC_HS | ARM64_CC_LO => FLAG_C,
+        ARM64_CC_MI | ARM64_CC_PL => FLAG_N,
+        ARM64_CC_VS | ARM64_CC_VC => FLAG_V,
+        ARM64_CC_HI | ARM64_CC_LS => FLAG_C | FLAG_Z,
+        ARM64_CC_GE | ARM64_CC_LT => FLAG_N | FLAG_V,
+        ARM64_CC_GT | ARM64_CC_LE => FLAG_N | FLAG_Z | FLAG_V,
+        ARM64_CC_AL | ARM64_CC_NV | ARM64_CC_INVALID => 0,
+    }
+}
+
+/// Compute the flag effects of an ARM or Thumb instruction.
+pub fn effects_arm(insn: &Insn, detail: &InsnDetail, arch: &ArmInsnDetail) -> FlagEffects {
+    use ArmInsn::*;
+
+    let id = insn.id().0;
+    let is = |insn: ArmInsn| id == insn as InsnIdInt;
+    let operands: Vec<ArmOperandType> = arch.operands().map(|op| op.op_type).collect();
+    let reads_flags_register = has_reg(
+        detail.regs_read(),
+        &[ArmReg::ARM_REG_CPSR, ArmReg::ARM_REG_APSR],
+    );
+
+    // `it` only sets up the conditions of the instructions that follow it
+    let mut read = if is(ARM_INS_IT) {
+        0
+    } else {
+        condition_flags_arm(arch.cc())
+    };
+    let reads_carry = is(ARM_INS_ADC)
+        || is(ARM_INS_SBC)
+        || is(ARM_INS_RSC)
+        || is(ARM_INS_RRX)
+        || arch
+            .operands()
+            .any(|op| matches!(op.shift, ArmShift::Rrx(_)));
+    if reads_carry {
+        read |= FLAG_C;
+    } else if is(ARM_INS_MRS) {
+        if let Some(ArmOperandType::Reg(reg)) = operands.get(1) {
+            if has_reg(&[*reg], &[ArmReg::ARM_REG_APSR, ArmReg::ARM_REG_CPSR]) {
+                read |= FLAGS_ALL;
+            }
+        }
+    } else if read == 0 && reads_flags_register {
+        read = FLAGS_ALL;
+    }
+
+    // Capstone reports `update_flags` for `adc`, `sbc` and `rsc` even without the `s`
+    // suffix, so check the mnemonic instead. No condition code starts with `s`.
+    let updates_flags = if is(ARM_INS_ADC) || is(ARM_INS_SBC) || is(ARM_INS_RSC) {
+        insn.mnemonic()
+            .is_some_and(|m| m.as_bytes().get(3) == Some(&b's'))
+    } else {
+        arch.update_flags()
+    };
+
+    let written = if is(ARM_INS_MSR) {
+        match operands.first() {
+            Some(ArmOperandType::SysReg(reg))
+                if reg.0 < ARM_SYSREG_APSR && reg.0 & ARM_SYSREG_FLAGS_MASK != 0 =>
+            {
+                FLAGS_ALL
+            }
+            Some(ArmOperandType::SysReg(reg))
+                if reg.0 == ARM_SYSREG_APSR_NZCVQ || reg.0 == ARM_SYSREG_APSR_NZCVQG =>
+            {
+                FLAGS_ALL
+            }
+            _ => 0,
+        }
+    } else if is(ARM_INS_VMRS) {
+        match operands.first() {
+            Some(ArmOperandType::Reg(reg)) if reg.0 == ArmReg::ARM_REG_APSR_NZCV as RegIdInt => {
+                FLAGS_ALL
+            }
+            _ => 0,
+        }
+    } else if !updates_flags {
+        0
+    } else if is(ARM_INS_MUL)
+        || is(ARM_INS_MLA)
+        || is(ARM_INS_SMULL)
+        || is(ARM_INS_UMULL)
+        || is(ARM_INS_SMLAL)
+        || is(ARM_INS_UMLAL)
+    {
+        FLAG_N | FLAG_Z
+    } else if is(ARM_INS_LSL)
+        || is(ARM_INS_LSR)
+        || is(ARM_INS_ASR)
+        || is(ARM_INS_ROR)
+        || is(ARM_INS_RRX)
+    {
+        FLAG_N | FLAG_Z | FLAG_C
+    } else if is(ARM_INS_AND)
+        || is(ARM_INS_ORR)
+        || is(ARM_INS_ORN)
+        || is(ARM_INS_EOR)
+        || is(ARM_INS_BIC)
+        || is(ARM_INS_MVN)
+        || is(ARM_INS_MOV)
+        || is(ARM_INS_TST)
+        || is(ARM_INS_TEQ)
+    {
+        // Logical instructions only write the carry out of a shifted operand, or of an
+        // immediate that needs a rotation to be encoded
+        let shifted = arch.operands().last().is_some_and(|op| match op.op_type {
+            ArmOperandType::Imm(imm) => imm as u32 > 0xff,
+            _ => !matches!(op.shift, ArmShift::Invalid),
+        });
+        if shifted {
+            FLAG_N | FLAG_Z | FLAG_C
+        } else {
+            FLAG_N | FLAG_Z
+        }
+    } else {
+        FLAGS_ALL
+    };
+
+    FlagEffects { read, written }
+}
+
+/// Compute the flag effects of an ARM64 instruction.
+pub fn effects_arm64(insn: &Insn, detail: &InsnDetail, arch: &Arm64InsnDetail) -> FlagEffects {
+    use Arm64Insn::*;
+
+    let id = insn.id().0;
+    let is = |insn: Arm64Insn| id == insn as InsnIdInt;
+    let is_nzcv = |op: Option<&Arm64OperandType>| match op {
+        Some(Arm64OperandType::RegMrs(reg) | Arm64OperandType::RegMsr(reg)) => {
+            *reg as u32 == ARM64_SYSREG_NZCV
+        }
+        Some(Arm64OperandType::Sys(reg)) => *reg == ARM64_SYSREG_NZCV,
+        _ => false,
+    };
+    let operands: Vec<Arm64OperandType> = arch.operands().map(|op| op.op_type).collect();
+
+    let mut read = condition_flags_arm64(arch.cc());
+    if is(ARM64_INS_ADC)
+        || is(ARM64_INS_ADCS)
+        || is(ARM64_INS_SBC)
+        || is(ARM64_INS_SBCS)
+        || is(ARM64_INS_NGC)
+        || is(ARM64_INS_NGCS)
+        || is(ARM64_INS_CFINV)
+    {
+        read |= FLAG_C;
+    } else if is(ARM64_INS_MRS) && is_nzcv(operands.get(1)) {
+        read |= FLAGS_ALL;
+    } else if read == 0 && has_reg(detail.regs_read(), &[Arm64Reg::ARM64_REG_NZCV]) {
+        read = FLAGS_ALL;
+    }
+
+    let written = if is(ARM64_INS_CFINV) {
+        FLAG_C
+    } else if is(ARM64_INS_SETF8) || is(ARM64_INS_SETF16) {
+        FLAG_N | FLAG_Z | FLAG_V
+    } else if is(ARM64_INS_RMIF) {
+        // The mask selects the flags to replace, in NZCV order
+        match operands.get(2) {
+            Some(Arm64OperandType::Imm(mask)) => *mask as u8 & FLAGS_ALL,
+            _ => FLAGS_ALL,
+        }
+    } else if is(ARM64_INS_MSR) {
+        // Capstone reports `update_flags` for writes to PSTATE fields like `daifset`
+        if is_nzcv(operands.first()) {
+            FLAGS_ALL
+        } else {
+            0
+        }
+    } else if arch.update_flags() {
+        // Logical instructions like `ands` clear C and V, so they write every flag too
+        FLAGS_ALL
+    } else {
+        0
+    };
+
+    if let Some(effects) = effects_flag_format(insn.mnemonic().unwrap_or_default()) {
+        return effects;
+    }
+
+    FlagEffects { read, written }
+}
+
+/// Get the flags read and written by the `axflag` and `xaflag` conversions between
+/// the Arm and external floating-point comparison formats. Capstone has no
+/// instruction ids for these, so they are matched by mnemonic.
+fn effects_flag_format(mnemonic: &str) -> Option<FlagEffects> {
+    match mnemonic {
+        "axflag" => Some(FlagEffects {
+            read: FLAG_Z | FLAG_C | FLAG_V,
+            written: FLAGS_ALL,
+        }),
+        "xaflag" => Some(FlagEffects {
+            read: FLAG_Z | FLAG_C,
+            written: FLAGS_ALL,
+        }),
+        _ => None,
+    }
+}
+
+/// Check whether any of the registers is one of `ids`.
+fn has_reg(regs: &[RegId], ids: &[u32]) -> bool {
+    regs.iter()
+        .any(|reg| ids.iter().any(|id| reg.0 == *id as RegIdInt))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::testing::disassemble;
+    use crate::capstone::mode::CapstoneMode;
+
+    fn effects(mode: CapstoneMode, code: &[u8]) -> Vec<FlagEffects> {
+        disassemble(mode, code, 0x1000, |cs, mode, insns, _| {
+            insns
+                .iter()
+                .map(|insn| {
+                    let detail = cs.insn_detail(insn).unwrap();
+                    let arch_detail = detail.arch_detail();
+                    match mode {
+                        CapstoneMode::ARM64 => {
+                            effects_arm64(insn, &detail, arch_detail.arm64().unwrap())
+                        }
+                        _ => effects_arm(insn, &detail, arch_detail.arm().unwrap()),
+                    }
+                })
+                .collect()
+        })
+    }
+
+    fn flags(read: u8, written: u8) -> FlagEffects {
+        FlagEffects { read, written }
+    }
+
+    #[test]
+    fn arm64_effects() {
+        // 'cmp x0, #3', 'ccmp x0, #2, #4, ne', 'csel x0, x1, x2, eq', 'adc x0, x1, x2',
+        // 'msr nzcv, x0', 'mrs x1, nzcv', 'cfinv', 'add x0, x1, #1', 'msr daifset, #2'
+        let code = [
+            0x1f, 0x0c, 0x00, 0xf1, 0x04, 0x18, 0x42, 0xfa, 0x20, 0x00, 0x82, 0x9a, 0x20, 0x00,
+            0x02, 0x9a, 0x00, 0x42, 0x1b, 0xd5, 0x01, 0x42, 0x3b, 0xd5, 0x1f, 0x40, 0x00, 0xd5,
+            0x20, 0x04, 0x00, 0x91, 0xdf, 0x42, 0x03, 0xd5,
+        ];
+        assert_eq!(
+            effects(CapstoneMode::ARM64, &code),
+            [
+                flags(0, FLAGS_ALL),
+                flags(FLAG_Z, FLAGS_ALL),
+                flags(FLAG_Z, 0),
+                flags(FLAG_C, 0),
+                flags(0, FLAGS_ALL),
+                flags(FLAGS_ALL, 0),
+                flags(FLAG_C, FLAG_C),
+                flags(0, 0),
+                flags(0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn arm64_flag_manipulation() {
+        // 'setf8 w1', 'setf16 w2', 'rmif x3, #4, #2', 'rmif x3, #0, #15'
+        let code = [
+            0x2d, 0x08, 0x00, 0x3a, 0x4d, 0x48, 0x00, 0x3a, 0x62, 0x04, 0x02, 0xba, 0x6f, 0x04,
+            0x00, 0xba,
+        ];
+        assert_eq!(
+            effects(CapstoneMode::ARM64, &code),
+            [
+                flags(0, FLAG_N | FLAG_Z | FLAG_V),
+                flags(0, FLAG_N | FLAG_Z | FLAG_V),
+                flags(0, FLAG_C),
+                flags(0, FLAGS_ALL),
+            ]
+        );
+
+        assert_eq!(
+            effects_flag_format("axflag"),
+            Some(flags(FLAG_Z | FLAG_C | FLAG_V, FLAGS_ALL))
+        );
+        assert_eq!(
+            effects_flag_format("xaflag"),
+            Some(flags(FLAG_Z | FLAG_C, FLAGS_ALL))
+        );
+        assert_eq!(effects_flag_format("cfinv"), None);
+    }
+
+    #[test]
+    fn arm32_effects() {
+        // 'adds r0, r1, #1', 'adc r0, r1, r2', 'adcs r0, r1, r2', 'movs r0, r1',
+        // 'ands r0, r1, r2, lsl #2', 'addgt r0, r1, r2', 'msr apsr_nzcvq, r0',
+        // 'vmrs APSR_nzcv, fpscr'
+        let code = [
+            0x01, 0x00, 0x91, 0xe2, 0x02, 0x00, 0xa1, 0xe0, 0x02, 0x00, 0xb1, 0xe0, 0x01, 0x00,
+            0xb0, 0xe1, 0x02, 0x01, 0x11, 0xe0, 0x02, 0x00, 0x81, 0xc0, 0x00, 0xf0, 0x28, 0xe1,
+            0x10, 0xfa, 0xf1, 0xee,
+        ];
+        assert_eq!(
+            effects(CapstoneMode::ARM32, &code),
+            [
+                flags(0, FLAGS_ALL),
+                flags(FLAG_C, 0),
+                flags(FLAG_C, FLAGS_ALL),
+                flags(0, FLAG_N | FLAG_Z),
+                flags(0, FLAG_N | FLAG_Z | FLAG_C),
+                flags(FLAG_N | FLAG_Z | FLAG_V, 0),
+                flags(0, FLAGS_ALL),
+                flags(0, FLAGS_ALL),
+            ]
+        );
+    }
+}
@@ -0,0 +1,365 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use capstone::arch::arm::{ArmCC, ArmInsn, ArmInsnDetail, ArmOperand, ArmOperandType, ArmReg};
+use capstone::arch::arm64::{Arm64CC, Arm64Insn, Arm64InsnDetail, Arm64OperandType};
+use capstone::arch::DetailsArchInsn;
+use capstone::{InsnGroupId, InsnGroupIdInt, InsnId, InsnIdInt, RegId, RegIdInt};
+
+// Common Capstone instruction groups
+const GRP_JUMP: InsnGroupIdInt = 1;
+const GRP_CALL: InsnGroupIdInt = 2;
+const GRP_RET: InsnGroupIdInt = 3;
+const GRP_INT: InsnGroupIdInt = 4;
+const GRP_IRET: InsnGroupIdInt = 5;
+const GRP_PRIVILEGE: InsnGroupIdInt = 6;
+const GRP_BRANCH_RELATIVE: InsnGroupIdInt = 7;
+
+/// Control-flow classification of a single instruction.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FlowInfo {
+    /// The instruction may transfer control somewhere other than the next instruction.
+    /// This includes calls and returns.
+    pub is_branch: bool,
+    /// The control transfer only happens if a condition holds.
+    pub is_conditional: bool,
+    pub is_call: bool,
+    pub is_return: bool,
+    /// The target is taken from a register or memory.
+    pub is_indirect: bool,
+    /// The instruction computes an address relative to the PC.
+    pub is_pc_relative: bool,
+    pub is_privileged: bool,
+    pub is_interrupt: bool,
+    /// Execution never continues with the next instruction.
+    pub is_terminator: bool,
+}
+
+impl FlowInfo {
+    /// Apply the common Capstone groups of an instruction.
+    fn from_groups(groups: &[InsnGroupId]) -> FlowInfo {
+        let has = |group: InsnGroupIdInt| groups.iter().any(|g| g.0 == group);
+
+        let is_call = has(GRP_CALL);
+        let is_return = has(GRP_RET) || has(GRP_IRET);
+        FlowInfo {
+            is_branch: has(GRP_JUMP) || is_call || is_return,
+            is_call,
+            is_return,
+            is_pc_relative: has(GRP_BRANCH_RELATIVE),
+            is_privileged: has(GRP_PRIVILEGE),
+            is_interrupt: has(GRP_INT),
+            ..FlowInfo::default()
+        }
+    }
+
+    /// Mark the instruction as a jump to a computed target.
+    fn indirect_jump(&mut self) {
+        self.is_branch = true;
+        self.is_indirect = true;
+    }
+
+    /// Mark the instruction as a return.
+    fn return_(&mut self) {
+        self.is_branch = true;
+        self.is_return = true;
+        self.is_indirect = true;
+    }
+
+    /// Derive the terminator flag once everything else is known.
+    fn finish(mut self, is_conditional: bool) -> FlowInfo {
+        self.is_conditional = self.is_branch && is_conditional;
+        self.is_terminator |= self.is_branch && !self.is_call && !self.is_conditional;
+        self
+    }
+}
+
+/// Target of a direct branch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BranchTarget {
+    /// Absolute address of the target.
+    pub address: u64,
+    /// The branch switches between the ARM and Thumb instruction sets.
+    pub is_interworking: bool,
+}
+
+fn is_arm_insn(id: InsnId, ids: &[ArmInsn]) -> bool {
+    ids.iter().any(|i| id.0 == *i as InsnIdInt)
+}
+
+fn is_arm64_insn(id: InsnId, ids: &[Arm64Insn]) -> bool {
+    ids.iter().any(|i| id.0 == *i as InsnIdInt)
+}
+
+fn is_reg(reg: RegId, id: u32) -> bool {
+    reg.0 == id as RegIdInt
+}
+
+/// ARM32 data-processing instructions that branch when their destination is the PC.
+const DATA_PROCESSING: &[ArmInsn] = &[
+    ArmInsn::ARM_INS_ADD,
+    ArmInsn::ARM_INS_ADC,
+    ArmInsn::ARM_INS_SUB,
+    ArmInsn::ARM_INS_SBC,
+    ArmInsn::ARM_INS_RSB,
+    ArmInsn::ARM_INS_RSC,
+    ArmInsn::ARM_INS_AND,
+    ArmInsn::ARM_INS_ORR,
+    ArmInsn::ARM_INS_EOR,
+    ArmInsn::ARM_INS_BIC,
+    ArmInsn::ARM_INS_MVN,
+    ArmInsn::ARM_INS_LSL,
+    ArmInsn::ARM_INS_LSR,
+    ArmInsn::ARM_INS_ASR,
+    ArmInsn::ARM_INS_ROR,
+];
+
+/// Classify an ARM32 instruction.
+pub fn classify_arm(id: InsnId, groups: &[InsnGroupId], arch: &ArmInsnDetail) -> FlowInfo {
+    use ArmInsn::*;
+
+    let mut flow = FlowInfo::from_groups(groups);
+    let operands: Vec<ArmOperand> = arch.operands().collect();
+
+    let first_reg = operands.first().and_then(|op| match op.op_type {
+        ArmOperandType::Reg(reg) => Some(reg),
+        _ => None,
+    });
+    let writes_pc = first_reg.is_some_and(|reg| is_reg(reg, ArmReg::ARM_REG_PC));
+    let lists_pc = operands.iter().any(
+        |op| matches!(op.op_type, ArmOperandType::Reg(reg) if is_reg(reg, ArmReg::ARM_REG_PC)),
+    );
+    let reads_pc = operands.iter().skip(1).any(|op| match op.op_type {
+        ArmOperandType::Reg(reg) => is_reg(reg, ArmReg::ARM_REG_PC),
+        ArmOperandType::Mem(ref mem) => is_reg(mem.base(), ArmReg::ARM_REG_PC),
+        _ => false,
+    });
+    let has_reg_operand = first_reg.is_some();
+
+    if is_arm_insn(id, &[ARM_INS_B, ARM_INS_CBZ, ARM_INS_CBNZ]) {
+        flow.is_branch = true;
+        flow.is_pc_relative = true;
+    } else if is_arm_insn(id, &[ARM_INS_BL, ARM_INS_BLX, ARM_INS_BLXNS]) {
+        flow.is_branch = true;
+        flow.is_call = true;
+        flow.is_indirect = has_reg_operand;
+        flow.is_pc_relative = !has_reg_operand;
+    } else if is_arm_insn(id, &[ARM_INS_BX, ARM_INS_BXJ, ARM_INS_BXNS]) {
+        if first_reg.is_some_and(|reg| is_reg(reg, ArmReg::ARM_REG_LR)) {
+            flow.return_();
+        } else {
+            flow.indirect_jump();
+        }
+    } else if is_arm_insn(id, &[ARM_INS_TBB, ARM_INS_TBH]) {
+        flow.indirect_jump();
+    } else if is_arm_insn(id, &[ARM_INS_POP]) && lists_pc {
+        flow.return_();
+    } else if is_arm_insn(
+        id,
+        &[ARM_INS_LDM, ARM_INS_LDMDA, ARM_INS_LDMDB, ARM_INS_LDMIB],
+    ) && lists_pc
+    {
+        // 'ldm sp!, {..., pc}' is a pop in disguise
+        if first_reg.is_some_and(|reg| is_reg(reg, ArmReg::ARM_REG_SP)) {
+            flow.return_();
+        } else {
+            flow.indirect_jump();
+        }
+    } else if is_arm_insn(id, &[ARM_INS_LDR]) && writes_pc {
+        let from_stack = operands.iter().any(|op| {
+            matches!(op.op_type, ArmOperandType::Mem(ref mem) if is_reg(mem.base(), ArmReg::ARM_REG_SP))
+        });
+        if from_stack {
+            flow.return_();
+        } else {
+            flow.indirect_jump();
+        }
+    } else if is_arm_insn(id, &[ARM_INS_MOV]) && writes_pc {
+        let from_lr = operands.get(1).is_some_and(
+            |op| matches!(op.op_type, ArmOperandType::Reg(reg) if is_reg(reg, ArmReg::ARM_REG_LR)),
+        );
+        if from_lr {
+            flow.return_();
+        } else {
+            flow.indirect_jump();
+        }
+    } else if is_arm_insn(id, &[ARM_INS_SUBS]) && writes_pc {
+        // 'subs pc, lr, #imm' returns from an exception
+        flow.return_();
+        flow.is_privileged = true;
+    } else if writes_pc && is_arm_insn(id, DATA_PROCESSING) {
+        flow.indirect_jump();
+    } else if is_arm_insn(
+        id,
+        &[
+            ARM_INS_ERET,
+            ARM_INS_RFEDA,
+            ARM_INS_RFEDB,
+            ARM_INS_RFEIA,
+            ARM_INS_RFEIB,
+        ],
+    ) {
+        flow.return_();
+        flow.is_privileged = true;
+    } else if is_arm_insn(id, &[ARM_INS_SVC, ARM_INS_BKPT]) {
+        flow.is_interrupt = true;
+    } else if is_arm_insn(id, &[ARM_INS_SMC, ARM_INS_HVC]) {
+        flow.is_interrupt = true;
+        flow.is_privileged = true;
+    } else if is_arm_insn(id, &[ARM_INS_UDF]) {
+        flow.is_terminator = true;
+    }
+
+    if is_arm_insn(id, &[ARM_INS_ADR]) || reads_pc {
+        flow.is_pc_relative = true;
+    }
+
+    if is_arm_insn(
+        id,
+        &[
+            ARM_INS_CPS,
+            ARM_INS_SRSDA,
+            ARM_INS_SRSDB,
+            ARM_INS_SRSIA,
+            ARM_INS_SRSIB,
+        ],
+    ) {
+        flow.is_privileged = true;
+    }
+
+    let is_conditional = is_arm_insn(id, &[ARM_INS_CBZ, ARM_INS_CBNZ])
+        || !matches!(arch.cc(), ArmCC::ARM_CC_AL | ArmCC::ARM_CC_INVALID);
+    flow.finish(is_conditional)
+}
+
+/// Classify an ARM64 instruction.
+pub fn classify_arm64(id: InsnId, groups: &[InsnGroupId], arch: &Arm64InsnDetail) -> FlowInfo {
+    use Arm64Insn::*;
+
+    let mut flow = FlowInfo::from_groups(groups);
+
+    if is_arm64_insn(id, &[ARM64_INS_B]) {
+        flow.is_branch = true;
+        flow.is_pc_relative = true;
+    } else if is_arm64_insn(
+        id,
+        &[ARM64_INS_CBZ, ARM64_INS_CBNZ, ARM64_INS_TBZ, ARM64_INS_TBNZ],
+    ) {
+        flow.is_branch = true;
+        flow.is_conditional = true;
+        flow.is_pc_relative = true;
+    } else if is_arm64_insn(id, &[ARM64_INS_BL]) {
+        flow.is_branch = true;
+        flow.is_call = true;
+        flow.is_pc_relative = true;
+    } else if is_arm64_insn(
+        id,
+        &[
+            ARM64_INS_BLR,
+            ARM64_INS_BLRAA,
+            ARM64_INS_BLRAAZ,
+            ARM64_INS_BLRAB,
+            ARM64_INS_BLRABZ,
+        ],
+    ) {
+        flow.is_branch = true;
+        flow.is_call = true;
+        flow.is_indirect = true;
+    } else if is_arm64_insn(
+        id,
+        &[
+            ARM64_INS_BR,
+            ARM64_INS_BRAA,
+            ARM64_INS_BRAAZ,
+            ARM64_INS_BRAB,
+            ARM64_INS_BRABZ,
+        ],
+    ) {
+        flow.indirect_jump();
+    } else if is_arm64_insn(id, &[ARM64_INS_RET, ARM64_INS_RETAA, ARM64_INS_RETAB]) {
+        flow.return_();
+    } else if is_arm64_insn(id, &[ARM64_INS_ERET, ARM64_INS_ERETAA, ARM64_INS_ERETAB]) {
+        flow.return_();
+        flow.is_privileged = true;
+    } else if is_arm64_insn(id, &[ARM64_INS_SVC, ARM64_INS_BRK, ARM64_INS_HLT]) {
+        flow.is_interrupt = true;
+    } else if is_arm64_insn(id, &[ARM64_INS_HVC, ARM64_INS_SMC]) {
+        flow.is_interrupt = true;
+        flow.is_privileged = true;
+    } else if is_arm64_insn(id, &[ARM64_INS_ADR, ARM64_INS_ADRP]) {
+        flow.is_pc_relative = true;
+    } else if is_arm64_insn(id, &[ARM64_INS_LDR, ARM64_INS_LDRSW, ARM64_INS_PRFM]) {
+        // Literal loads encode their address as an immediate operand
+        flow.is_pc_relative = arch
+            .operands()
+            .any(|op| matches!(op.op_type, Arm64OperandType::Imm(_)));
+    }
+
+    let is_conditional = flow.is_conditional
+        || !matches!(
+            arch.cc(),
+            Arm64CC::ARM64_CC_AL | Arm64CC::ARM64_CC_NV | Arm64CC::ARM64_CC_INVALID
+        );
+    flow.finish(is_conditional)
+}
+
+/// Resolve the target of a direct ARM32 branch, or `None` for anything else.
+pub fn branch_target_arm(id: InsnId, arch: &ArmInsnDetail) -> Option<BranchTarget> {
+    use ArmInsn::*;
+
+    if !is_arm_insn(
+        id,
+        &[
+            ARM_INS_B,
+            ARM_INS_BL,
+            ARM_INS_BLX,
+            ARM_INS_CBZ,
+            ARM_INS_CBNZ,
+        ],
+    ) {
+        return None;
+    }
+
+    // Capstone already resolves the target to an absolute address. Indirect
+    // 'blx' has a register operand instead.
+    let address = arch.operands().last().and_then(|op| match op.op_type {
+        ArmOperandType::Imm(v) => Some(v as u32 as u64),
+        _ => None,
+    })?;
+
+    Some(BranchTarget {
+        address,
+        is_interworking: is_arm_insn(id, &[ARM_INS_BLX]),
+    })
+}
+
+/// Resolve the target of a direct ARM64 branch, or `None` for anything else.
+pub fn branch_target_arm64(id: InsnId, arch: &Arm64InsnDetail) -> Option<BranchTarget> {
+    use Arm64Insn::*;
+
+    if !is_arm64_insn(
+        id,
+        &[
+            ARM64_INS_B,
+            ARM64_INS_BL,
+            ARM64_INS_CBZ,
+            ARM64_INS_CBNZ,
+            ARM64_INS_TBZ,
+            ARM64_INS_TBNZ,
+        ],
+    ) {
+        return None;
+    }
+
+    let address = arch.operands().last().and_then(|op| match op.op_type {
+        Arm64OperandType::Imm(v) => Some(v as u64),
+        _ => None,
+    })?;
+
+    Some(BranchTarget {
+        address,
+        is_interworking: false,
+    })
+}
@@ -0,0 +1,318 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use std::collections::BTreeMap;
+
+use capstone::arch::arm::{ArmInsn, ArmInsnDetail, ArmOperandType, ArmReg};
+use capstone::arch::arm64::{Arm64Insn, Arm64InsnDetail, Arm64OperandType, Arm64Reg};
+use capstone::arch::DetailsArchInsn;
+use capstone::{Capstone, Insn, InsnIdInt, RegId, RegIdInt};
+
+use crate::analysis::decode::{self, Decoded};
+use crate::analysis::{jumptable, traversal, Image};
+use crate::capstone::mode::CapstoneMode;
+
+/// Get the alignment of instructions in the given mode.
+fn insn_alignment(mode: &CapstoneMode) -> u64 {
+    match mode {
+        CapstoneMode::Thumb => 2,
+        _ => 4,
+    }
+}
+
+/// A function recovered from an image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Function {
+    pub start: u64,
+    /// Address following the last instruction of the function.
+    pub end: u64,
+    pub instruction_count: usize,
+    /// The function is an entry point or the target of a direct call.
+    pub is_call_target: bool,
+    /// The function starts with a prologue.
+    pub has_prologue: bool,
+    /// A return instruction is reachable from the start of the function.
+    pub returns: bool,
+}
+
+fn is_reg(reg: RegId, id: u32) -> bool {
+    reg.0 == id as RegIdInt
+}
+
+/// Check for 'push {..., lr}', 'stmdb sp!, {..., lr}' and 'sub sp, sp, #imm'.
+fn is_prologue_arm(id: InsnIdInt, arch: &ArmInsnDetail) -> bool {
+    use ArmInsn::*;
+
+    let operands: Vec<ArmOperandType> = arch.operands().map(|op| op.op_type).collect();
+    let lists_lr = operands
+        .iter()
+        .any(|op| matches!(op, ArmOperandType::Reg(reg) if is_reg(*reg, ArmReg::ARM_REG_LR)));
+
+    if id == ARM_INS_PUSH as InsnIdInt {
+        lists_lr
+    } else if id == ARM_INS_STMDB as InsnIdInt {
+        let on_stack = matches!(operands.first(), Some(ArmOperandType::Reg(reg)) if is_reg(*reg, ArmReg::ARM_REG_SP));
+        on_stack && arch.writeback() && lists_lr
+    } else if id == ARM_INS_SUB as InsnIdInt {
+        matches!(
+            operands.as_slice(),
+            [ArmOperandType::Reg(dst), ArmOperandType::Reg(src), ArmOperandType::Imm(_)]
+                if is_reg(*dst, ArmReg::ARM_REG_SP) && is_reg(*src, ArmReg::ARM_REG_SP)
+        )
+    } else {
+        false
+    }
+}
+
+/// Check for 'stp x29, x30, [sp, #-imm]!', 'sub sp, sp, #imm' and 'paciasp'.
+fn is_prologue_arm64(id: InsnIdInt, arch: &Arm64InsnDetail) -> bool {
+    use Arm64Insn::*;
+
+    let operands: Vec<Arm64OperandType> = arch.operands().map(|op| op.op_type).collect();
+
+    if id == ARM64_INS_STP as InsnIdInt {
+        matches!(
+            operands.as_slice(),
+            [Arm64OperandType::Reg(fp), Arm64OperandType::Reg(lr), Arm64OperandType::Mem(mem)]
+                if is_reg(*fp, Arm64Reg::ARM64_REG_X29)
+                    && is_reg(*lr, Arm64Reg::ARM64_REG_X30)
+                    && is_reg(mem.base(), Arm64Reg::ARM64_REG_SP)
+                    && mem.disp() < 0
+        ) && arch.writeback()
+    } else if id == ARM64_INS_SUB as InsnIdInt {
+        matches!(
+            operands.as_slice(),
+            [Arm64OperandType::Reg(dst), Arm64OperandType::Reg(src), Arm64OperandType::Imm(_)]
+                if is_reg(*dst, Arm64Reg::ARM64_REG_SP) && is_reg(*src, Arm64Reg::ARM64_REG_SP)
+        )
+    } else {
+        id == ARM64_INS_PACIASP as InsnIdInt
+    }
+}
+
+/// Check whether an instruction matches a function prologue signature.
+fn is_prologue(capstone: &Capstone, mode: &CapstoneMode, insn: &Insn) -> bool {
+    let Ok(detail) = capstone.insn_detail(insn) else {
+        return false;
+    };
+    let arch_detail = detail.arch_detail();
+    let id = insn.id().0;
+
+    match mode {
+        CapstoneMode::ARM32 | CapstoneMode::Thumb => arch_detail
+            .arm()
+            .is_some_and(|arch| is_prologue_arm(id, arch)),
+        CapstoneMode::ARM64 => arch_detail
+            .arm64()
+            .is_some_and(|arch| is_prologue_arm64(id, arch)),
+        _ => false,
+    }
+}
+
+/// Check whether an instruction is padding between functions.
+fn is_padding(mode: &CapstoneMode, insn: &Insn) -> bool {
+    let id = insn.id().0;
+    match mode {
+        CapstoneMode::ARM32 | CapstoneMode::Thumb => id == ArmInsn::ARM_INS_NOP as InsnIdInt,
+        CapstoneMode::ARM64 => id == Arm64Insn::ARM64_INS_NOP as InsnIdInt,
+        _ => false,
+    }
+}
+
+/// Sweep the image for prologues that follow a function boundary. A prologue only
+/// starts a function if the preceding word is not code that falls through into it,
+/// so that e.g. the 'sub sp, sp' after a 'push' is not mistaken for a new function.
+fn find_prologues(capstone: &Capstone, mode: &CapstoneMode, image: &Image, end: u64) -> Vec<u64> {
+    let mut prologues = Vec::new();
+    let mut at_boundary = true;
+    let mut next = 0;
+
+    let alignment = insn_alignment(mode);
+    let Some(start) = image.address.checked_next_multiple_of(alignment) else {
+        return prologues;
+    };
+
+    for address in (start..end).step_by(alignment as usize) {
+        // Skip the remainder of wide instructions
+        if address < next {
+            continue;
+        }
+        let Some(code) = image.tail(address) else {
+            break;
+        };
+        let instructions = capstone.disasm_count(code, address, 1).ok();
+        let Some(insn) = instructions.as_ref().and_then(|insns| insns.first()) else {
+            // Undecodable words are data or padding
+            at_boundary = true;
+            continue;
+        };
+        next = address + insn.len() as u64;
+
+        if at_boundary && is_prologue(capstone, mode, insn) {
+            prologues.push(address);
+        }
+
+        at_boundary = is_padding(mode, insn)
+            || decode::classify(capstone, mode, image, insn)
+                .is_some_and(|decoded| decoded.flow.is_terminator);
+    }
+
+    prologues
+}
+
+/// Check whether the instruction at the given address matches a prologue signature.
+fn has_prologue(capstone: &Capstone, mode: &CapstoneMode, image: &Image, address: u64) -> bool {
+    let Some(code) = image.tail(address) else {
+        return false;
+    };
+    capstone
+        .disasm_count(code, address, 1)
+        .ok()
+        .and_then(|insns| insns.first().map(|insn| is_prologue(capstone, mode, insn)))
+        .unwrap_or(false)
+}
+
+/// Collect the targets of direct calls.
+fn call_targets<'a>(
+    decoded: &'a BTreeMap<u64, Decoded>,
+    image: &'a Image,
+) -> impl Iterator<Item = u64> + 'a {
+    decoded
+        .values()
+        .filter(|insn| insn.flow.is_call)
+        .filter_map(Decoded::followable_target)
+        .filter(|target| image.contains(*target))
+}
+
+/// Walk the body of the function starting at `start` without following calls. Control
+/// flow outside of `start..limit` belongs to other functions and is not followed.
+fn walk_body(
+    capstone: &Capstone,
+    mode: &CapstoneMode,
+    image: &Image,
+    start: u64,
+    limit: u64,
+) -> Function {
+    let mut function = Function {
+        start,
+        end: start,
+        instruction_count: 0,
+        is_call_target: false,
+        has_prologue: false,
+        returns: false,
+    };
+
+    let mut body: BTreeMap<u64, Decoded> = BTreeMap::new();
+    let mut worklist: Vec<u64> = vec![start];
+
+    while let Some(mut address) = worklist.pop() {
+        while (start..limit).contains(&address) && !body.contains_key(&address) {
+            let Some(mut insn) = decode::decode(capstone, mode, image, address) else {
+                break;
+            };
+            if let Some(table) = jumptable::resolve_at(capstone, mode, image, &body, &insn) {
+                insn.indirect_targets.extend(table.targets);
+            }
+
+            function.end = function.end.max(insn.next());
+            function.instruction_count += 1;
+            function.returns |= insn.flow.is_return;
+
+            if !insn.flow.is_call {
+                worklist.extend(insn.followable_target());
+            }
+            worklist.extend(insn.indirect_targets.iter().copied());
+
+            let is_terminator = insn.flow.is_terminator;
+            address = insn.next();
+            body.insert(insn.address, insn);
+
+            if is_terminator {
+                break;
+            }
+        }
+    }
+
+    function
+}
+
+/// Find the functions in an image. Function starts are the entry points, the targets
+/// of direct calls and prologues that follow a function boundary. Each function extends
+/// to the last instruction reachable from its start before the next function.
+pub fn find(
+    capstone: &Capstone,
+    mode: &CapstoneMode,
+    image: &Image,
+    entries: &[u64],
+) -> Result<Vec<Function>, &'static str> {
+    let image_end = image
+        .end()
+        .ok_or("image extends past the end of the address space")?;
+
+    // Function starts, mapped to whether they are entry points or call targets
+    let mut starts: BTreeMap<u64, bool> = BTreeMap::new();
+
+    let reached = traversal::traverse(capstone, mode, image, entries);
+    for address in entries.iter().copied().chain(call_targets(&reached, image)) {
+        starts.insert(address, true);
+    }
+
+    for address in find_prologues(capstone, mode, image, image_end) {
+        // A prologue in the middle of a known function is not a function start
+        if !reached.contains_key(&address) {
+            starts.entry(address).or_insert(false);
+        }
+    }
+
+    // Functions found by their prologue may call functions we have not seen yet
+    let roots: Vec<u64> = starts.keys().copied().collect();
+    let reached = traversal::traverse(capstone, mode, image, &roots);
+    for address in call_targets(&reached, image) {
+        starts.insert(address, true);
+    }
+
+    let limits = starts
+        .keys()
+        .skip(1)
+        .copied()
+        .chain(std::iter::once(image_end));
+
+    Ok(starts
+        .iter()
+        .zip(limits)
+        .map(|((start, is_call_target), limit)| Function {
+            is_call_target: *is_call_target,
+            has_prologue: has_prologue(capstone, mode, image, *start),
+            ..walk_body(capstone, mode, image, *start, limit)
+        })
+        .filter(|function| function.instruction_count > 0)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::testing::{disassemble, ARM64_CODE};
+
+    #[test]
+    fn rejects_wrapping_images() {
+        disassemble(
+            CapstoneMode::ARM64,
+            ARM64_CODE,
+            0x1000,
+            |cs, mode, _, image| {
+                let functions = find(cs, mode, image, &[0x1000]).unwrap();
+                assert_eq!(functions.len(), 1);
+                assert_eq!(functions[0].end, 0x1020);
+
+                let wrapping = Image {
+                    address: u64::MAX - 0xf,
+                    ..*image
+                };
+                assert!(find(cs, mode, &wrapping, &[]).is_err());
+            },
+        );
+    }
+}
@@ -0,0 +1,505 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use std::collections::BTreeMap;
+
+use capstone::arch::arm::{ArmCC, ArmInsn, ArmOperand, ArmOperandType, ArmReg, ArmShift};
+use capstone::arch::arm64::{
+    Arm64CC, Arm64Extender, Arm64Insn, Arm64Operand, Arm64OperandType, Arm64Shift,
+};
+use capstone::arch::DetailsArchInsn;
+use capstone::{Capstone, Insn, InsnIdInt, RegId, RegIdInt};
+
+use crate::analysis::decode::{self, Decoded};
+use crate::analysis::pcrel::{self, PcRelReference};
+use crate::analysis::Image;
+use crate::capstone::mode::CapstoneMode;
+
+/// Upper bound on the number of cases read from a single table.
+const MAX_CASES: u64 = 4096;
+
+/// Number of instructions preceding an indirect branch that are searched for the
+/// instructions setting up the table.
+const WINDOW: usize = 16;
+
+/// A jump table used by an indirect branch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JumpTable {
+    /// Address of the first table entry.
+    pub address: u64,
+    /// Case targets in table order.
+    pub targets: Vec<u64>,
+}
+
+/// Resolve the jump tables of all indirect branches. `references` and `decoded` hold the
+/// PC-relative references and classifications of `instructions`, and the result is
+/// index-aligned with all three.
+pub fn resolve(
+    mode: &CapstoneMode,
+    capstone: &Capstone,
+    instructions: &[&Insn],
+    references: &[Option<PcRelReference>],
+    decoded: &[Option<Decoded>],
+    image: &Image,
+) -> Vec<Option<JumpTable>> {
+    (0..instructions.len())
+        .map(|i| {
+            let flow = &decoded[i].as_ref()?.flow;
+            if !flow.is_branch || !flow.is_indirect || flow.is_return || flow.is_call {
+                return None;
+            }
+
+            let start = window_start(decoded, i);
+            let window = &instructions[start..=i];
+            match mode {
+                CapstoneMode::ARM32 => resolve_arm(capstone, window, image, false),
+                CapstoneMode::Thumb => resolve_arm(capstone, window, image, true),
+                CapstoneMode::ARM64 => {
+                    resolve_arm64(capstone, window, &references[start..=i], image)
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Resolve the jump table of an indirect branch found during traversal. `decoded` holds
+/// the instructions decoded so far, which provide the instructions leading up to it.
+pub fn resolve_at(
+    capstone: &Capstone,
+    mode: &CapstoneMode,
+    image: &Image,
+    decoded: &BTreeMap<u64, Decoded>,
+    branch: &Decoded,
+) -> Option<JumpTable> {
+    let flow = &branch.flow;
+    if !flow.is_branch || !flow.is_indirect || flow.is_return || flow.is_call {
+        return None;
+    }
+
+    // Walk back over the straight-line code falling through into the branch
+    let mut start = branch.address;
+    for _ in 0..WINDOW {
+        let previous = decoded.range(..start).next_back().map(|(_, insn)| insn);
+        match previous {
+            Some(previous) if previous.next() == start && !previous.flow.is_terminator => {
+                start = previous.address;
+            }
+            _ => break,
+        }
+    }
+
+    let code = image.slice(start, (branch.next() - start) as usize)?;
+    let instructions = capstone.disasm_all(code, start).ok()?;
+    let instructions: Vec<&Insn> = (*instructions).iter().collect();
+    if instructions.last()?.address() != branch.address {
+        return None;
+    }
+
+    let references = pcrel::resolve(mode, capstone, &instructions, image)?;
+    let decoded = decode::classify_all(capstone, mode, image, &instructions);
+    resolve(mode, capstone, &instructions, &references, &decoded, image).pop()?
+}
+
+/// Find the start of the straight-line code leading up to the instruction at `end`.
+fn window_start(decoded: &[Option<Decoded>], end: usize) -> usize {
+    let mut start = end;
+    while start > 0 && end - start < WINDOW {
+        match (&decoded[start - 1], &decoded[start]) {
+            (Some(previous), Some(current))
+                if previous.next() == current.address && !previous.flow.is_terminator =>
+            {
+                start -= 1
+            }
+            _ => break,
+        }
+    }
+    start
+}
+
+fn sign_extend(value: u64, bits: u32) -> u64 {
+    let shift = 64 - bits;
+    (((value << shift) as i64) >> shift) as u64
+}
+
+/// Read up to `count` entries from a table, stopping at the end of the image.
+fn read_entries(image: &Image, table: u64, size: usize, count: u64) -> Vec<u64> {
+    (0..count.min(MAX_CASES))
+        .map_while(|i| image.read(table.wrapping_add(i * size as u64), size))
+        .collect()
+}
+
+fn arm_operands(capstone: &Capstone, insn: &Insn) -> Option<(ArmCC, Vec<ArmOperand>)> {
+    let detail = capstone.insn_detail(insn).ok()?;
+    let arch_detail = detail.arch_detail();
+    let arch = arch_detail.arm()?;
+    Some((arch.cc(), arch.operands().collect()))
+}
+
+/// ARM32 instructions whose first register operand is not written.
+const ARM_NON_DEFINING: &[ArmInsn] = &[
+    ArmInsn::ARM_INS_CMP,
+    ArmInsn::ARM_INS_CMN,
+    ArmInsn::ARM_INS_TST,
+    ArmInsn::ARM_INS_TEQ,
+    ArmInsn::ARM_INS_STR,
+    ArmInsn::ARM_INS_STRB,
+    ArmInsn::ARM_INS_STRH,
+    ArmInsn::ARM_INS_STRD,
+    ArmInsn::ARM_INS_STM,
+    ArmInsn::ARM_INS_STMDB,
+    ArmInsn::ARM_INS_PUSH,
+    ArmInsn::ARM_INS_B,
+    ArmInsn::ARM_INS_BX,
+    ArmInsn::ARM_INS_BLX,
+    ArmInsn::ARM_INS_CBZ,
+    ArmInsn::ARM_INS_CBNZ,
+];
+
+/// Search backwards for the 'cmp rN, #imm' bounding the table index.
+fn arm_bound(capstone: &Capstone, preceding: &[&Insn], index: RegId) -> Option<u64> {
+    let mut index = index;
+    for insn in preceding.iter().rev() {
+        let (_, operands) = arm_operands(capstone, insn)?;
+        let id = insn.id().0;
+
+        match operands.as_slice() {
+            [first, second] if id == ArmInsn::ARM_INS_CMP as InsnIdInt => {
+                if let (ArmOperandType::Reg(reg), ArmOperandType::Imm(imm)) =
+                    (&first.op_type, &second.op_type)
+                {
+                    if *reg == index {
+                        return Some(*imm as u64);
+                    }
+                }
+            }
+            [first, ..] if matches!(first.op_type, ArmOperandType::Reg(reg) if reg == index) => {
+                if ARM_NON_DEFINING.iter().any(|i| id == *i as InsnIdInt) {
+                    continue;
+                }
+                // Follow register copies, anything else clobbers the index
+                match operands.get(1).map(|op| &op.op_type) {
+                    Some(ArmOperandType::Reg(src))
+                        if operands.len() == 2 && id == ArmInsn::ARM_INS_MOV as InsnIdInt =>
+                    {
+                        index = *src
+                    }
+                    _ => return None,
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Recognize 'ldr pc, [pc, rN, lsl #2]', 'tbb [pc, rN]' and 'tbh [pc, rN, lsl #1]'.
+fn resolve_arm(
+    capstone: &Capstone,
+    window: &[&Insn],
+    image: &Image,
+    thumb: bool,
+) -> Option<JumpTable> {
+    use ArmInsn::*;
+
+    let (branch, preceding) = window.split_last()?;
+    let (cc, operands) = arm_operands(capstone, branch)?;
+    let id = branch.id().0;
+
+    let (op, mem) = operands.iter().find_map(|op| match op.op_type {
+        ArmOperandType::Mem(ref mem) => Some((op, *mem)),
+        _ => None,
+    })?;
+    if mem.base().0 != ArmReg::ARM_REG_PC as RegIdInt || mem.index().0 == 0 {
+        return None;
+    }
+
+    let bound = arm_bound(capstone, preceding, mem.index())?;
+    // 'ldrlo' only branches for indices below the bound, everything else below or equal
+    let count = if cc == ArmCC::ARM_CC_LO {
+        bound
+    } else {
+        bound.checked_add(1)?
+    };
+
+    if id == ARM_INS_LDR as InsnIdInt {
+        if !matches!(op.shift, ArmShift::Lsl(2)) {
+            return None;
+        }
+        // Absolute addresses at the PC, we can only follow targets in the current state
+        let table = if thumb {
+            (branch.address() + 4) & !3
+        } else {
+            branch.address() + 8
+        };
+        let targets = read_entries(image, table, 4, count)
+            .into_iter()
+            .filter(|target| (target & 1 == 1) == thumb)
+            .map(|target| target & !1)
+            .collect();
+        Some(JumpTable {
+            address: table,
+            targets,
+        })
+    } else if id == ARM_INS_TBB as InsnIdInt || id == ARM_INS_TBH as InsnIdInt {
+        // Halfword offsets from the Thumb PC, which is also where the table starts
+        let table = branch.address() + 4;
+        let size = if id == ARM_INS_TBB as InsnIdInt { 1 } else { 2 };
+        let targets = read_entries(image, table, size, count)
+            .into_iter()
+            .map(|entry| table + 2 * entry)
+            .collect();
+        Some(JumpTable {
+            address: table,
+            targets,
+        })
+    } else {
+        None
+    }
+}
+
+fn arm64_operands(capstone: &Capstone, insn: &Insn) -> Option<Vec<Arm64Operand>> {
+    let detail = capstone.insn_detail(insn).ok()?;
+    let arch_detail = detail.arch_detail();
+    let arch = arch_detail.arm64()?;
+    Some(arch.operands().collect())
+}
+
+/// Get the number of a general purpose register, so that 'wN' and 'xN' compare equal.
+fn arm64_reg_number(capstone: &Capstone, reg: RegId) -> Option<u32> {
+    let name = capstone.reg_name(reg)?;
+    match name.as_str() {
+        "fp" => Some(29),
+        "lr" => Some(30),
+        _ => name
+            .strip_prefix('x')
+            .or_else(|| name.strip_prefix('w'))?
+            .parse()
+            .ok(),
+    }
+}
+
+/// ARM64 instructions whose first register operand is not written.
+const ARM64_NON_DEFINING: &[Arm64Insn] = &[
+    Arm64Insn::ARM64_INS_CMP,
+    Arm64Insn::ARM64_INS_CMN,
+    Arm64Insn::ARM64_INS_TST,
+    Arm64Insn::ARM64_INS_CCMP,
+    Arm64Insn::ARM64_INS_CCMN,
+    Arm64Insn::ARM64_INS_STR,
+    Arm64Insn::ARM64_INS_STRB,
+    Arm64Insn::ARM64_INS_STRH,
+    Arm64Insn::ARM64_INS_STUR,
+    Arm64Insn::ARM64_INS_STURB,
+    Arm64Insn::ARM64_INS_STURH,
+    Arm64Insn::ARM64_INS_STP,
+    Arm64Insn::ARM64_INS_CBZ,
+    Arm64Insn::ARM64_INS_CBNZ,
+    Arm64Insn::ARM64_INS_TBZ,
+    Arm64Insn::ARM64_INS_TBNZ,
+    Arm64Insn::ARM64_INS_BR,
+    Arm64Insn::ARM64_INS_BLR,
+];
+
+/// Check whether an instruction writes the given register number.
+fn arm64_defines(capstone: &Capstone, insn: &Insn, operands: &[Arm64Operand], reg: u32) -> bool {
+    let id = insn.id().0;
+    if ARM64_NON_DEFINING.iter().any(|i| id == *i as InsnIdInt) {
+        return false;
+    }
+
+    let is = |op: Option<&Arm64Operand>| matches!(op.map(|op| &op.op_type), Some(Arm64OperandType::Reg(r)) if arm64_reg_number(capstone, *r) == Some(reg));
+    is(operands.first()) || (id == Arm64Insn::ARM64_INS_LDP as InsnIdInt && is(operands.get(1)))
+}
+
+/// Find the last instruction before `before` that writes the given register number.
+fn arm64_last_def(
+    capstone: &Capstone,
+    window: &[&Insn],
+    before: usize,
+    reg: u32,
+) -> Option<(usize, Vec<Arm64Operand>)> {
+    (0..before).rev().find_map(|i| {
+        let operands = arm64_operands(capstone, window[i])?;
+        arm64_defines(capstone, window[i], &operands, reg).then_some((i, operands))
+    })
+}
+
+/// Get the address held by a register, if it was set by 'adr', 'adrp' or 'add'.
+fn arm64_address(
+    capstone: &Capstone,
+    window: &[&Insn],
+    references: &[Option<PcRelReference>],
+    before: usize,
+    reg: u32,
+) -> Option<u64> {
+    use Arm64Insn::*;
+
+    let (i, _) = arm64_last_def(capstone, window, before, reg)?;
+    let id = window[i].id().0;
+    let produces_address = id == ARM64_INS_ADR as InsnIdInt
+        || id == ARM64_INS_ADRP as InsnIdInt
+        || id == ARM64_INS_ADD as InsnIdInt;
+    produces_address
+        .then(|| references[i].map(|reference| reference.address))
+        .flatten()
+}
+
+/// Search backwards for the 'cmp wN, #imm' bounding the table index and return the
+/// number of table entries.
+fn arm64_count(capstone: &Capstone, window: &[&Insn], before: usize, index: u32) -> Option<u64> {
+    let mut index = index;
+    // Condition of the first conditional branch after the 'cmp'
+    let mut cc = Arm64CC::ARM64_CC_INVALID;
+    for i in (0..before).rev() {
+        let insn = window[i];
+        let operands = arm64_operands(capstone, insn)?;
+        let id = insn.id().0;
+
+        if id == Arm64Insn::ARM64_INS_CMP as InsnIdInt {
+            if let [first, second] = operands.as_slice() {
+                if let (Arm64OperandType::Reg(reg), Arm64OperandType::Imm(imm)) =
+                    (&first.op_type, &second.op_type)
+                {
+                    if arm64_reg_number(capstone, *reg) == Some(index) {
+                        let bound = *imm as u64;
+                        // 'b.hs' leaves the table for indices equal to the bound, 'b.hi' only
+                        // for larger ones
+                        return if cc == Arm64CC::ARM64_CC_HS {
+                            Some(bound)
+                        } else {
+                            bound.checked_add(1)
+                        };
+                    }
+                }
+            }
+        } else if id == Arm64Insn::ARM64_INS_B as InsnIdInt {
+            let detail = capstone.insn_detail(insn).ok()?;
+            let arch_detail = detail.arch_detail();
+            let branch_cc = arch_detail.arm64()?.cc();
+            if branch_cc != Arm64CC::ARM64_CC_INVALID && branch_cc != Arm64CC::ARM64_CC_AL {
+                cc = branch_cc;
+            }
+        } else if arm64_defines(capstone, insn, &operands, index) {
+            // Follow register copies, anything else clobbers the index
+            match operands.as_slice() {
+                [_, src] if id == Arm64Insn::ARM64_INS_MOV as InsnIdInt => match src.op_type {
+                    Arm64OperandType::Reg(src) => index = arm64_reg_number(capstone, src)?,
+                    _ => return None,
+                },
+                _ => return None,
+            }
+        }
+    }
+    None
+}
+
+/// Recognize the table dispatch emitted by compilers for AArch64:
+///
+/// ```text
+/// cmp   w0, #N
+/// b.hi  default
+/// adrp  x1, table / add x1, x1, :lo12:table
+/// ldrb  w2, [x1, w0, uxtw]
+/// adr   x3, base
+/// add   x2, x3, w2, sxtb #2
+/// br    x2
+/// ```
+///
+/// Entries may be bytes, halfwords or words, and the base may be the table itself.
+fn resolve_arm64(
+    capstone: &Capstone,
+    window: &[&Insn],
+    references: &[Option<PcRelReference>],
+    image: &Image,
+) -> Option<JumpTable> {
+    use Arm64Insn::*;
+
+    let branch = window.len() - 1;
+    if window[branch].id().0 != ARM64_INS_BR as InsnIdInt {
+        return None;
+    }
+    let target = match arm64_operands(capstone, window[branch])?.first()?.op_type {
+        Arm64OperandType::Reg(reg) => arm64_reg_number(capstone, reg)?,
+        _ => return None,
+    };
+
+    // add xT, xBase, xEntry{, extend/lsl #shift}
+    let (add, operands) = arm64_last_def(capstone, window, branch, target)?;
+    if window[add].id().0 != ARM64_INS_ADD as InsnIdInt {
+        return None;
+    }
+    let (base, entry, entry_op) = match operands.as_slice() {
+        [_, base_op, entry_op] => match (&base_op.op_type, &entry_op.op_type) {
+            (Arm64OperandType::Reg(base), Arm64OperandType::Reg(entry)) => (
+                arm64_reg_number(capstone, *base)?,
+                arm64_reg_number(capstone, *entry)?,
+                entry_op,
+            ),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let shift = match entry_op.shift {
+        Arm64Shift::Lsl(shift) => shift,
+        Arm64Shift::Invalid => 0,
+        _ => return None,
+    };
+    let extend_bits = match entry_op.ext {
+        Arm64Extender::ARM64_EXT_SXTB => Some(8),
+        Arm64Extender::ARM64_EXT_SXTH => Some(16),
+        Arm64Extender::ARM64_EXT_SXTW => Some(32),
+        _ => None,
+    };
+    let base = arm64_address(capstone, window, references, add, base)?;
+
+    // ldr{b,h,sb,sh,sw} wEntry, [xTable, wIndex{, extend #scale}]
+    let (load, operands) = arm64_last_def(capstone, window, add, entry)?;
+    let load_id = window[load].id().0;
+    let (size, signed) = if load_id == ARM64_INS_LDRB as InsnIdInt {
+        (1, false)
+    } else if load_id == ARM64_INS_LDRSB as InsnIdInt {
+        (1, true)
+    } else if load_id == ARM64_INS_LDRH as InsnIdInt {
+        (2, false)
+    } else if load_id == ARM64_INS_LDRSH as InsnIdInt {
+        (2, true)
+    } else if load_id == ARM64_INS_LDRSW as InsnIdInt {
+        (4, true)
+    } else if load_id == ARM64_INS_LDR as InsnIdInt {
+        (4, false)
+    } else {
+        return None;
+    };
+    let mem = operands.iter().find_map(|op| match op.op_type {
+        Arm64OperandType::Mem(mem) => Some(mem),
+        _ => None,
+    })?;
+    if mem.index().0 == 0 {
+        return None;
+    }
+    let table = arm64_reg_number(capstone, mem.base())?;
+    let table = arm64_address(capstone, window, references, load, table)?;
+    let index = arm64_reg_number(capstone, mem.index())?;
+
+    let count = arm64_count(capstone, window, load, index)?;
+    let targets = read_entries(image, table, size, count)
+        .into_iter()
+        .map(|entry| {
+            let entry = if signed {
+                sign_extend(entry, size as u32 * 8)
+            } else {
+                entry
+            };
+            let entry = extend_bits.map_or(entry, |bits| sign_extend(entry, bits));
+            base.wrapping_add(entry << shift)
+        })
+        .collect();
+
+    Some(JumpTable {
+        address: table,
+        targets,
+    })
+}
@@ -0,0 +1,115 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+pub mod cfg;
+pub mod decode;
+pub mod flags;
+pub mod flow;
+pub mod functions;
+pub mod jumptable;
+pub mod pcrel;
+pub mod registers;
+pub mod symbolize;
+pub mod traversal;
+pub mod xref;
+
+/// A disassembled buffer.
+pub struct Image<'a> {
+    pub code: &'a [u8],
+    pub address: u64,
+}
+
+impl<'a> Image<'a> {
+    /// Read a little-endian value of the given size from the image.
+    pub fn read(&self, address: u64, size: usize) -> Option<u64> {
+        let bytes = self.slice(address, size)?;
+        Some(
+            bytes
+                .iter()
+                .rev()
+                .fold(0u64, |value, b| (value << 8) | *b as u64),
+        )
+    }
+
+    /// Get the bytes starting at the given address up to the end of the image.
+    pub fn tail(&self, address: u64) -> Option<&'a [u8]> {
+        let offset = address.checked_sub(self.address)?;
+        self.code.get(usize::try_from(offset).ok()?..)
+    }
+
+    /// Get `size` bytes starting at the given address.
+    pub fn slice(&self, address: u64, size: usize) -> Option<&'a [u8]> {
+        self.tail(address)?.get(..size)
+    }
+
+    /// Get the address following the last byte of the image, or `None` if the image
+    /// extends past the end of the address space.
+    pub fn end(&self) -> Option<u64> {
+        self.address.checked_add(self.code.len() as u64)
+    }
+
+    /// Check whether the given address lies within the image.
+    pub fn contains(&self, address: u64) -> bool {
+        self.tail(address).is_some_and(|tail| !tail.is_empty())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod testing {
+    use capstone::{Capstone, Insn};
+
+    use crate::analysis::Image;
+    use crate::capstone::create;
+    use crate::capstone::mode::CapstoneMode;
+
+    /// A small ARM64 function at 0x1000: a prologue, `adrp`/`add` of 0x1790, a call to
+    /// 0xedc, `mov w0, #0` and an epilogue ending in `ret`.
+    pub const ARM64_CODE: &[u8] = &[
+        0xfd, 0x7b, 0xbf, 0xa9, 0xfd, 0x03, 0x00, 0x91, 0x00, 0x00, 0x00, 0x90, 0x00, 0x40, 0x1e,
+        0x91, 0xb3, 0xff, 0xff, 0x97, 0x00, 0x00, 0x80, 0x52, 0xfd, 0x7b, 0xc1, 0xa8, 0xc0, 0x03,
+        0x5f, 0xd6,
+    ];
+
+    /// Disassemble `code` at `address` and pass the instructions to `f`.
+    pub fn disassemble<T>(
+        mode: CapstoneMode,
+        code: &[u8],
+        address: u64,
+        f: impl FnOnce(&Capstone, &CapstoneMode, &[&Insn], &Image) -> T,
+    ) -> T {
+        let capstone = create(&mode).unwrap();
+        let instructions = capstone.disasm_all(code, address).unwrap();
+        let instructions: Vec<&Insn> = (*instructions).iter().collect();
+        let image = Image { code, address };
+        f(&capstone, &mode, &instructions, &image)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_bounds() {
+        let image = Image {
+            code: &[0x01, 0x02, 0x03, 0x04],
+            address: 0x100,
+        };
+        assert_eq!(image.read(0x100, 4), Some(0x04030201));
+        assert_eq!(image.read(0x102, 2), Some(0x0403));
+        assert_eq!(image.read(0x102, 4), None);
+        assert_eq!(image.tail(0x103), Some(&[0x04][..]));
+        assert!(image.contains(0x103));
+        assert!(!image.contains(0x104));
+        assert!(!image.contains(0xff));
+        assert_eq!(image.end(), Some(0x104));
+
+        let wrapping = Image {
+            code: &[0x01, 0x02],
+            address: u64::MAX,
+        };
+        assert_eq!(wrapping.end(), None);
+    }
+}
@@ -0,0 +1,355 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use std::collections::HashMap;
+
+use capstone::arch::arm::{ArmInsn, ArmOperand, ArmOperandType, ArmReg};
+use capstone::arch::arm64::{Arm64Insn, Arm64Operand, Arm64OperandType, Arm64Shift};
+use capstone::arch::DetailsArchInsn;
+use capstone::{Capstone, Insn, InsnIdInt, RegId, RegIdInt};
+
+use crate::analysis::{flow, registers, Image};
+use crate::capstone::mode::CapstoneMode;
+
+/// An address materialized relative to the PC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PcRelReference {
+    /// The absolute address that is referenced.
+    pub address: u64,
+    /// The value loaded from the address, if the instruction is a load and the
+    /// address lies within the disassembled buffer.
+    pub value: Option<u64>,
+}
+
+impl PcRelReference {
+    fn address(address: u64) -> PcRelReference {
+        PcRelReference {
+            address,
+            value: None,
+        }
+    }
+}
+
+/// Resolve PC-relative references for every instruction. The result is index-aligned with
+/// `instructions`. Returns `None` if the mode has no PC-relative analysis.
+pub fn resolve(
+    mode: &CapstoneMode,
+    capstone: &Capstone,
+    instructions: &[&Insn],
+    image: &Image,
+) -> Option<Vec<Option<PcRelReference>>> {
+    match mode {
+        CapstoneMode::ARM32 => Some(resolve_arm(capstone, instructions, image, false)),
+        CapstoneMode::Thumb => Some(resolve_arm(capstone, instructions, image, true)),
+        CapstoneMode::ARM64 => Some(resolve_arm64(capstone, instructions, image)),
+        _ => None,
+    }
+}
+
+fn is_reg(reg: RegId, id: u32) -> bool {
+    reg.0 == id as RegIdInt
+}
+
+/// Get the size of a load from the ARM32 instruction id.
+fn arm_load_size(id: InsnIdInt) -> Option<usize> {
+    use ArmInsn::*;
+
+    let is = |insn: ArmInsn| id == insn as InsnIdInt;
+
+    if is(ARM_INS_LDR) {
+        Some(4)
+    } else if is(ARM_INS_LDRH) || is(ARM_INS_LDRSH) {
+        Some(2)
+    } else if is(ARM_INS_LDRB) || is(ARM_INS_LDRSB) {
+        Some(1)
+    } else if is(ARM_INS_LDRD) {
+        Some(8)
+    } else {
+        None
+    }
+}
+
+fn resolve_arm(
+    capstone: &Capstone,
+    instructions: &[&Insn],
+    image: &Image,
+    thumb: bool,
+) -> Vec<Option<PcRelReference>> {
+    instructions
+        .iter()
+        .map(|insn| {
+            let detail = capstone.insn_detail(insn).ok()?;
+            let arch_detail = detail.arch_detail();
+            let arch = arch_detail.arm()?;
+            let operands: Vec<ArmOperand> = arch.operands().collect();
+
+            // The PC reads as the address of the current instruction plus 8 in ARM state
+            // and plus 4 in Thumb state
+            let pc = insn.address() + if thumb { 4 } else { 8 };
+            let id = insn.id().0;
+
+            if id == ArmInsn::ARM_INS_ADR as InsnIdInt {
+                let imm = operands.iter().find_map(|op| match op.op_type {
+                    ArmOperandType::Imm(v) => Some(v),
+                    _ => None,
+                })?;
+                return Some(PcRelReference::address(
+                    (pc & !3).wrapping_add(imm as i64 as u64) & 0xffff_ffff,
+                ));
+            }
+
+            if id == ArmInsn::ARM_INS_ADD as InsnIdInt || id == ArmInsn::ARM_INS_SUB as InsnIdInt {
+                // 'add rD, pc, #imm' is the ARM encoding of 'adr'
+                return match operands.as_slice() {
+                    [_, src, imm] => match (&src.op_type, &imm.op_type) {
+                        (ArmOperandType::Reg(src), ArmOperandType::Imm(imm))
+                            if is_reg(*src, ArmReg::ARM_REG_PC) =>
+                        {
+                            let imm = if id == ArmInsn::ARM_INS_SUB as InsnIdInt {
+                                -(*imm as i64)
+                            } else {
+                                *imm as i64
+                            };
+                            Some(PcRelReference::address(
+                                (pc & !3).wrapping_add(imm as u64) & 0xffff_ffff,
+                            ))
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                };
+            }
+
+            let size = arm_load_size(id)?;
+            let (op, mem) = operands.iter().find_map(|op| match op.op_type {
+                ArmOperandType::Mem(ref mem) => Some((op, *mem)),
+                _ => None,
+            })?;
+            if !is_reg(mem.base(), ArmReg::ARM_REG_PC) || mem.index().0 != 0 {
+                return None;
+            }
+
+            let disp = if op.subtracted && mem.disp() > 0 {
+                -(mem.disp() as i64)
+            } else {
+                mem.disp() as i64
+            };
+            let address = (pc & !3).wrapping_add(disp as u64) & 0xffff_ffff;
+            Some(PcRelReference {
+                address,
+                value: image.read(address, size),
+            })
+        })
+        .collect()
+}
+
+/// Get the size of a load from an ARM64 instruction and its destination register.
+fn arm64_load_size(id: InsnIdInt, dst: RegId) -> Option<usize> {
+    use Arm64Insn::*;
+
+    let is = |insn: Arm64Insn| id == insn as InsnIdInt;
+
+    if is(ARM64_INS_LDRSW) {
+        Some(4)
+    } else if is(ARM64_INS_LDRH) || is(ARM64_INS_LDRSH) {
+        Some(2)
+    } else if is(ARM64_INS_LDRB) || is(ARM64_INS_LDRSB) {
+        Some(1)
+    } else if is(ARM64_INS_LDR) {
+        // The access size depends on the width of the destination register
+        let size = registers::info(&CapstoneMode::ARM64, dst)?.size as usize / 8;
+        (size > 0).then_some(size)
+    } else {
+        None
+    }
+}
+
+/// Forget the addresses held by any register that overlaps `reg`, so that writing
+/// `w8` also clobbers an address in `x8`.
+fn forget(known: &mut HashMap<RegIdInt, u64>, reg: RegId) {
+    known.retain(|id, _| !registers::overlaps(&CapstoneMode::ARM64, RegId(*id), reg));
+}
+
+fn resolve_arm64(
+    capstone: &Capstone,
+    instructions: &[&Insn],
+    image: &Image,
+) -> Vec<Option<PcRelReference>> {
+    use Arm64Insn::*;
+
+    // Registers currently known to hold an absolute address
+    let mut known: HashMap<RegIdInt, u64> = HashMap::new();
+
+    instructions
+        .iter()
+        .map(|insn| {
+            let detail = capstone.insn_detail(insn).ok()?;
+            let arch_detail = detail.arch_detail();
+            let arch = arch_detail.arm64()?;
+            let operands: Vec<Arm64Operand> = arch.operands().collect();
+            let id = insn.id().0;
+
+            let dst = operands.first().and_then(|op| match op.op_type {
+                Arm64OperandType::Reg(reg) => Some(reg),
+                _ => None,
+            });
+
+            let reference = if id == ARM64_INS_ADRP as InsnIdInt || id == ARM64_INS_ADR as InsnIdInt
+            {
+                // Capstone already resolves the page or address
+                match operands.get(1).map(|op| &op.op_type) {
+                    Some(Arm64OperandType::Imm(v)) => Some(PcRelReference::address(*v as u64)),
+                    _ => None,
+                }
+            } else if id == ARM64_INS_ADD as InsnIdInt {
+                match operands.as_slice() {
+                    [_, src, imm] => match (&src.op_type, &imm.op_type) {
+                        (Arm64OperandType::Reg(src), Arm64OperandType::Imm(v)) => {
+                            let offset = match imm.shift {
+                                Arm64Shift::Lsl(shift) => (*v as u64) << shift,
+                                _ => *v as u64,
+                            };
+                            known
+                                .get(&src.0)
+                                .map(|base| PcRelReference::address(base.wrapping_add(offset)))
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                }
+            } else {
+                let mem = operands.iter().find_map(|op| match op.op_type {
+                    Arm64OperandType::Mem(ref mem) => Some(*mem),
+                    _ => None,
+                });
+                let literal = operands.get(1).and_then(|op| match op.op_type {
+                    Arm64OperandType::Imm(v) => Some(v as u64),
+                    _ => None,
+                });
+
+                let address = match mem {
+                    Some(mem) if mem.index().0 == 0 => known
+                        .get(&mem.base().0)
+                        .map(|base| base.wrapping_add(mem.disp() as i64 as u64)),
+                    Some(_) => None,
+                    None if id == ARM64_INS_LDR as InsnIdInt
+                        || id == ARM64_INS_LDRSW as InsnIdInt
+                        || id == ARM64_INS_PRFM as InsnIdInt =>
+                    {
+                        literal
+                    }
+                    None => None,
+                };
+
+                address.map(|address| {
+                    let size = dst.and_then(|dst| arm64_load_size(id, dst));
+                    PcRelReference {
+                        address,
+                        value: size
+                            .filter(|size| *size <= 8)
+                            .and_then(|size| image.read(address, size)),
+                    }
+                })
+            };
+
+            // Track the registers that now hold an address. Anything else written
+            // to a register invalidates what we know about it.
+            let flow = flow::classify_arm64(insn.id(), detail.groups(), arch);
+            if flow.is_call || flow.is_terminator {
+                known.clear();
+            } else {
+                for reg in detail.regs_write() {
+                    forget(&mut known, *reg);
+                }
+                if let Some(dst) = dst {
+                    forget(&mut known, dst);
+                    let produces_address = id == ARM64_INS_ADRP as InsnIdInt
+                        || id == ARM64_INS_ADR as InsnIdInt
+                        || id == ARM64_INS_ADD as InsnIdInt;
+                    if let (true, Some(reference)) = (produces_address, reference) {
+                        known.insert(dst.0, reference.address);
+                    }
+                }
+                if id == ARM64_INS_LDP as InsnIdInt {
+                    if let Some(Arm64OperandType::Reg(reg)) = operands.get(1).map(|op| &op.op_type)
+                    {
+                        forget(&mut known, *reg);
+                    }
+                }
+            }
+
+            reference
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::testing::{disassemble, ARM64_CODE};
+
+    #[test]
+    fn arm64_page_references() {
+        disassemble(
+            CapstoneMode::ARM64,
+            ARM64_CODE,
+            0x1000,
+            |cs, mode, insns, image| {
+                let references = resolve(mode, cs, insns, image).unwrap();
+                assert_eq!(references[2].map(|r| r.address), Some(0x1000));
+                assert_eq!(references[3].map(|r| r.address), Some(0x1790));
+                assert!(references[0].is_none());
+            },
+        );
+    }
+
+    #[test]
+    fn arm64_partial_write_clobbers_page() {
+        // 'adrp x8, 0x1000', 'mov w8, #1', 'add x0, x8, #0x10'
+        let code = [
+            0x08, 0x00, 0x00, 0x90, 0x28, 0x00, 0x80, 0x52, 0x00, 0x41, 0x00, 0x91,
+        ];
+        disassemble(
+            CapstoneMode::ARM64,
+            &code,
+            0x1000,
+            |cs, mode, insns, image| {
+                let references = resolve(mode, cs, insns, image).unwrap();
+                assert_eq!(references[0].map(|r| r.address), Some(0x1000));
+                assert!(references[2].is_none());
+            },
+        );
+    }
+
+    #[test]
+    fn arm32_literal_value() {
+        // 'ldr r0, [pc, #0]' followed by the literal pool two words later
+        let code = [
+            0x00, 0x00, 0x9f, 0xe5, 0x1e, 0xff, 0x2f, 0xe1, 0x78, 0x56, 0x34, 0x12,
+        ];
+        disassemble(
+            CapstoneMode::ARM32,
+            &code,
+            0x8000,
+            |cs, mode, insns, image| {
+                let reference = resolve(mode, cs, insns, image).unwrap()[0].unwrap();
+                assert_eq!(reference.address, 0x8008);
+                assert_eq!(reference.value, Some(0x12345678));
+            },
+        );
+    }
+
+    #[test]
+    fn x86_is_not_resolved() {
+        disassemble(
+            CapstoneMode::X86_64,
+            &[0xc3],
+            0,
+            |cs, mode, insns, image| {
+                assert!(resolve(mode, cs, insns, image).is_none());
+            },
+        );
+    }
+}
@@ -0,0 +1,289 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use capstone::arch::arm::ArmReg;
+use capstone::arch::arm64::Arm64Reg;
+use capstone::{RegId, RegIdInt};
+
+use crate::capstone::mode::CapstoneMode;
+
+/// The kind of value a register holds. The discriminants are the `CLASS_*` codes of the
+/// Java `RegisterInfo`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegClass {
+    General = 1,
+    StackPointer = 2,
+    ProgramCounter = 3,
+    /// A register that always reads as zero, like `xzr`.
+    Zero = 4,
+    /// A status register holding condition flags.
+    Flags = 5,
+    /// A system or floating-point control register.
+    System = 6,
+    /// A floating-point or Advanced SIMD register.
+    Simd = 7,
+    /// An SVE vector register.
+    ScalableVector = 8,
+    /// An SVE predicate register.
+    Predicate = 9,
+}
+
+/// Metadata of a single register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegisterInfo {
+    pub id: RegIdInt,
+    /// The size in bits. SVE registers are 0 since their size depends on the CPU.
+    pub size: u16,
+    pub class: RegClass,
+    /// The smallest other register that contains this one, e.g. `x0` for `w0`.
+    pub parent: Option<RegIdInt>,
+    /// The register holds condition flags.
+    pub is_flags: bool,
+}
+
+impl RegisterInfo {
+    fn new(id: u32, size: u16, class: RegClass, parent: Option<u32>) -> RegisterInfo {
+        RegisterInfo {
+            id: id as RegIdInt,
+            size,
+            class,
+            parent: parent.map(|parent| parent as RegIdInt),
+            is_flags: class == RegClass::Flags,
+        }
+    }
+
+    /// Check whether the register is a floating-point, SIMD or SVE register.
+    pub fn is_simd(&self) -> bool {
+        matches!(
+            self.class,
+            RegClass::Simd | RegClass::ScalableVector | RegClass::Predicate
+        )
+    }
+}
+
+/// Get the number of register ids of a mode, or 0 if register metadata is not supported.
+pub fn count(mode: &CapstoneMode) -> u32 {
+    match mode {
+        CapstoneMode::ARM32 | CapstoneMode::Thumb => ArmReg::ARM_REG_ENDING,
+        CapstoneMode::ARM64 => Arm64Reg::ARM64_REG_ENDING,
+        _ => 0,
+    }
+}
+
+/// Get the metadata of a register, or `None` if the id is invalid.
+pub fn info(mode: &CapstoneMode, reg: RegId) -> Option<RegisterInfo> {
+    match mode {
+        CapstoneMode::ARM32 | CapstoneMode::Thumb => arm32(reg.0 as u32),
+        CapstoneMode::ARM64 => arm64(reg.0 as u32),
+        _ => None,
+    }
+}
+
+/// Get the widest register that contains the given one, e.g. `x0` for `w0`.
+pub fn full_register(mode: &CapstoneMode, reg: RegId) -> RegId {
+    let mut reg = reg;
+    while let Some(parent) = info(mode, reg).and_then(|info| info.parent) {
+        reg = RegId(parent);
+    }
+    reg
+}
+
+/// Get every register contained in the given one, in id order.
+pub fn sub_registers(mode: &CapstoneMode, reg: RegId) -> Vec<RegId> {
+    (1..count(mode))
+        .map(|id| RegId(id as RegIdInt))
+        .filter(|&sub| sub != reg && contains(mode, reg, sub))
+        .collect()
+}
+
+/// Check whether `outer` is `inner` or contains it.
+pub fn contains(mode: &CapstoneMode, outer: RegId, inner: RegId) -> bool {
+    let mut reg = Some(inner);
+    while let Some(current) = reg {
+        if current == outer {
+            return true;
+        }
+        reg = info(mode, current).and_then(|info| info.parent.map(RegId));
+    }
+    false
+}
+
+/// Check whether writing one register may change the other.
+pub fn overlaps(mode: &CapstoneMode, a: RegId, b: RegId) -> bool {
+    a == b || full_register(mode, a) == full_register(mode, b)
+}
+
+/// Get the index of `reg` within the register bank `first..=last`.
+fn bank(reg: u32, first: u32, last: u32) -> Option<u32> {
+    (first..=last).contains(&reg).then(|| reg - first)
+}
+
+fn arm32(reg: u32) -> Option<RegisterInfo> {
+    use RegClass::*;
+
+    let info = |size, class, parent| Some(RegisterInfo::new(reg, size, class, parent));
+    match reg {
+        ArmReg::ARM_REG_SP => info(32, StackPointer, None),
+        ArmReg::ARM_REG_LR => info(32, General, None),
+        ArmReg::ARM_REG_PC => info(32, ProgramCounter, None),
+        ArmReg::ARM_REG_CPSR | ArmReg::ARM_REG_SPSR => info(32, Flags, None),
+        ArmReg::ARM_REG_APSR => info(32, Flags, Some(ArmReg::ARM_REG_CPSR)),
+        ArmReg::ARM_REG_APSR_NZCV => info(4, Flags, Some(ArmReg::ARM_REG_APSR)),
+        ArmReg::ARM_REG_ITSTATE => info(8, System, Some(ArmReg::ARM_REG_CPSR)),
+        // The FPSCR is mostly control bits, but also holds the floating-point flags
+        ArmReg::ARM_REG_FPSCR => Some(RegisterInfo {
+            is_flags: true,
+            ..RegisterInfo::new(reg, 32, System, None)
+        }),
+        ArmReg::ARM_REG_FPSCR_NZCV => info(4, Flags, Some(ArmReg::ARM_REG_FPSCR)),
+        ArmReg::ARM_REG_FPEXC
+        | ArmReg::ARM_REG_FPINST
+        | ArmReg::ARM_REG_FPINST2
+        | ArmReg::ARM_REG_FPSID
+        | ArmReg::ARM_REG_MVFR0
+        | ArmReg::ARM_REG_MVFR1
+        | ArmReg::ARM_REG_MVFR2 => info(32, System, None),
+        _ => {
+            // s(2n) and s(2n + 1) are the halves of d(n), d(2n) and d(2n + 1) the halves of q(n)
+            if bank(reg, ArmReg::ARM_REG_R0, ArmReg::ARM_REG_R12).is_some() {
+                info(32, General, None)
+            } else if let Some(n) = bank(reg, ArmReg::ARM_REG_S0, ArmReg::ARM_REG_S31) {
+                info(32, Simd, Some(ArmReg::ARM_REG_D0 + n / 2))
+            } else if let Some(n) = bank(reg, ArmReg::ARM_REG_D0, ArmReg::ARM_REG_D31) {
+                info(64, Simd, Some(ArmReg::ARM_REG_Q0 + n / 2))
+            } else if bank(reg, ArmReg::ARM_REG_Q0, ArmReg::ARM_REG_Q15).is_some() {
+                info(128, Simd, None)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+fn arm64(reg: u32) -> Option<RegisterInfo> {
+    use RegClass::*;
+
+    let info = |size, class, parent| Some(RegisterInfo::new(reg, size, class, parent));
+    match reg {
+        // x29 and x30 have the same ids as fp and lr
+        Arm64Reg::ARM64_REG_X29 | Arm64Reg::ARM64_REG_X30 => info(64, General, None),
+        Arm64Reg::ARM64_REG_SP => info(64, StackPointer, None),
+        Arm64Reg::ARM64_REG_WSP => info(32, StackPointer, Some(Arm64Reg::ARM64_REG_SP)),
+        Arm64Reg::ARM64_REG_XZR => info(64, Zero, None),
+        Arm64Reg::ARM64_REG_WZR => info(32, Zero, Some(Arm64Reg::ARM64_REG_XZR)),
+        Arm64Reg::ARM64_REG_NZCV => info(4, Flags, None),
+        Arm64Reg::ARM64_REG_FFR => info(0, Predicate, None),
+        _ => {
+            if bank(reg, Arm64Reg::ARM64_REG_X0, Arm64Reg::ARM64_REG_X28).is_some() {
+                return info(64, General, None);
+            }
+            if let Some(n) = bank(reg, Arm64Reg::ARM64_REG_W0, Arm64Reg::ARM64_REG_W30) {
+                let parent = match n {
+                    29 => Arm64Reg::ARM64_REG_X29,
+                    30 => Arm64Reg::ARM64_REG_X30,
+                    _ => Arm64Reg::ARM64_REG_X0 + n,
+                };
+                return info(32, General, Some(parent));
+            }
+            if bank(reg, Arm64Reg::ARM64_REG_P0, Arm64Reg::ARM64_REG_P15).is_some() {
+                return info(0, Predicate, None);
+            }
+            if bank(reg, Arm64Reg::ARM64_REG_Z0, Arm64Reg::ARM64_REG_Z31).is_some() {
+                return info(0, ScalableVector, None);
+            }
+
+            // b(n) < h(n) < s(n) < d(n) < q(n) < v(n) < z(n), q(n) being the scalar view of v(n)
+            let simd = [
+                (Arm64Reg::ARM64_REG_B0, 8, Arm64Reg::ARM64_REG_H0),
+                (Arm64Reg::ARM64_REG_H0, 16, Arm64Reg::ARM64_REG_S0),
+                (Arm64Reg::ARM64_REG_S0, 32, Arm64Reg::ARM64_REG_D0),
+                (Arm64Reg::ARM64_REG_D0, 64, Arm64Reg::ARM64_REG_Q0),
+                (Arm64Reg::ARM64_REG_Q0, 128, Arm64Reg::ARM64_REG_V0),
+                (Arm64Reg::ARM64_REG_V0, 128, Arm64Reg::ARM64_REG_Z0),
+            ];
+            simd.into_iter().find_map(|(first, size, parent)| {
+                let n = bank(reg, first, first + 31)?;
+                info(size, Simd, Some(parent + n))
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reg(reg: u32) -> RegId {
+        RegId(reg as RegIdInt)
+    }
+
+    #[test]
+    fn arm64_aliasing() {
+        let mode = CapstoneMode::ARM64;
+        let w0 = info(&mode, reg(Arm64Reg::ARM64_REG_W0)).unwrap();
+        assert_eq!(w0.size, 32);
+        assert_eq!(w0.class, RegClass::General);
+        assert_eq!(w0.parent, Some(Arm64Reg::ARM64_REG_X0 as RegIdInt));
+
+        assert_eq!(
+            full_register(&mode, reg(Arm64Reg::ARM64_REG_W29)),
+            reg(Arm64Reg::ARM64_REG_FP)
+        );
+        assert!(overlaps(
+            &mode,
+            reg(Arm64Reg::ARM64_REG_S3),
+            reg(Arm64Reg::ARM64_REG_V3)
+        ));
+        assert!(!overlaps(
+            &mode,
+            reg(Arm64Reg::ARM64_REG_S3),
+            reg(Arm64Reg::ARM64_REG_D4)
+        ));
+        assert_eq!(
+            sub_registers(&mode, reg(Arm64Reg::ARM64_REG_Q0)),
+            [
+                Arm64Reg::ARM64_REG_B0,
+                Arm64Reg::ARM64_REG_D0,
+                Arm64Reg::ARM64_REG_H0,
+                Arm64Reg::ARM64_REG_S0
+            ]
+            .map(reg)
+        );
+        assert!(info(&mode, reg(Arm64Reg::ARM64_REG_NZCV)).unwrap().is_flags);
+        assert!(info(&mode, reg(Arm64Reg::ARM64_REG_INVALID)).is_none());
+    }
+
+    #[test]
+    fn arm32_aliasing() {
+        let mode = CapstoneMode::ARM32;
+        assert_eq!(reg(ArmReg::ARM_REG_R13), reg(ArmReg::ARM_REG_SP));
+        assert_eq!(
+            info(&mode, reg(ArmReg::ARM_REG_SP)).unwrap().class,
+            RegClass::StackPointer
+        );
+        assert_eq!(
+            full_register(&mode, reg(ArmReg::ARM_REG_S5)),
+            reg(ArmReg::ARM_REG_Q1)
+        );
+        assert_eq!(
+            sub_registers(&mode, reg(ArmReg::ARM_REG_D1)),
+            [ArmReg::ARM_REG_S2, ArmReg::ARM_REG_S3].map(reg)
+        );
+        assert!(sub_registers(&mode, reg(ArmReg::ARM_REG_D16)).is_empty());
+        assert!(info(&mode, reg(ArmReg::ARM_REG_D16)).unwrap().is_simd());
+        assert!(info(&mode, reg(ArmReg::ARM_REG_FPSCR)).unwrap().is_flags);
+    }
+
+    #[test]
+    fn every_register_is_described() {
+        for mode in [CapstoneMode::ARM32, CapstoneMode::ARM64] {
+            for id in 1..count(&mode) {
+                let info = info(&mode, RegId(id as RegIdInt));
+                assert!(info.is_some(), "{} register {} has no metadata", mode, id);
+            }
+        }
+        assert!(info(&CapstoneMode::X86_64, RegId(1)).is_none());
+    }
+}
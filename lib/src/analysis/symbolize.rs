@@ -0,0 +1,288 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use capstone::arch::arm64::{Arm64Insn, Arm64OperandType};
+use capstone::arch::DetailsArchInsn;
+use capstone::{Capstone, Insn, InsnIdInt, RegIdInt};
+
+use crate::analysis::pcrel::PcRelReference;
+use crate::analysis::{decode, pcrel, Image};
+use crate::capstone::mode::CapstoneMode;
+
+/// Supplies symbol names for addresses.
+pub trait SymbolResolver {
+    /// Get the name of the symbol at exactly the given address.
+    fn resolve(&mut self, address: u64) -> Option<String>;
+}
+
+impl SymbolResolver for BTreeMap<u64, String> {
+    fn resolve(&mut self, address: u64) -> Option<String> {
+        self.get(&address).cloned()
+    }
+}
+
+/// Find the last immediate token (`#...`) of an operand string and return its range.
+fn last_immediate(op_str: &str) -> Option<(usize, usize)> {
+    let start = op_str.rfind('#')?;
+    let end = op_str[start..]
+        .find([',', ']', '!', '}'])
+        .map_or(op_str.len(), |end| start + end);
+    Some((start, end))
+}
+
+/// Replace the last immediate of an operand string.
+fn replace_last_immediate(op_str: &str, replacement: &str) -> Option<String> {
+    let (start, end) = last_immediate(op_str)?;
+    Some(format!(
+        "{}{}{}",
+        &op_str[..start],
+        replacement,
+        &op_str[end..]
+    ))
+}
+
+/// Replace a PC-relative memory operand, e.g. `[pc, #0x10]`.
+fn replace_pc_memory(op_str: &str, replacement: &str) -> Option<String> {
+    let start = op_str.find("[pc")?;
+    let end = start + op_str[start..].find(']')? + 1;
+    Some(format!(
+        "{}{}{}",
+        &op_str[..start],
+        replacement,
+        &op_str[end..]
+    ))
+}
+
+/// Pair each ARM64 `adrp` with the full address completed by a later instruction using
+/// its page, e.g. `add x0, x0, #0x790` or `ldr x1, [x0, #0x10]`. The result maps the
+/// index of the `adrp` to the address completed by its first consumer, and collects the
+/// indices of all consumers.
+fn pair_pages(
+    capstone: &Capstone,
+    image: &Image,
+    instructions: &[&Insn],
+    references: &[Option<PcRelReference>],
+) -> (HashMap<usize, u64>, HashSet<usize>) {
+    use Arm64Insn::*;
+
+    let mut pages: HashMap<usize, u64> = HashMap::new();
+    let mut consumers: HashSet<usize> = HashSet::new();
+    // Registers holding a page, mapped to the index of the 'adrp' producing it
+    let mut live: HashMap<RegIdInt, usize> = HashMap::new();
+
+    for (i, insn) in instructions.iter().enumerate() {
+        let Ok(detail) = capstone.insn_detail(insn) else {
+            live.clear();
+            continue;
+        };
+        let arch_detail = detail.arch_detail();
+        let Some(arch) = arch_detail.arm64() else {
+            live.clear();
+            continue;
+        };
+        let operands: Vec<Arm64OperandType> = arch.operands().map(|op| op.op_type).collect();
+        let id = insn.id().0;
+
+        let dst = match operands.first() {
+            Some(Arm64OperandType::Reg(reg)) => Some(reg.0),
+            _ => None,
+        };
+        if id == ARM64_INS_ADRP as InsnIdInt {
+            if let Some(dst) = dst {
+                live.insert(dst, i);
+            }
+            continue;
+        }
+
+        // The register the instruction adds an offset to
+        let base = if id == ARM64_INS_ADD as InsnIdInt {
+            match operands.as_slice() {
+                [_, Arm64OperandType::Reg(src), Arm64OperandType::Imm(_)] => Some(src.0),
+                _ => None,
+            }
+        } else {
+            operands.iter().find_map(|op| match op {
+                Arm64OperandType::Mem(mem) if mem.index().0 == 0 => Some(mem.base().0),
+                _ => None,
+            })
+        };
+        if let (Some(base), Some(reference)) = (base, references[i]) {
+            if let Some(page) = live.get(&base).copied() {
+                pages.entry(page).or_insert(reference.address);
+                consumers.insert(i);
+            }
+        }
+
+        if decode::classify(capstone, &CapstoneMode::ARM64, image, insn)
+            .is_some_and(|decoded| decoded.flow.is_call || decoded.flow.is_terminator)
+        {
+            live.clear();
+        } else {
+            for reg in detail.regs_write() {
+                live.remove(&reg.0);
+            }
+            if let Some(dst) = dst {
+                live.remove(&dst);
+            }
+        }
+    }
+
+    (pages, consumers)
+}
+
+/// Render the operands of every instruction with symbol names in place of the branch
+/// targets and PC-relative addresses they resolve to. The result is index-aligned with
+/// `instructions`. Operands without a resolved symbol are rendered unchanged.
+pub fn render(
+    mode: &CapstoneMode,
+    capstone: &Capstone,
+    instructions: &[&Insn],
+    image: &Image,
+    resolver: &mut dyn SymbolResolver,
+) -> Vec<String> {
+    let references = pcrel::resolve(mode, capstone, instructions, image)
+        .unwrap_or_else(|| vec![None; instructions.len()]);
+    let (pages, consumers) = match mode {
+        CapstoneMode::ARM64 => pair_pages(capstone, image, instructions, &references),
+        _ => Default::default(),
+    };
+
+    instructions
+        .iter()
+        .enumerate()
+        .map(|(i, insn)| {
+            let op_str = insn.op_str().unwrap_or("");
+
+            let rendered = if let Some(address) = pages.get(&i) {
+                // 'adrp x0, g_table@PAGE'
+                resolver
+                    .resolve(*address)
+                    .and_then(|name| replace_last_immediate(op_str, &format!("{}@PAGE", name)))
+            } else if let (true, Some(reference)) = (consumers.contains(&i), references[i]) {
+                // 'add x0, x0, g_table@PAGEOFF', each consumer of a page may complete a
+                // different address
+                resolver
+                    .resolve(reference.address)
+                    .and_then(|name| replace_last_immediate(op_str, &format!("{}@PAGEOFF", name)))
+            } else if let Some(target) =
+                decode::classify(capstone, mode, image, insn).and_then(|decoded| decoded.target)
+            {
+                // 'bl memcpy'
+                resolver
+                    .resolve(target.address)
+                    .and_then(|name| replace_last_immediate(op_str, &name))
+            } else if let Some(reference) = references[i] {
+                // 'ldr r0, =g_table' if the literal holds the address of a symbol,
+                // otherwise 'ldr r0, literal' or 'adr x0, g_table'
+                let value = reference.value.and_then(|value| resolver.resolve(value));
+                match value.and_then(|name| replace_pc_memory(op_str, &format!("={}", name))) {
+                    Some(rendered) => Some(rendered),
+                    None => resolver.resolve(reference.address).and_then(|name| {
+                        replace_pc_memory(op_str, &name)
+                            .or_else(|| replace_last_immediate(op_str, &name))
+                    }),
+                }
+            } else {
+                None
+            };
+
+            rendered.unwrap_or_else(|| op_str.to_string())
+        })
+        .collect()
+}
+
+/// Records the addresses looked up without resolving any of them, so that every
+/// fallback is looked up too. Used to find the symbols to resolve up front.
+#[derive(Default)]
+pub struct LookupRecorder(pub BTreeSet<u64>);
+
+impl SymbolResolver for LookupRecorder {
+    fn resolve(&mut self, address: u64) -> Option<String> {
+        self.0.insert(address);
+        None
+    }
+}
+
+/// Collect every address `render` may look up for the given instructions.
+pub fn lookups(
+    mode: &CapstoneMode,
+    capstone: &Capstone,
+    instructions: &[&Insn],
+    image: &Image,
+) -> BTreeSet<u64> {
+    let mut recorder = LookupRecorder::default();
+    render(mode, capstone, instructions, image, &mut recorder);
+    recorder.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::testing::{disassemble, ARM64_CODE};
+
+    #[test]
+    fn replaces_immediates() {
+        assert_eq!(
+            replace_last_immediate("x0, x0, #0x790", "name").as_deref(),
+            Some("x0, x0, name")
+        );
+        assert_eq!(
+            replace_last_immediate("x1, [x0, #0x10]", "name").as_deref(),
+            Some("x1, [x0, name]")
+        );
+        assert_eq!(replace_last_immediate("x0, x1", "name"), None);
+        assert_eq!(
+            replace_pc_memory("r0, [pc, #4]", "=name").as_deref(),
+            Some("r0, =name")
+        );
+    }
+
+    #[test]
+    fn renders_symbols() {
+        let mut symbols =
+            BTreeMap::from([(0xedc, "puts".to_string()), (0x1790, "g_table".to_string())]);
+        disassemble(
+            CapstoneMode::ARM64,
+            ARM64_CODE,
+            0x1000,
+            |cs, mode, insns, image| {
+                let operands = render(mode, cs, insns, image, &mut symbols);
+                assert_eq!(operands[0], "x29, x30, [sp, #-0x10]!");
+                assert_eq!(operands[2], "x0, g_table@PAGE");
+                assert_eq!(operands[3], "x0, x0, g_table@PAGEOFF");
+                assert_eq!(operands[4], "puts");
+                assert_eq!(
+                    lookups(mode, cs, insns, image),
+                    BTreeSet::from([0xedc, 0x1790])
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn renders_each_page_offset() {
+        // 'adrp x8, #0x2000', 'ldr x0, [x8, #0x10]', 'ldr x1, [x8, #0x20]'
+        let code = [
+            0x08, 0x00, 0x00, 0xb0, 0x00, 0x09, 0x40, 0xf9, 0x01, 0x11, 0x40, 0xf9,
+        ];
+        let mut symbols = BTreeMap::from([
+            (0x2010, "first".to_string()),
+            (0x2020, "second".to_string()),
+        ]);
+        disassemble(
+            CapstoneMode::ARM64,
+            &code,
+            0x1000,
+            |cs, mode, insns, image| {
+                let operands = render(mode, cs, insns, image, &mut symbols);
+                assert_eq!(operands[0], "x8, first@PAGE");
+                assert_eq!(operands[1], "x0, [x8, first@PAGEOFF]");
+                assert_eq!(operands[2], "x1, [x8, second@PAGEOFF]");
+            },
+        );
+    }
+}
@@ -0,0 +1,89 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use std::collections::BTreeMap;
+
+use capstone::Capstone;
+
+use crate::analysis::decode::{self, Decoded};
+use crate::analysis::jumptable;
+use crate::analysis::Image;
+use crate::capstone::mode::CapstoneMode;
+
+/// Recursive-descent traversal of an image. Starting at each entry point, instructions are
+/// decoded linearly until a terminator is reached. Direct branch and call targets, as well as
+/// known indirect targets and jump table cases, are queued as further entry points.
+///
+/// Returns the decoded instructions keyed by address.
+pub fn traverse(
+    capstone: &Capstone,
+    mode: &CapstoneMode,
+    image: &Image,
+    entries: &[u64],
+) -> BTreeMap<u64, Decoded> {
+    let mut decoded: BTreeMap<u64, Decoded> = BTreeMap::new();
+    let mut worklist: Vec<u64> = entries.iter().rev().copied().collect();
+
+    while let Some(mut address) = worklist.pop() {
+        while image.contains(address) && !decoded.contains_key(&address) {
+            let Some(mut insn) = decode::decode(capstone, mode, image, address) else {
+                break;
+            };
+            if let Some(table) = jumptable::resolve_at(capstone, mode, image, &decoded, &insn) {
+                insn.indirect_targets.extend(table.targets);
+            }
+
+            worklist.extend(insn.followable_target());
+            worklist.extend(insn.indirect_targets.iter().copied());
+
+            let is_terminator = insn.flow.is_terminator;
+            address = insn.next();
+            decoded.insert(insn.address, insn);
+
+            if is_terminator {
+                break;
+            }
+        }
+    }
+
+    decoded
+}
+
+/// Group decoded instructions into runs of contiguous instructions. Each run is returned as its
+/// start address and instruction count, ordered by address.
+pub fn runs(decoded: &BTreeMap<u64, Decoded>) -> Vec<(u64, usize)> {
+    let mut runs: Vec<(u64, usize)> = Vec::new();
+    let mut next: Option<u64> = None;
+
+    for insn in decoded.values() {
+        match runs.last_mut() {
+            Some((_, count)) if next == Some(insn.address) => *count += 1,
+            _ => runs.push((insn.address, 1)),
+        }
+        next = Some(insn.next());
+    }
+
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::testing::{disassemble, ARM64_CODE};
+
+    #[test]
+    fn stops_at_terminators() {
+        // The function followed by data that must not be decoded
+        let mut code = ARM64_CODE.to_vec();
+        code.extend_from_slice(&[0xff; 8]);
+        disassemble(CapstoneMode::ARM64, &code, 0x1000, |cs, mode, _, image| {
+            let decoded = traverse(cs, mode, image, &[0x1000]);
+            assert_eq!(decoded.len(), 8);
+            assert!(decoded.contains_key(&0x101c));
+            assert!(!decoded.contains_key(&0x1020));
+            assert_eq!(runs(&decoded), vec![(0x1000, 8)]);
+        });
+    }
+}
@@ -0,0 +1,151 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use capstone::arch::arm64::Arm64Insn;
+use capstone::{Capstone, Insn, InsnIdInt};
+
+use crate::analysis::{decode, jumptable, pcrel, Image};
+use crate::capstone::mode::CapstoneMode;
+
+/// Kind of cross-reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum XrefKind {
+    /// Target of a direct call.
+    Call = 0,
+    /// Target of a direct branch.
+    Jump = 1,
+    /// Known target of an indirect branch, e.g. a jump table case.
+    Indirect = 2,
+    /// Address read by a PC-relative load.
+    Read = 3,
+    /// Address materialized in a register, e.g. by 'adr' or an 'adrp' pair.
+    Address = 4,
+}
+
+/// A reference from an instruction to an address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Xref {
+    /// Address of the referencing instruction.
+    pub from: u64,
+    /// The referenced address.
+    pub to: u64,
+    pub kind: XrefKind,
+}
+
+/// Cross-references of an image, indexed by target and by source.
+#[derive(Debug, Default)]
+pub struct XrefIndex {
+    /// All references, ordered by target address.
+    pub by_target: Vec<Xref>,
+    /// Indices into `by_target`, ordered by source address.
+    pub by_source: Vec<usize>,
+}
+
+impl XrefIndex {
+    fn new(mut xrefs: Vec<Xref>) -> XrefIndex {
+        xrefs.sort_by_key(|xref| (xref.to, xref.from, xref.kind));
+        xrefs.dedup();
+
+        let mut by_source: Vec<usize> = (0..xrefs.len()).collect();
+        by_source.sort_by_key(|i| (xrefs[*i].from, xrefs[*i].to, xrefs[*i].kind));
+
+        XrefIndex {
+            by_target: xrefs,
+            by_source,
+        }
+    }
+}
+
+/// Check whether an instruction materializes an address rather than loading from it.
+fn is_address(mode: &CapstoneMode, insn: &Insn) -> bool {
+    use capstone::arch::arm::ArmInsn::*;
+    use Arm64Insn::*;
+
+    let id = insn.id().0;
+    match mode {
+        CapstoneMode::ARM32 | CapstoneMode::Thumb => [ARM_INS_ADR, ARM_INS_ADD, ARM_INS_SUB]
+            .iter()
+            .any(|i| id == *i as InsnIdInt),
+        CapstoneMode::ARM64 => [ARM64_INS_ADR, ARM64_INS_ADD]
+            .iter()
+            .any(|i| id == *i as InsnIdInt),
+        _ => false,
+    }
+}
+
+/// Collect the code and data references of the given instructions. Pages computed by
+/// 'adrp' are not recorded on their own, only the addresses completed from them.
+pub fn build(
+    mode: &CapstoneMode,
+    capstone: &Capstone,
+    instructions: &[&Insn],
+    image: &Image,
+) -> XrefIndex {
+    let references = pcrel::resolve(mode, capstone, instructions, image);
+    let decoded = decode::classify_all(capstone, mode, image, instructions);
+    let jump_tables = references.as_deref().map(|references| {
+        jumptable::resolve(mode, capstone, instructions, references, &decoded, image)
+    });
+
+    let mut xrefs = Vec::new();
+    for (i, insn) in instructions.iter().enumerate() {
+        let from = insn.address();
+
+        if let Some(decoded) = &decoded[i] {
+            if let Some(target) = decoded.target {
+                let kind = if decoded.flow.is_call {
+                    XrefKind::Call
+                } else {
+                    XrefKind::Jump
+                };
+                xrefs.push(Xref {
+                    from,
+                    to: target.address,
+                    kind,
+                });
+            }
+            for to in &decoded.indirect_targets {
+                xrefs.push(Xref {
+                    from,
+                    to: *to,
+                    kind: XrefKind::Indirect,
+                });
+            }
+        }
+
+        if let Some(table) = jump_tables.as_ref().and_then(|tables| tables[i].as_ref()) {
+            xrefs.push(Xref {
+                from,
+                to: table.address,
+                kind: XrefKind::Read,
+            });
+            for to in &table.targets {
+                xrefs.push(Xref {
+                    from,
+                    to: *to,
+                    kind: XrefKind::Indirect,
+                });
+            }
+        }
+
+        let reference = references.as_ref().and_then(|references| references[i]);
+        let is_page = matches!(mode, CapstoneMode::ARM64)
+            && insn.id().0 == Arm64Insn::ARM64_INS_ADRP as InsnIdInt;
+        if let (Some(reference), false) = (reference, is_page) {
+            let kind = if is_address(mode, insn) {
+                XrefKind::Address
+            } else {
+                XrefKind::Read
+            };
+            xrefs.push(Xref {
+                from,
+                to: reference.address,
+                kind,
+            });
+        }
+    }
+
+    XrefIndex::new(xrefs)
+}
@@ -0,0 +1,68 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use jni::objects::JObject;
+use jni::JNIEnv;
+
+use crate::analysis::functions::Function;
+use crate::obj::obj::Obj;
+use crate::util::{AutoObject, JResult};
+use crate::writer::layout::{ClassLayout, Member};
+
+const FUNCTION_CLASS: &str = "Lorg/native4j/capstone/analysis/FunctionRange;";
+const TABLE_CLASS: &str = "org/native4j/capstone/analysis/FunctionTable";
+
+/// Get the layout of the Java classes written by `write_functions`.
+pub fn get_layout() -> Vec<ClassLayout<'static>> {
+    vec![
+        ClassLayout::new(
+            FUNCTION_CLASS,
+            &[&[
+                Member::Constructor("()V"),
+                Member::Field("startAddress", "J"),
+                Member::Field("endAddress", "J"),
+                Member::Field("instructionCount", "I"),
+                Member::Field("isCallTarget", "Z"),
+                Member::Field("hasPrologue", "Z"),
+                Member::Field("returns", "Z"),
+            ]],
+        ),
+        ClassLayout::new(
+            TABLE_CLASS,
+            &[&[Member::Field(
+                "functions",
+                "[Lorg/native4j/capstone/analysis/FunctionRange;",
+            )]],
+        ),
+    ]
+}
+
+/// Write the recovered functions to a Java `FunctionTable` object.
+pub fn write_functions<'jni>(
+    env: &mut JNIEnv<'jni>,
+    table_object: &JObject<'jni>,
+    functions: &[Function],
+) -> JResult<()> {
+    let mut objects: Vec<AutoObject<'jni>> = Vec::with_capacity(functions.len());
+    for function in functions {
+        let object = {
+            let obj = env.new_object(FUNCTION_CLASS, "()V", &[])?;
+            env.auto_local(obj)
+        };
+
+        let mut dst = Obj::from(env, &object);
+        dst.set_long("startAddress", function.start as i64)?;
+        dst.set_long("endAddress", function.end as i64)?;
+        dst.set_int("instructionCount", function.instruction_count as i32)?;
+        dst.set_bool("isCallTarget", function.is_call_target)?;
+        dst.set_bool("hasPrologue", function.has_prologue)?;
+        dst.set_bool("returns", function.returns)?;
+
+        objects.push(object);
+    }
+
+    let mut dst = Obj::from(env, table_object);
+    dst.set_object_array("functions", FUNCTION_CLASS, &objects)
+}
@@ -0,0 +1,94 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use jni::objects::{JObject, JValue};
+use jni::JNIEnv;
+
+use crate::analysis::cfg::ControlFlowGraph;
+use crate::obj::obj::Obj;
+use crate::util::{AutoObject, JResult};
+use crate::writer::layout::{ClassLayout, Member};
+
+const BLOCK_CLASS: &str = "Lorg/native4j/capstone/analysis/BasicBlock;";
+const EDGE_CLASS: &str = "Lorg/native4j/capstone/analysis/FlowEdge;";
+const GRAPH_CLASS: &str = "org/native4j/capstone/analysis/ControlFlowGraph";
+const EDGE_CONSTRUCTOR: &str =
+    "(BLorg/native4j/capstone/analysis/BasicBlock;Lorg/native4j/capstone/analysis/BasicBlock;)V";
+const EDGE_ARRAY: &str = "[Lorg/native4j/capstone/analysis/FlowEdge;";
+
+/// Get the layout of the Java classes written by `write_graph`.
+pub fn get_layout() -> Vec<ClassLayout<'static>> {
+    vec![
+        ClassLayout::new(
+            BLOCK_CLASS,
+            &[&[
+                Member::Constructor("()V"),
+                Member::Field("startAddress", "J"),
+                Member::Field("endAddress", "J"),
+                Member::Field("instructionCount", "I"),
+                Member::Field("successors", EDGE_ARRAY),
+                Member::Field("predecessors", EDGE_ARRAY),
+            ]],
+        ),
+        ClassLayout::new(EDGE_CLASS, &[&[Member::Constructor(EDGE_CONSTRUCTOR)]]),
+        ClassLayout::new(
+            GRAPH_CLASS,
+            &[&[
+                Member::Field("blocks", "[Lorg/native4j/capstone/analysis/BasicBlock;"),
+                Member::Field("edges", EDGE_ARRAY),
+            ]],
+        ),
+    ]
+}
+
+/// Write a control-flow graph to a Java `ControlFlowGraph` object.
+pub fn write_graph<'jni>(
+    env: &mut JNIEnv<'jni>,
+    graph_object: &JObject<'jni>,
+    graph: &ControlFlowGraph,
+) -> JResult<()> {
+    let mut blocks: Vec<AutoObject<'jni>> = Vec::with_capacity(graph.blocks.len());
+    for block in &graph.blocks {
+        let object = {
+            let obj = env.new_object(BLOCK_CLASS, "()V", &[])?;
+            env.auto_local(obj)
+        };
+
+        let mut dst = Obj::from(env, &object);
+        dst.set_long("startAddress", block.start as i64)?;
+        dst.set_long("endAddress", block.end as i64)?;
+        dst.set_int("instructionCount", block.instruction_count as i32)?;
+
+        blocks.push(object);
+    }
+
+    let mut edges: Vec<AutoObject<'jni>> = Vec::with_capacity(graph.edges.len());
+    for edge in &graph.edges {
+        let obj = env.new_object(
+            EDGE_CLASS,
+            EDGE_CONSTRUCTOR,
+            &[
+                JValue::Byte(edge.kind as i8),
+                JValue::Object(&blocks[edge.from]),
+                JValue::Object(&blocks[edge.to]),
+            ],
+        )?;
+        edges.push(env.auto_local(obj));
+    }
+
+    for (block, object) in graph.blocks.iter().zip(&blocks) {
+        let successors: Vec<&AutoObject> = block.successors.iter().map(|i| &edges[*i]).collect();
+        let predecessors: Vec<&AutoObject> =
+            block.predecessors.iter().map(|i| &edges[*i]).collect();
+
+        let mut dst = Obj::from(env, object);
+        dst.set_object_array("successors", EDGE_CLASS, &successors)?;
+        dst.set_object_array("predecessors", EDGE_CLASS, &predecessors)?;
+    }
+
+    let mut dst = Obj::from(env, graph_object);
+    dst.set_object_array("blocks", BLOCK_CLASS, &blocks)?;
+    dst.set_object_array("edges", EDGE_CLASS, &edges)
+}
@@ -3,45 +3,118 @@
  *
  * Use of this source code is governed by the MIT license found in the LICENSE file.
  */
+use crate::analysis::symbolize::{LookupRecorder, SymbolResolver};
+use crate::analysis::{cfg, decode, functions, registers, symbolize, traversal, xref, Image};
 use crate::capstone::context::CapstoneContext;
 use crate::capstone::mode::CapstoneMode;
 use crate::capstone::output::CapstoneOutput;
+use crate::format::json;
+use crate::format::listing::{self, ListingOptions};
+use crate::obj::obj::Obj;
+use crate::writer::layout::{self, ClassLayout, Member};
 use capstone::arch::BuildsCapstone;
-use capstone::{arch, Capstone, InsnGroupId, InsnGroupIdInt, InsnId, InsnIdInt, RegId, RegIdInt};
-use jni::objects::{JByteArray, JObject, ReleaseMode};
+use capstone::{
+    arch, Capstone, CsResult, Insn, InsnGroupId, InsnGroupIdInt, InsnId, InsnIdInt, Instructions,
+    RegId, RegIdInt,
+};
+use jni::objects::{JByteArray, JLongArray, JObject, JString, ReleaseMode};
 use jni::sys::{jint, jlong, jshort};
 use jni::JNIEnv;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
 use std::ops::BitAnd;
+use std::sync::OnceLock;
 
 pub mod context;
+pub mod function_table;
+pub mod graph;
 pub mod mode;
+pub mod name_table;
 pub mod output;
+pub mod register_table;
+pub mod resolver;
+pub mod xref_index;
 
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
-pub fn init<'local>(
-    env: &mut JNIEnv<'local>,
-    this: JObject<'local>,
-    mode: JObject<'local>,
-) -> Result<()> {
-    let mode = CapstoneMode::from(env, &mode);
-
-    let capstone = match mode {
-        Some(CapstoneMode::ARM32) => Capstone::new()
+/// Create a Capstone instance with instruction detail enabled for the given mode.
+pub fn create(mode: &CapstoneMode) -> CsResult<Capstone> {
+    match mode {
+        CapstoneMode::ARM32 => Capstone::new()
             .arm()
             .mode(arch::arm::ArchMode::Arm)
             .detail(true)
             .build(),
-        Some(CapstoneMode::ARM64) => Capstone::new()
+        CapstoneMode::ARM64 => Capstone::new()
             .arm64()
             .mode(arch::arm64::ArchMode::Arm)
             .detail(true)
             .build(),
-        _ => return Err("invalid argument 'mode'".into()),
+        CapstoneMode::Thumb => Capstone::new()
+            .arm()
+            .mode(arch::arm::ArchMode::Thumb)
+            .detail(true)
+            .build(),
+        CapstoneMode::X86_32 => Capstone::new()
+            .x86()
+            .mode(arch::x86::ArchMode::Mode32)
+            .detail(true)
+            .build(),
+        CapstoneMode::X86_64 => Capstone::new()
+            .x86()
+            .mode(arch::x86::ArchMode::Mode64)
+            .detail(true)
+            .build(),
     }
-    .map_err(|e| e.to_string())?;
+}
+
+/// Results of validating the Java classes used in each mode, indexed by ordinal.
+static VALIDATED: [OnceLock<std::result::Result<(), String>>; 5] = [const { OnceLock::new() }; 5];
+
+/// Get the layout of every Java class written or read by a Capstone instance in the
+/// given mode.
+fn get_layout(mode: &CapstoneMode) -> Vec<ClassLayout<'static>> {
+    let mut layouts = output::get_layout(mode);
+    layouts.extend(function_table::get_layout());
+    layouts.extend(graph::get_layout());
+    layouts.extend(xref_index::get_layout());
+    layouts.extend(name_table::get_layout());
+    layouts.extend(register_table::get_layout());
+    layouts.extend([
+        ClassLayout::new(
+            "org/native4j/capstone/listing/Listing",
+            &[&[Member::Field("text", "Ljava/lang/String;")]],
+        ),
+        ClassLayout::new(
+            "org/native4j/capstone/json/JsonExport",
+            &[&[Member::Field("text", "Ljava/lang/String;")]],
+        ),
+        ClassLayout::new(
+            "org/native4j/capstone/listing/ListingOptions",
+            &[&[
+                Member::Field("byteGrouping", "I"),
+                Member::Field("addressWidth", "I"),
+                Member::Field("comments", "Z"),
+                Member::Field("symbols", "Lorg/native4j/capstone/symbol/SymbolResolver;"),
+            ]],
+        ),
+    ]);
+    layouts
+}
 
-    let instance = CapstoneContext::new(capstone, mode.unwrap());
+pub fn init<'local>(
+    env: &mut JNIEnv<'local>,
+    this: JObject<'local>,
+    mode: JObject<'local>,
+) -> Result<()> {
+    let mode = CapstoneMode::from(env, &mode).ok_or("invalid argument 'mode'")?;
+
+    // Fail early if the Java classes do not match what the writers expect
+    layout::validate_once(&VALIDATED[mode.ordinal()], env, || get_layout(&mode))?;
+
+    let capstone = create(&mode).map_err(|e| e.to_string())?;
+
+    let instance = CapstoneContext::new(capstone, mode);
 
     CapstoneContext::surrender_instance(instance, env, &this)?;
     Ok(())
@@ -55,10 +128,7 @@ pub fn disassemble<'local>(
     count: jint,
     address: jlong,
 ) -> Result<()> {
-    let code: Vec<u8> = {
-        let result = unsafe { env.get_array_elements(&data, ReleaseMode::NoCopyBack)? };
-        result.iter().map(|b| (*b as u8).bitand(0xff)).collect()
-    };
+    let code = read_bytes(env, &data)?;
 
     let ctx = CapstoneContext::get(env, &this)?;
     let capstone = ctx.capstone.lock().unwrap();
@@ -72,8 +142,344 @@ pub fn disassemble<'local>(
         .map_err(|e| e.to_string())?
     };
 
+    let instructions: Vec<&Insn> = (*instructions).iter().collect();
+
     let mut output = CapstoneOutput::new(env, &ctx.mode, &capstone, &result_object);
-    output.copy_instructions(instructions)
+    let image = Image {
+        code: &code,
+        address: address as u64,
+    };
+    output.copy_instructions(&instructions, &image)
+}
+
+pub fn disassemble_symbolized<'local>(
+    env: &mut JNIEnv<'local>,
+    this: JObject<'local>,
+    result_object: JObject<'local>,
+    data: JByteArray<'local>,
+    address: jlong,
+    resolver_object: JObject<'local>,
+) -> Result<()> {
+    let code = read_bytes(env, &data)?;
+
+    let ctx = CapstoneContext::get(env, &this)?;
+    if !decode::is_supported(&ctx.mode) {
+        return Err(format!("symbolization is not supported for {}", ctx.mode).into());
+    }
+
+    let image = Image {
+        code: &code,
+        address: address as u64,
+    };
+    let addresses = {
+        let capstone = ctx.capstone.lock().unwrap();
+        let instructions = capstone
+            .disasm_all(&code, address as u64)
+            .map_err(|e| e.to_string())?;
+        let instructions: Vec<&Insn> = (*instructions).iter().collect();
+        symbolize::lookups(&ctx.mode, &capstone, &instructions, &image)
+    };
+
+    // The resolver may call back into this instance, so it runs without the lock held
+    let mut symbols = resolver::resolve_all(env, &resolver_object, &addresses)?;
+
+    let capstone = ctx.capstone.lock().unwrap();
+    let instructions = capstone
+        .disasm_all(&code, address as u64)
+        .map_err(|e| e.to_string())?;
+    let instructions: Vec<&Insn> = (*instructions).iter().collect();
+    let operands = symbolize::render(&ctx.mode, &capstone, &instructions, &image, &mut symbols);
+
+    let mut output = CapstoneOutput::new(env, &ctx.mode, &capstone, &result_object)
+        .with_symbolized_operands(&operands);
+    output.copy_instructions(&instructions, &image)
+}
+
+pub fn render_listing<'local>(
+    env: &mut JNIEnv<'local>,
+    this: JObject<'local>,
+    listing_object: JObject<'local>,
+    data: JByteArray<'local>,
+    address: jlong,
+    options_object: JObject<'local>,
+    path: JString<'local>,
+) -> Result<()> {
+    let code = read_bytes(env, &data)?;
+    let options = read_listing_options(env, &options_object)?;
+    let symbols = env
+        .get_field(
+            &options_object,
+            "symbols",
+            "Lorg/native4j/capstone/symbol/SymbolResolver;",
+        )?
+        .l()?;
+
+    let ctx = CapstoneContext::get(env, &this)?;
+    let image = Image {
+        code: &code,
+        address: address as u64,
+    };
+
+    // The resolver may call back into this instance, so it runs without the lock held
+    let mut symbols = if symbols.is_null() {
+        None
+    } else {
+        let addresses = {
+            let capstone = ctx.capstone.lock().unwrap();
+            let mut recorder = LookupRecorder::default();
+            listing::write_listing(
+                io::sink(),
+                &ctx.mode,
+                &options,
+                Some(&mut recorder),
+                &capstone,
+                &image,
+            )?;
+            recorder.0
+        };
+        Some(resolver::resolve_all(env, &symbols, &addresses)?)
+    };
+
+    let capstone = ctx.capstone.lock().unwrap();
+    write_output(env, &listing_object, &path, |_, out| {
+        let resolver = symbols
+            .as_mut()
+            .map(|symbols| symbols as &mut dyn SymbolResolver);
+        listing::write_listing(out, &ctx.mode, &options, resolver, &capstone, &image)?;
+        Ok(())
+    })
+}
+
+pub fn export_json<'local>(
+    env: &mut JNIEnv<'local>,
+    this: JObject<'local>,
+    export_object: JObject<'local>,
+    data: JByteArray<'local>,
+    address: jlong,
+    path: JString<'local>,
+) -> Result<()> {
+    let code = read_bytes(env, &data)?;
+
+    let ctx = CapstoneContext::get(env, &this)?;
+    let capstone = ctx.capstone.lock().unwrap();
+    let instructions = capstone
+        .disasm_all(&code, address as u64)
+        .map_err(|e| e.to_string())?;
+    let instructions: Vec<&Insn> = (*instructions).iter().collect();
+
+    let image = Image {
+        code: &code,
+        address: address as u64,
+    };
+    write_output(env, &export_object, &path, |_, out| {
+        json::write_json(out, &ctx.mode, &capstone, &instructions, &image)?;
+        Ok(())
+    })
+}
+
+/// Write rendered text to the file at `path`, or to the `text` field of `text_object`
+/// if `path` is null.
+fn write_output<'local>(
+    env: &mut JNIEnv<'local>,
+    text_object: &JObject<'local>,
+    path: &JString<'local>,
+    render: impl FnOnce(&mut JNIEnv<'local>, &mut dyn Write) -> Result<()>,
+) -> Result<()> {
+    if path.is_null() {
+        let mut out = Vec::new();
+        render(env, &mut out)?;
+        let text = String::from_utf8(out)?;
+        Obj::from(env, text_object).set_str("text", text.as_str())?;
+    } else {
+        let path: String = env.get_string(path)?.into();
+        let mut out = BufWriter::new(File::create(path)?);
+        render(env, &mut out)?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+/// Read a Java `ListingOptions` object.
+fn read_listing_options(env: &mut JNIEnv, options: &JObject) -> Result<ListingOptions> {
+    Ok(ListingOptions {
+        byte_grouping: usize::try_from(env.get_field(options, "byteGrouping", "I")?.i()?)?,
+        address_width: usize::try_from(env.get_field(options, "addressWidth", "I")?.i()?)?,
+        comments: env.get_field(options, "comments", "Z")?.z()?,
+        ..ListingOptions::default()
+    })
+}
+
+pub fn disassemble_recursive<'local>(
+    env: &mut JNIEnv<'local>,
+    this: JObject<'local>,
+    result_object: JObject<'local>,
+    data: JByteArray<'local>,
+    address: jlong,
+    entries: JLongArray<'local>,
+) -> Result<()> {
+    let code = read_bytes(env, &data)?;
+    let entries = read_addresses(env, &entries)?;
+
+    let ctx = CapstoneContext::get(env, &this)?;
+    if !decode::is_supported(&ctx.mode) {
+        return Err(format!("recursive disassembly is not supported for {}", ctx.mode).into());
+    }
+
+    let capstone = ctx.capstone.lock().unwrap();
+    let image = Image {
+        code: &code,
+        address: address as u64,
+    };
+    let runs = disassemble_reachable(&capstone, &ctx.mode, &image, &entries)?;
+    let instructions: Vec<&Insn> = runs.iter().flat_map(|run| &**run).collect();
+
+    let mut output = CapstoneOutput::new(env, &ctx.mode, &capstone, &result_object);
+    output.copy_instructions(&instructions, &image)
+}
+
+/// Disassemble the instructions reachable from the given entry points, as runs of
+/// contiguous instructions ordered by address.
+fn disassemble_reachable<'cs>(
+    capstone: &'cs Capstone,
+    mode: &CapstoneMode,
+    image: &Image,
+    entries: &[u64],
+) -> Result<Vec<Instructions<'cs>>> {
+    let decoded = traversal::traverse(capstone, mode, image, entries);
+
+    // Disassemble each run of contiguous reachable instructions in one go
+    let mut runs: Vec<Instructions> = Vec::new();
+    for (start, count) in traversal::runs(&decoded) {
+        let code = image
+            .tail(start)
+            .ok_or("reachable instruction outside of image")?;
+        runs.push(
+            capstone
+                .disasm_count(code, start, count)
+                .map_err(|e| e.to_string())?,
+        );
+    }
+    Ok(runs)
+}
+
+pub fn build_cfg<'local>(
+    env: &mut JNIEnv<'local>,
+    this: JObject<'local>,
+    graph_object: JObject<'local>,
+    data: JByteArray<'local>,
+    address: jlong,
+    entries: JLongArray<'local>,
+) -> Result<()> {
+    let code = read_bytes(env, &data)?;
+    let entries = read_addresses(env, &entries)?;
+
+    let ctx = CapstoneContext::get(env, &this)?;
+    if !decode::is_supported(&ctx.mode) {
+        return Err(format!("control flow analysis is not supported for {}", ctx.mode).into());
+    }
+
+    let capstone = ctx.capstone.lock().unwrap();
+    let image = Image {
+        code: &code,
+        address: address as u64,
+    };
+    let graph = cfg::build(&capstone, &ctx.mode, &image, &entries);
+
+    graph::write_graph(env, &graph_object, &graph)?;
+    Ok(())
+}
+
+pub fn find_functions<'local>(
+    env: &mut JNIEnv<'local>,
+    this: JObject<'local>,
+    table_object: JObject<'local>,
+    data: JByteArray<'local>,
+    address: jlong,
+    entries: JLongArray<'local>,
+) -> Result<()> {
+    let code = read_bytes(env, &data)?;
+    let entries = read_addresses(env, &entries)?;
+
+    let ctx = CapstoneContext::get(env, &this)?;
+    if !decode::is_supported(&ctx.mode) {
+        return Err(format!("function detection is not supported for {}", ctx.mode).into());
+    }
+
+    let capstone = ctx.capstone.lock().unwrap();
+    let image = Image {
+        code: &code,
+        address: address as u64,
+    };
+    let functions = functions::find(&capstone, &ctx.mode, &image, &entries)?;
+
+    function_table::write_functions(env, &table_object, &functions)?;
+    Ok(())
+}
+
+pub fn build_xrefs<'local>(
+    env: &mut JNIEnv<'local>,
+    this: JObject<'local>,
+    index_object: JObject<'local>,
+    data: JByteArray<'local>,
+    address: jlong,
+    entries: JLongArray<'local>,
+) -> Result<()> {
+    let code = read_bytes(env, &data)?;
+    let entries = read_addresses(env, &entries)?;
+
+    let ctx = CapstoneContext::get(env, &this)?;
+    if !decode::is_supported(&ctx.mode) {
+        return Err(format!("cross-references are not supported for {}", ctx.mode).into());
+    }
+
+    let capstone = ctx.capstone.lock().unwrap();
+    let image = Image {
+        code: &code,
+        address: address as u64,
+    };
+
+    // Without entry points the whole buffer is treated as code
+    let runs = if entries.is_empty() {
+        vec![capstone
+            .disasm_all(&code, address as u64)
+            .map_err(|e| e.to_string())?]
+    } else {
+        disassemble_reachable(&capstone, &ctx.mode, &image, &entries)?
+    };
+    let instructions: Vec<&Insn> = runs.iter().flat_map(|run| &**run).collect();
+    let index = xref::build(&ctx.mode, &capstone, &instructions, &image);
+
+    xref_index::write_xrefs(env, &index_object, &index)?;
+    Ok(())
+}
+
+pub fn load_names<'local>(
+    env: &mut JNIEnv<'local>,
+    this: JObject<'local>,
+    table_object: JObject<'local>,
+) -> Result<()> {
+    let ctx = CapstoneContext::get(env, &this)?;
+    let names = {
+        let capstone = ctx.capstone.lock().unwrap();
+        name_table::collect(&capstone, &ctx.mode)
+    };
+
+    name_table::write_names(env, &table_object, &names)?;
+    Ok(())
+}
+
+pub fn load_registers<'local>(
+    env: &mut JNIEnv<'local>,
+    this: JObject<'local>,
+    table_object: JObject<'local>,
+) -> Result<()> {
+    let ctx = CapstoneContext::get(env, &this)?;
+    if registers::count(&ctx.mode) == 0 {
+        return Err(format!("register metadata is not supported for {}", ctx.mode).into());
+    }
+
+    register_table::write_registers(env, &table_object, &ctx.mode)?;
+    Ok(())
 }
 
 pub fn get_insn_name<'local>(
@@ -106,6 +512,18 @@ pub fn get_group_name<'local>(
     Ok(capstone.group_name(InsnGroupId(group_id as InsnGroupIdInt)))
 }
 
+/// Copy the contents of a Java byte array.
+pub(crate) fn read_bytes(env: &mut JNIEnv, data: &JByteArray) -> Result<Vec<u8>> {
+    let result = unsafe { env.get_array_elements(data, ReleaseMode::NoCopyBack)? };
+    Ok(result.iter().map(|b| (*b as u8).bitand(0xff)).collect())
+}
+
+/// Copy the contents of a Java long array as addresses.
+fn read_addresses(env: &mut JNIEnv, data: &JLongArray) -> Result<Vec<u64>> {
+    let result = unsafe { env.get_array_elements(data, ReleaseMode::NoCopyBack)? };
+    Ok(result.iter().map(|a| *a as u64).collect())
+}
+
 pub fn throw(env: &mut JNIEnv, message: &str) {
     env.throw_new("org/native4j/capstone/exception/CapstoneException", message)
         .unwrap();
@@ -8,13 +8,17 @@ use std::fmt::{Display, Formatter};
 use jni::objects::JObject;
 use jni::JNIEnv;
 
-use crate::capstone::mode::CapstoneMode::{ARM32, ARM64};
+use crate::capstone::mode::CapstoneMode::{Thumb, ARM32, ARM64, X86_32, X86_64};
 use crate::obj::obj;
+use crate::util::JResult;
 
 #[derive(Debug)]
 pub enum CapstoneMode {
     ARM32 = 0,
     ARM64 = 1,
+    X86_32 = 2,
+    X86_64 = 3,
+    Thumb = 4,
 }
 
 impl CapstoneMode {
@@ -23,9 +27,33 @@ impl CapstoneMode {
         match ord {
             Some(0) => Some(ARM32),
             Some(1) => Some(ARM64),
+            Some(2) => Some(X86_32),
+            Some(3) => Some(X86_64),
+            Some(4) => Some(Thumb),
             _ => None,
         }
     }
+
+    /// Get the ordinal of the matching Java enum constant.
+    pub fn ordinal(&self) -> usize {
+        match self {
+            ARM32 => 0,
+            ARM64 => 1,
+            X86_32 => 2,
+            X86_64 => 3,
+            Thumb => 4,
+        }
+    }
+
+    /// Get the matching Java enum constant.
+    pub fn to_object<'jni>(&self, env: &mut JNIEnv<'jni>) -> JResult<JObject<'jni>> {
+        env.get_static_field(
+            "org/native4j/capstone/CapstoneMode",
+            self.to_string(),
+            "Lorg/native4j/capstone/CapstoneMode;",
+        )?
+        .l()
+    }
 }
 
 impl Display for CapstoneMode {
@@ -33,6 +61,9 @@ impl Display for CapstoneMode {
         match self {
             ARM32 => f.write_str("ARM32"),
             ARM64 => f.write_str("ARM64"),
+            X86_32 => f.write_str("X86_32"),
+            X86_64 => f.write_str("X86_64"),
+            Thumb => f.write_str("THUMB"),
         }
     }
 }
@@ -0,0 +1,123 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use capstone::arch::arm::{ArmInsn, ArmInsnGroup, ArmReg};
+use capstone::arch::arm64::{Arm64Insn, Arm64InsnGroup, Arm64Reg};
+use capstone::arch::x86::{X86Insn, X86InsnGroup, X86Reg};
+use capstone::{Capstone, InsnGroupId, InsnGroupIdInt, InsnId, InsnIdInt, RegId, RegIdInt};
+use jni::objects::JObject;
+use jni::JNIEnv;
+
+use crate::capstone::mode::CapstoneMode;
+use crate::obj::obj::Obj;
+use crate::util::JResult;
+use crate::writer::layout::{ClassLayout, Member};
+
+const TABLE_CLASS: &str = "org/native4j/capstone/insn/NameTable";
+
+/// The names of the instructions, registers and groups of a mode, indexed by id.
+pub struct Names {
+    pub instructions: Vec<Option<String>>,
+    pub registers: Vec<Option<String>>,
+    pub groups: Vec<Option<String>>,
+}
+
+/// Get the number of instruction, register and group ids of a mode.
+fn id_counts(mode: &CapstoneMode) -> (u32, u32, u32) {
+    match mode {
+        CapstoneMode::ARM32 | CapstoneMode::Thumb => (
+            ArmInsn::ARM_INS_ENDING as u32,
+            ArmReg::ARM_REG_ENDING,
+            ArmInsnGroup::ARM_GRP_ENDING,
+        ),
+        CapstoneMode::ARM64 => (
+            Arm64Insn::ARM64_INS_ENDING as u32,
+            Arm64Reg::ARM64_REG_ENDING,
+            Arm64InsnGroup::ARM64_GRP_ENDING,
+        ),
+        CapstoneMode::X86_32 | CapstoneMode::X86_64 => (
+            X86Insn::X86_INS_ENDING as u32,
+            X86Reg::X86_REG_ENDING,
+            X86InsnGroup::X86_GRP_ENDING,
+        ),
+    }
+}
+
+/// Collect the names of every id of the given mode.
+pub fn collect(capstone: &Capstone, mode: &CapstoneMode) -> Names {
+    let (instructions, registers, groups) = id_counts(mode);
+    Names {
+        instructions: (0..instructions)
+            .map(|id| capstone.insn_name(InsnId(id as InsnIdInt)))
+            .collect(),
+        registers: (0..registers)
+            .map(|id| capstone.reg_name(RegId(id as RegIdInt)))
+            .collect(),
+        groups: (0..groups)
+            .map(|id| capstone.group_name(InsnGroupId(id as InsnGroupIdInt)))
+            .collect(),
+    }
+}
+
+/// Get the layout of the Java class written by `write_names`.
+pub fn get_layout() -> Vec<ClassLayout<'static>> {
+    vec![ClassLayout::new(
+        TABLE_CLASS,
+        &[&[
+            Member::Field("instructions", "[Ljava/lang/String;"),
+            Member::Field("registers", "[Ljava/lang/String;"),
+            Member::Field("groups", "[Ljava/lang/String;"),
+        ]],
+    )]
+}
+
+/// Write the names to a Java `NameTable` object.
+pub fn write_names<'jni>(
+    env: &mut JNIEnv<'jni>,
+    table_object: &JObject<'jni>,
+    names: &Names,
+) -> JResult<()> {
+    let mut dst = Obj::from(env, table_object);
+    for (field, names) in [
+        ("instructions", &names.instructions),
+        ("registers", &names.registers),
+        ("groups", &names.groups),
+    ] {
+        let names: Vec<Option<&str>> = names.iter().map(|name| name.as_deref()).collect();
+        dst.set_str_array(field, &names)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capstone::create;
+
+    #[test]
+    fn collects_names_by_id() {
+        let mode = CapstoneMode::ARM64;
+        let capstone = create(&mode).unwrap();
+        let names = collect(&capstone, &mode);
+
+        assert_eq!(
+            names.instructions.len(),
+            Arm64Insn::ARM64_INS_ENDING as usize
+        );
+        assert_eq!(names.instructions[0], None);
+        assert_eq!(
+            names.instructions[Arm64Insn::ARM64_INS_ADD as usize].as_deref(),
+            Some("add")
+        );
+        assert_eq!(
+            names.registers[Arm64Reg::ARM64_REG_SP as usize].as_deref(),
+            Some("sp")
+        );
+        assert_eq!(
+            names.groups[Arm64InsnGroup::ARM64_GRP_JUMP as usize].as_deref(),
+            Some("jump")
+        );
+    }
+}
@@ -3,25 +3,49 @@
  *
  * Use of this source code is governed by the MIT license found in the LICENSE file.
  */
-use capstone::{Capstone, Instructions};
+use capstone::{Capstone, Insn};
 use jni::objects::{JObject, JObjectArray, JValue};
 use jni::signature::ReturnType;
 use jni::sys::jsize;
 use jni::JNIEnv;
 use std::sync::MutexGuard;
 
+use crate::analysis::{decode, jumptable, pcrel, Image};
 use crate::capstone::mode::CapstoneMode;
 use crate::obj::obj::Obj;
 use crate::util::JResult;
 use crate::writer;
+use crate::writer::layout::{ClassLayout, Member};
 
+const RESULT_CLASS: &str = "Lorg/native4j/capstone/insn/CapstoneResult;";
 const BASE_INSTRUCTION_CLASS: &str = "Lorg/native4j/capstone/insn/CsInsn;";
 
+/// Get the layout of the Java classes written when disassembling in the given mode.
+pub fn get_layout(mode: &CapstoneMode) -> Vec<ClassLayout<'static>> {
+    let writer = writer::create_writer(mode);
+    let mut layouts = vec![
+        ClassLayout::new(
+            RESULT_CLASS,
+            &[&[
+                Member::Field("instructionCount", "I"),
+                Member::Field("instructions", "[Lorg/native4j/capstone/insn/CsInsn;"),
+            ]],
+        ),
+        ClassLayout::new(
+            writer.get_instruction_class(),
+            &[&[Member::Constructor("()V")]],
+        ),
+    ];
+    layouts.extend(writer.get_layout());
+    layouts
+}
+
 pub struct CapstoneOutput<'jni, 'a> {
     env: &'a mut JNIEnv<'jni>,
     mode: &'a CapstoneMode,
     capstone: &'a MutexGuard<'a, Capstone>,
     result_object: &'a JObject<'jni>,
+    symbolized_operands: Option<&'a [String]>,
 }
 
 impl<'jni, 'a> CapstoneOutput<'jni, 'a> {
@@ -36,14 +60,35 @@ impl<'jni, 'a> CapstoneOutput<'jni, 'a> {
             mode,
             capstone,
             result_object,
+            symbolized_operands: None,
         }
     }
 
+    /// Also write the given symbolized operand strings, index-aligned with the
+    /// instructions.
+    pub fn with_symbolized_operands(mut self, operands: &'a [String]) -> CapstoneOutput<'jni, 'a> {
+        self.symbolized_operands = Some(operands);
+        self
+    }
+
     pub fn copy_instructions(
         &mut self,
-        instructions: Instructions,
+        instructions: &[&Insn],
+        image: &Image,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let count = instructions.len();
+        let references = pcrel::resolve(self.mode, self.capstone, instructions, image);
+        let decoded = decode::classify_all(self.capstone, self.mode, image, instructions);
+        let jump_tables = references.as_deref().map(|references| {
+            jumptable::resolve(
+                self.mode,
+                self.capstone,
+                instructions,
+                references,
+                &decoded,
+                image,
+            )
+        });
 
         let mut result = Obj::from(self.env, self.result_object);
         result.set_int("instructionCount", count as i32)?;
@@ -79,12 +124,18 @@ impl<'jni, 'a> CapstoneOutput<'jni, 'a> {
                 .into());
             }
 
-            writer.write(
-                self.env,
-                &insn_object,
-                instructions.get(i).unwrap(),
-                self.capstone,
-            )?;
+            writer.write(self.env, &insn_object, instructions[i], self.capstone)?;
+
+            if let Some(ref references) = references {
+                writer.write_reference(self.env, &insn_object, references[i].as_ref())?;
+            }
+            if let Some(ref jump_tables) = jump_tables {
+                writer.write_jump_table(self.env, &insn_object, jump_tables[i].as_ref())?;
+            }
+            let operand = self
+                .symbolized_operands
+                .map(|operands| operands[i].as_str());
+            writer.write_symbolized_operand(self.env, &insn_object, operand)?;
         }
 
         Ok(())
@@ -0,0 +1,96 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use capstone::{RegId, RegIdInt};
+use jni::objects::JObject;
+use jni::JNIEnv;
+
+use crate::analysis::registers;
+use crate::capstone::mode::CapstoneMode;
+use crate::obj::obj::Obj;
+use crate::util::JResult;
+use crate::writer::layout::{ClassLayout, Member};
+
+const REGISTER_CLASS: &str = "Lorg/native4j/capstone/insn/RegisterInfo;";
+const TABLE_CLASS: &str = "org/native4j/capstone/insn/RegisterTable";
+
+/// Get the layout of the Java classes written by `write_registers`.
+pub fn get_layout() -> Vec<ClassLayout<'static>> {
+    vec![
+        ClassLayout::new(
+            REGISTER_CLASS,
+            &[&[
+                Member::Constructor("()V"),
+                Member::Field("id", "I"),
+                Member::Field("size", "I"),
+                Member::Field("registerClass", "B"),
+                Member::Field("parent", "I"),
+                Member::Field("fullRegister", "I"),
+                Member::Field("subRegisters", "[I"),
+                Member::Field("isSimd", "Z"),
+                Member::Field("isFlags", "Z"),
+            ]],
+        ),
+        ClassLayout::new(
+            TABLE_CLASS,
+            &[&[Member::Field(
+                "registers",
+                "[Lorg/native4j/capstone/insn/RegisterInfo;",
+            )]],
+        ),
+    ]
+}
+
+/// Write the metadata of every register of a mode to a Java `RegisterTable` object.
+/// Invalid ids are left null.
+pub fn write_registers<'jni>(
+    env: &mut JNIEnv<'jni>,
+    table_object: &JObject<'jni>,
+    mode: &CapstoneMode,
+) -> JResult<()> {
+    // Fill the array as we go so only one register object is alive at a time
+    let registers = {
+        let array = env.new_object_array(
+            registers::count(mode) as i32,
+            REGISTER_CLASS,
+            JObject::null(),
+        )?;
+        env.auto_local(array)
+    };
+    for id in 0..registers::count(mode) {
+        let reg = RegId(id as RegIdInt);
+        let Some(info) = registers::info(mode, reg) else {
+            continue;
+        };
+
+        let object = {
+            let obj = env.new_object(REGISTER_CLASS, "()V", &[])?;
+            env.auto_local(obj)
+        };
+        let sub_registers: Vec<i32> = registers::sub_registers(mode, reg)
+            .iter()
+            .map(|sub| sub.0 as i32)
+            .collect();
+
+        let mut dst = Obj::from(env, &object);
+        dst.set_int("id", info.id as i32)?;
+        dst.set_int("size", info.size as i32)?;
+        dst.set_byte("registerClass", info.class as i8)?;
+        dst.set_int("parent", info.parent.unwrap_or(0) as i32)?;
+        dst.set_int("fullRegister", registers::full_register(mode, reg).0 as i32)?;
+        dst.set_int_array("subRegisters", &sub_registers)?;
+        dst.set_bool("isSimd", info.is_simd())?;
+        dst.set_bool("isFlags", info.is_flags)?;
+
+        env.set_object_array_element(&registers, id as i32, &object)?;
+    }
+
+    let mut dst = Obj::from(env, table_object);
+    dst.set_object(
+        "registers",
+        &format!("[{}", REGISTER_CLASS),
+        registers.as_ref(),
+    )
+}
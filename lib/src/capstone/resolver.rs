@@ -0,0 +1,78 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use std::collections::{BTreeMap, BTreeSet};
+
+use jni::objects::{JLongArray, JObject, JObjectArray, JString, JValue};
+use jni::JNIEnv;
+
+use crate::util::JResult;
+
+const SYMBOL_TABLE_CLASS: &str = "org/native4j/capstone/symbol/SymbolTable";
+
+/// Call a Java `SymbolResolver` for the given address.
+fn call_resolver(env: &mut JNIEnv, resolver: &JObject, address: u64) -> JResult<Option<String>> {
+    let name = env.call_method(
+        resolver,
+        "resolve",
+        "(J)Ljava/lang/String;",
+        &[JValue::Long(address as i64)],
+    )?;
+    let name = env.auto_local(JString::from(name.l()?));
+    if name.is_null() {
+        return Ok(None);
+    }
+    let name: String = env.get_string(&name)?.into();
+    Ok(Some(name))
+}
+
+/// Copy the symbols of a Java `SymbolTable` so they can be resolved without callbacks.
+fn read_symbol_table(env: &mut JNIEnv, table: &JObject) -> JResult<BTreeMap<u64, String>> {
+    let addresses = {
+        let array = env.get_field(table, "addresses", "[J")?.l()?;
+        env.auto_local(JLongArray::from(array))
+    };
+    let names = {
+        let array = env.get_field(table, "names", "[Ljava/lang/String;")?.l()?;
+        env.auto_local(JObjectArray::from(array))
+    };
+
+    let count = env.get_array_length(&*addresses)?;
+    let mut buffer = vec![0i64; count as usize];
+    env.get_long_array_region(&*addresses, 0, &mut buffer)?;
+
+    let mut symbols = BTreeMap::new();
+    for (i, address) in buffer.into_iter().enumerate() {
+        let name = {
+            let name = env.get_object_array_element(&*names, i as i32)?;
+            env.auto_local(JString::from(name))
+        };
+        if !name.is_null() {
+            symbols.insert(address as u64, env.get_string(&name)?.into());
+        }
+    }
+    Ok(symbols)
+}
+
+/// Resolve the given addresses with a Java `SymbolResolver`. Symbol tables are copied,
+/// any other resolver is called back for each address. Callers must not hold the
+/// Capstone lock, as the resolver may call back into the same instance.
+pub fn resolve_all<'jni>(
+    env: &mut JNIEnv<'jni>,
+    resolver_object: &JObject<'jni>,
+    addresses: &BTreeSet<u64>,
+) -> JResult<BTreeMap<u64, String>> {
+    if env.is_instance_of(resolver_object, SYMBOL_TABLE_CLASS)? {
+        return read_symbol_table(env, resolver_object);
+    }
+
+    let mut symbols = BTreeMap::new();
+    for address in addresses {
+        if let Some(name) = call_resolver(env, resolver_object, *address)? {
+            symbols.insert(*address, name);
+        }
+    }
+    Ok(symbols)
+}
@@ -0,0 +1,57 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use jni::objects::{JObject, JValue};
+use jni::JNIEnv;
+
+use crate::analysis::xref::XrefIndex;
+use crate::obj::obj::Obj;
+use crate::util::{AutoObject, JResult};
+use crate::writer::layout::{ClassLayout, Member};
+
+const XREF_CLASS: &str = "Lorg/native4j/capstone/analysis/Xref;";
+const INDEX_CLASS: &str = "org/native4j/capstone/analysis/XrefIndex";
+const XREF_ARRAY: &str = "[Lorg/native4j/capstone/analysis/Xref;";
+
+/// Get the layout of the Java classes written by `write_xrefs`.
+pub fn get_layout() -> Vec<ClassLayout<'static>> {
+    vec![
+        ClassLayout::new(XREF_CLASS, &[&[Member::Constructor("(JJB)V")]]),
+        ClassLayout::new(
+            INDEX_CLASS,
+            &[&[
+                Member::Field("byTarget", XREF_ARRAY),
+                Member::Field("bySource", XREF_ARRAY),
+            ]],
+        ),
+    ]
+}
+
+/// Write a cross-reference index to a Java `XrefIndex` object.
+pub fn write_xrefs<'jni>(
+    env: &mut JNIEnv<'jni>,
+    index_object: &JObject<'jni>,
+    index: &XrefIndex,
+) -> JResult<()> {
+    let mut by_target: Vec<AutoObject<'jni>> = Vec::with_capacity(index.by_target.len());
+    for xref in &index.by_target {
+        let obj = env.new_object(
+            XREF_CLASS,
+            "(JJB)V",
+            &[
+                JValue::Long(xref.from as i64),
+                JValue::Long(xref.to as i64),
+                JValue::Byte(xref.kind as i8),
+            ],
+        )?;
+        by_target.push(env.auto_local(obj));
+    }
+
+    let by_source: Vec<&AutoObject> = index.by_source.iter().map(|i| &by_target[*i]).collect();
+
+    let mut dst = Obj::from(env, index_object);
+    dst.set_object_array("byTarget", XREF_CLASS, &by_target)?;
+    dst.set_object_array("bySource", XREF_CLASS, &by_source)
+}
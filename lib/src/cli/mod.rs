@@ -0,0 +1,271 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use std::io::{self, BufWriter, Write};
+use std::process::ExitCode;
+
+use capstone::{Endian, Insn, Syntax};
+
+use crate::analysis::Image;
+use crate::capstone::create;
+use crate::capstone::mode::CapstoneMode;
+use crate::format::{json, listing};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+const USAGE: &str = "\
+Usage: cstool [OPTIONS] <MODE> <HEX> [ADDRESS]
+       cstool [OPTIONS] -f <FILE> <MODE> [ADDRESS]
+
+Disassemble hex bytes, e.g. \"fd7bbfa9\" or \"fd 7b bf a9\", or the bytes of a file.
+The address of the first byte is given in hex and defaults to 0.
+
+Modes:
+    arm, thumb, arm64, x86, x64
+
+Options:
+    -f, --file <FILE>      Disassemble the contents of a file
+    -b, --big-endian       Decode big-endian instructions (ARM modes)
+    -s, --syntax <SYNTAX>  Assembler syntax: intel, att or masm (x86 modes)
+    -d, --detail           Show the registers and groups of each instruction
+    -j, --json             Write a JSON document instead of a listing
+    -h, --help             Show this help";
+
+/// Options parsed from the command line.
+struct Args {
+    mode: CapstoneMode,
+    code: Vec<u8>,
+    address: u64,
+    big_endian: bool,
+    syntax: Option<Syntax>,
+    detail: bool,
+    json: bool,
+}
+
+/// Parse a mode name.
+fn parse_mode(name: &str) -> Result<CapstoneMode> {
+    match name.to_ascii_lowercase().as_str() {
+        "arm" | "arm32" => Ok(CapstoneMode::ARM32),
+        "thumb" => Ok(CapstoneMode::Thumb),
+        "arm64" | "aarch64" => Ok(CapstoneMode::ARM64),
+        "x86" | "x32" | "x86_32" => Ok(CapstoneMode::X86_32),
+        "x64" | "x86_64" => Ok(CapstoneMode::X86_64),
+        _ => Err(format!("unknown mode '{}'", name).into()),
+    }
+}
+
+/// Parse an assembler syntax name.
+fn parse_syntax(name: &str) -> Result<Syntax> {
+    match name.to_ascii_lowercase().as_str() {
+        "intel" => Ok(Syntax::Intel),
+        "att" => Ok(Syntax::Att),
+        "masm" => Ok(Syntax::Masm),
+        _ => Err(format!("unknown syntax '{}'", name).into()),
+    }
+}
+
+/// Parse hex bytes. Whitespace and commas separate tokens, which may have a `0x`
+/// prefix. `\x` escapes are ignored.
+fn parse_hex(text: &str) -> Result<Vec<u8>> {
+    let digits: String = text
+        .replace("\\x", " ")
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .map(|token| token.strip_prefix("0x").unwrap_or(token))
+        .collect();
+    if let Some(c) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(format!("invalid hex digit '{}'", c).into());
+    }
+    if !digits.len().is_multiple_of(2) {
+        return Err("odd number of hex digits".into());
+    }
+
+    // Every digit is ASCII, so each pair is a valid slice
+    Ok((0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap())
+        .collect())
+}
+
+/// Parse a hex address, with or without a `0x` prefix.
+fn parse_address(text: &str) -> Result<u64> {
+    let digits = text.strip_prefix("0x").unwrap_or(text);
+    u64::from_str_radix(digits, 16).map_err(|_| format!("invalid address '{}'", text).into())
+}
+
+/// Parse the command line. Returns `None` if help was requested.
+fn parse_args(args: &[String]) -> Result<Option<Args>> {
+    let mut file = None;
+    let mut big_endian = false;
+    let mut syntax = None;
+    let mut detail = false;
+    let mut json = false;
+    let mut positional = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-f" | "--file" => file = Some(args.next().ok_or("missing file")?),
+            "-b" | "--big-endian" => big_endian = true,
+            "-s" | "--syntax" => syntax = Some(parse_syntax(args.next().ok_or("missing syntax")?)?),
+            "-d" | "--detail" => detail = true,
+            "-j" | "--json" => json = true,
+            _ if arg.starts_with('-') && arg.len() > 1 => {
+                return Err(format!("unknown option '{}'", arg).into())
+            }
+            _ => positional.push(arg.as_str()),
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    let mode = parse_mode(positional.next().ok_or("missing mode")?)?;
+    let code = match file {
+        Some(file) => std::fs::read(file).map_err(|e| format!("{}: {}", file, e))?,
+        None => parse_hex(positional.next().ok_or("missing hex bytes")?)?,
+    };
+    let address = positional.next().map(parse_address).transpose()?;
+    if let Some(arg) = positional.next() {
+        return Err(format!("unexpected argument '{}'", arg).into());
+    }
+
+    Ok(Some(Args {
+        mode,
+        code,
+        address: address.unwrap_or(0),
+        big_endian,
+        syntax,
+        detail,
+        json,
+    }))
+}
+
+/// Disassemble according to the parsed arguments.
+fn disassemble<W: Write>(args: &Args, out: W) -> Result<W> {
+    let mut capstone = create(&args.mode).map_err(|e| e.to_string())?;
+    if args.big_endian {
+        capstone
+            .set_endian(Endian::Big)
+            .map_err(|e| e.to_string())?;
+    }
+    if let Some(syntax) = args.syntax {
+        capstone.set_syntax(syntax).map_err(|e| e.to_string())?;
+    }
+
+    let image = Image {
+        code: &args.code,
+        address: args.address,
+    };
+
+    if args.json {
+        let instructions = capstone
+            .disasm_all(&args.code, args.address)
+            .map_err(|e| e.to_string())?;
+        let instructions: Vec<&Insn> = (*instructions).iter().collect();
+        let mut out = json::write_json(out, &args.mode, &capstone, &instructions, &image)?;
+        writeln!(out)?;
+        Ok(out)
+    } else {
+        let options = listing::ListingOptions {
+            detail: args.detail,
+            big_endian: args.big_endian,
+            ..Default::default()
+        };
+        let out = listing::write_listing(out, &args.mode, &options, None, &capstone, &image)?;
+        Ok(out)
+    }
+}
+
+/// Entry point of the `cstool` binary, with the arguments following the program name.
+pub fn main(args: &[String]) -> ExitCode {
+    let args = match parse_args(args) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let out = BufWriter::new(io::stdout().lock());
+    match disassemble(&args, out).and_then(|mut out| Ok(out.flush()?)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Result<Option<Args>> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        parse_args(&args)
+    }
+
+    #[test]
+    fn parses_hex() {
+        let expected = vec![0xfd, 0x7b, 0xbf, 0xa9];
+        assert_eq!(parse_hex("fd7bbfa9").unwrap(), expected);
+        assert_eq!(parse_hex("fd 7b BF a9").unwrap(), expected);
+        assert_eq!(parse_hex("0xfd,0x7b,0xbf,0xa9").unwrap(), expected);
+        assert_eq!(parse_hex("\\xfd\\x7b\\xbf\\xa9").unwrap(), expected);
+        assert!(parse_hex("fd7").is_err());
+        assert!(parse_hex("fd0x7b").is_err());
+        assert!(parse_hex("fg").is_err());
+        assert!(parse_hex("é1").is_err());
+    }
+
+    #[test]
+    fn parses_addresses() {
+        assert_eq!(parse_address("1000").unwrap(), 0x1000);
+        assert_eq!(parse_address("0x1000").unwrap(), 0x1000);
+        assert!(parse_address("x").is_err());
+    }
+
+    #[test]
+    fn parses_args() {
+        let parsed = args(&["-d", "-s", "att", "x64", "c3", "0x400000"])
+            .unwrap()
+            .unwrap();
+        assert!(matches!(parsed.mode, CapstoneMode::X86_64));
+        assert_eq!(parsed.code, vec![0xc3]);
+        assert_eq!(parsed.address, 0x400000);
+        assert!(parsed.detail && !parsed.json && !parsed.big_endian);
+        assert_eq!(parsed.syntax, Some(Syntax::Att));
+
+        assert!(args(&["--help"]).unwrap().is_none());
+        assert!(args(&["arm64"]).is_err());
+        assert!(args(&["mips", "00"]).is_err());
+        assert!(args(&["-x", "arm64", "00"]).is_err());
+        assert!(args(&["arm64", "00", "0", "extra"]).is_err());
+    }
+
+    #[test]
+    fn disassembles() {
+        let parsed = args(&["thumb", "7047", "8000"]).unwrap().unwrap();
+        let out = disassemble(&parsed, Vec::new()).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "    8000:\t4770 \tbx\tlr\n"
+        );
+
+        let parsed = args(&["-b", "arm64", "d65f03c0"]).unwrap().unwrap();
+        let out = disassemble(&parsed, Vec::new()).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "       0:\td65f03c0 \tret\n"
+        );
+
+        // The code would extend past the end of the address space
+        let parsed = args(&["x64", "c3c3", "ffffffffffffffff"]).unwrap().unwrap();
+        assert!(disassemble(&parsed, Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,369 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+//! Generator for the Java constant classes.
+//!
+//! Instruction, register and group IDs are taken from the enums of the capstone crate and
+//! named after capstone's own names for them. Operand and shift type codes are taken from
+//! the tables the writers use, so the Java constants cannot drift from either.
+
+use std::fmt::{Debug, Write as _};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use capstone::arch::arm::{ArmCC, ArmInsn, ArmInsnGroup, ArmReg};
+use capstone::arch::arm64::{Arm64CC, Arm64Insn, Arm64InsnGroup, Arm64Reg};
+use capstone::{Capstone, InsnGroupId, InsnGroupIdInt, InsnId, InsnIdInt, RegId, RegIdInt};
+
+use crate::analysis::flags;
+use crate::capstone::create;
+use crate::capstone::mode::CapstoneMode;
+use crate::operand::{self, arm32, arm64};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+const USAGE: &str = "\
+Usage: gen_constants [OPTIONS] [JAVA_SOURCES]
+
+Generate the Java constant classes into the given Java source directory, by default
+the sources of the native4j-capstone project.
+
+Options:
+    -c, --check  Only check that the classes are up to date
+    -h, --help   Show this help";
+
+const HEADER: &str = "\
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+";
+
+/// A section of constants, introduced by a comment.
+struct Section {
+    title: &'static str,
+    constants: Vec<(String, i64)>,
+}
+
+/// A Java class of `int` constants.
+struct ConstantClass {
+    package: &'static str,
+    name: &'static str,
+    doc: &'static str,
+    sections: Vec<Section>,
+}
+
+impl ConstantClass {
+    /// Get the path of the source file below a source directory.
+    fn path(&self, sources: &Path) -> PathBuf {
+        sources
+            .join(self.package.replace('.', "/"))
+            .join(format!("{}.java", self.name))
+    }
+
+    /// Render the Java source of the class.
+    fn render(&self) -> String {
+        let mut out = String::from(HEADER);
+        writeln!(out, "package {};\n", self.package).unwrap();
+        writeln!(
+            out,
+            "/**\n * {}\n *\n * Generated by gen_constants, do not edit.\n */",
+            self.doc
+        )
+        .unwrap();
+        writeln!(out, "@SuppressWarnings(\"ALL\")").unwrap();
+        writeln!(out, "public final class {} {{", self.name).unwrap();
+        for section in &self.sections {
+            writeln!(out, "    /* {} */", section.title).unwrap();
+            for (name, value) in &section.constants {
+                writeln!(out, "    public static final int {} = {};", name, value).unwrap();
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Create a section from the codes of a writer table.
+fn codes<T: Into<i64> + Copy>(title: &'static str, table: &[(&str, T)]) -> Section {
+    Section {
+        title,
+        constants: table
+            .iter()
+            .map(|(name, code)| (name.to_string(), (*code).into()))
+            .collect(),
+    }
+}
+
+/// Create a section from enum variants, named as they are declared.
+fn variants<T: Debug + Copy>(title: &'static str, values: &[T], code: fn(T) -> i64) -> Section {
+    Section {
+        title,
+        constants: values
+            .iter()
+            .map(|value| (format!("{:?}", value), code(*value)))
+            .collect(),
+    }
+}
+
+/// Create a section from the IDs below `ending`, named after capstone's name for each ID.
+/// IDs without a name are skipped. Each alias is added after the ID it names, for enum
+/// names that differ from capstone's name, e.g. `ARM_REG_R9` for `sb`.
+fn named_ids(
+    title: &'static str,
+    prefix: &str,
+    ending: u32,
+    aliases: &[(&str, u32)],
+    name: impl Fn(u32) -> Option<String>,
+) -> Section {
+    let mut constants = vec![(format!("{}_INVALID", prefix), 0)];
+    for id in 1..ending {
+        if let Some(name) = name(id) {
+            constants.push((constant_name(prefix, &name), id as i64));
+        }
+        for (alias, _) in aliases.iter().filter(|(_, alias_id)| *alias_id == id) {
+            constants.push((alias.to_string(), id as i64));
+        }
+    }
+    constants.push((format!("{}_ENDING", prefix), ending as i64));
+    Section { title, constants }
+}
+
+/// Get the Java constant name for a capstone name, e.g. `ARM_INS_VADD` for `vadd`.
+fn constant_name(prefix: &str, name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            c if c.is_ascii_alphanumeric() => c.to_ascii_uppercase(),
+            _ => '_',
+        })
+        .collect();
+    format!("{}_{}", prefix, name)
+}
+
+fn insn_name(capstone: &Capstone, id: u32) -> Option<String> {
+    capstone.insn_name(InsnId(id as InsnIdInt))
+}
+
+fn reg_name(capstone: &Capstone, id: u32) -> Option<String> {
+    capstone.reg_name(RegId(id as RegIdInt))
+}
+
+fn group_name(capstone: &Capstone, id: u32) -> Option<String> {
+    capstone.group_name(InsnGroupId(id as InsnGroupIdInt))
+}
+
+/// Generate `ArmConstants`.
+fn arm_constants() -> Result<ConstantClass> {
+    let arm = create(&CapstoneMode::ARM32).map_err(|e| e.to_string())?;
+    let arm64 = create(&CapstoneMode::ARM64).map_err(|e| e.to_string())?;
+
+    Ok(ConstantClass {
+        package: "org.native4j.capstone.insn.arm",
+        name: "ArmConstants",
+        doc: "ARM capstone constants.",
+        sections: vec![
+            codes("Condition flags", flags::FLAGS),
+            codes("Memory addressing modes", operand::ADDRESSING_MODES),
+            codes("ARM32 operand types", arm32::OPERAND_TYPES),
+            variants(
+                "ARM32 condition types",
+                &[
+                    ArmCC::ARM_CC_INVALID,
+                    ArmCC::ARM_CC_EQ,
+                    ArmCC::ARM_CC_NE,
+                    ArmCC::ARM_CC_HS,
+                    ArmCC::ARM_CC_LO,
+                    ArmCC::ARM_CC_MI,
+                    ArmCC::ARM_CC_PL,
+                    ArmCC::ARM_CC_VS,
+                    ArmCC::ARM_CC_VC,
+                    ArmCC::ARM_CC_HI,
+                    ArmCC::ARM_CC_LS,
+                    ArmCC::ARM_CC_GE,
+                    ArmCC::ARM_CC_LT,
+                    ArmCC::ARM_CC_GT,
+                    ArmCC::ARM_CC_LE,
+                    ArmCC::ARM_CC_AL,
+                ],
+                |cc| cc as i64,
+            ),
+            codes("ARM32 shift types", arm32::SHIFT_TYPES),
+            named_ids(
+                "ARM32 instruction groups",
+                "ARM_GRP",
+                ArmInsnGroup::ARM_GRP_ENDING,
+                &[],
+                |id| group_name(&arm, id),
+            ),
+            named_ids(
+                "ARM32 registers",
+                "ARM_REG",
+                ArmReg::ARM_REG_ENDING,
+                &[
+                    ("ARM_REG_R9", ArmReg::ARM_REG_R9),
+                    ("ARM_REG_R10", ArmReg::ARM_REG_R10),
+                    ("ARM_REG_R11", ArmReg::ARM_REG_R11),
+                    ("ARM_REG_R12", ArmReg::ARM_REG_R12),
+                    ("ARM_REG_R13", ArmReg::ARM_REG_R13),
+                    ("ARM_REG_R14", ArmReg::ARM_REG_R14),
+                    ("ARM_REG_R15", ArmReg::ARM_REG_R15),
+                ],
+                |id| reg_name(&arm, id),
+            ),
+            named_ids(
+                "ARM32 instructions",
+                "ARM_INS",
+                ArmInsn::ARM_INS_ENDING as u32,
+                &[],
+                |id| insn_name(&arm, id),
+            ),
+            codes("ARM64 operand types", arm64::OPERAND_TYPES),
+            variants(
+                "ARM64 condition types",
+                &[
+                    Arm64CC::ARM64_CC_INVALID,
+                    Arm64CC::ARM64_CC_EQ,
+                    Arm64CC::ARM64_CC_NE,
+                    Arm64CC::ARM64_CC_HS,
+                    Arm64CC::ARM64_CC_LO,
+                    Arm64CC::ARM64_CC_MI,
+                    Arm64CC::ARM64_CC_PL,
+                    Arm64CC::ARM64_CC_VS,
+                    Arm64CC::ARM64_CC_VC,
+                    Arm64CC::ARM64_CC_HI,
+                    Arm64CC::ARM64_CC_LS,
+                    Arm64CC::ARM64_CC_GE,
+                    Arm64CC::ARM64_CC_LT,
+                    Arm64CC::ARM64_CC_GT,
+                    Arm64CC::ARM64_CC_LE,
+                    Arm64CC::ARM64_CC_AL,
+                    Arm64CC::ARM64_CC_NV,
+                ],
+                |cc| cc as i64,
+            ),
+            codes("ARM64 shift types", arm64::SHIFT_TYPES),
+            named_ids(
+                "ARM64 instruction groups",
+                "ARM64_GRP",
+                Arm64InsnGroup::ARM64_GRP_ENDING,
+                &[
+                    ("ARM64_GRP_RET", Arm64InsnGroup::ARM64_GRP_RET),
+                    ("ARM64_GRP_PAC", Arm64InsnGroup::ARM64_GRP_PAC),
+                ],
+                |id| group_name(&arm64, id),
+            ),
+            named_ids(
+                "ARM64 registers",
+                "ARM64_REG",
+                Arm64Reg::ARM64_REG_ENDING,
+                &[
+                    ("ARM64_REG_X29", Arm64Reg::ARM64_REG_X29),
+                    ("ARM64_REG_X30", Arm64Reg::ARM64_REG_X30),
+                ],
+                |id| reg_name(&arm64, id),
+            ),
+            named_ids(
+                "ARM64 instructions",
+                "ARM64_INS",
+                Arm64Insn::ARM64_INS_ENDING as u32,
+                &[],
+                |id| insn_name(&arm64, id),
+            ),
+        ],
+    })
+}
+
+/// Generate all constant classes.
+fn generate() -> Result<Vec<ConstantClass>> {
+    Ok(vec![arm_constants()?])
+}
+
+/// Write the constant classes, or only check them if `check` is set. Returns the paths
+/// of the classes that are out of date.
+fn run(sources: &Path, check: bool) -> Result<Vec<PathBuf>> {
+    let mut outdated = Vec::new();
+    for class in generate()? {
+        let path = class.path(sources);
+        let source = class.render();
+        if std::fs::read_to_string(&path).is_ok_and(|current| current == source) {
+            continue;
+        }
+        if !check {
+            std::fs::write(&path, source).map_err(|e| format!("{}: {}", path.display(), e))?;
+        }
+        outdated.push(path);
+    }
+    Ok(outdated)
+}
+
+/// Get the Java source directory of the native4j-capstone project.
+fn default_sources() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../native4j-capstone/src/main/java")
+}
+
+/// Entry point of the `gen_constants` binary, with the arguments following the program name.
+pub fn main(args: &[String]) -> ExitCode {
+    let mut check = false;
+    let mut sources = None;
+    for arg in args {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            "-c" | "--check" => check = true,
+            _ if arg.starts_with('-') || sources.is_some() => {
+                eprintln!("error: unexpected argument '{}'\n\n{}", arg, USAGE);
+                return ExitCode::from(2);
+            }
+            _ => sources = Some(PathBuf::from(arg)),
+        }
+    }
+
+    let sources = sources.unwrap_or_else(default_sources);
+    match run(&sources, check) {
+        Ok(outdated) if check && !outdated.is_empty() => {
+            for path in outdated {
+                eprintln!("out of date: {}", path.display());
+            }
+            ExitCode::FAILURE
+        }
+        Ok(outdated) => {
+            for path in outdated {
+                println!("wrote {}", path.display());
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_constants() {
+        assert_eq!(constant_name("ARM_INS", "vadd"), "ARM_INS_VADD");
+        assert_eq!(constant_name("ARM64_GRP", "v8_1a"), "ARM64_GRP_V8_1A");
+        assert_eq!(constant_name("X86_INS", "fxsave64"), "X86_INS_FXSAVE64");
+    }
+
+    #[test]
+    fn constants_are_up_to_date() {
+        let outdated = run(&default_sources(), true).unwrap();
+        assert!(
+            outdated.is_empty(),
+            "run `cargo run --bin gen_constants` to regenerate {:?}",
+            outdated
+        );
+    }
+}
@@ -0,0 +1,513 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use std::io::{self, Write};
+
+use capstone::arch::arm::{ArmInsnDetail, ArmOpMem};
+use capstone::arch::arm64::{Arm64InsnDetail, Arm64OpMem};
+use capstone::arch::DetailsArchInsn;
+use capstone::{Capstone, Insn, InsnDetail, InsnId, RegId};
+
+use crate::analysis::flags::{self, FlagEffects};
+use crate::analysis::flow::{self, BranchTarget, FlowInfo};
+use crate::analysis::jumptable::JumpTable;
+use crate::analysis::pcrel::PcRelReference;
+use crate::analysis::{decode, jumptable, pcrel, Image};
+use crate::capstone::mode::CapstoneMode;
+use crate::operand::sysreg::{self, SystemRegister};
+use crate::operand::{arm32, arm64, Addressing, OperandValue};
+use crate::writer::arm;
+
+/// Version of the document layout. Bumped whenever a field is renamed, removed or
+/// changes its meaning; adding fields does not change the version.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A JSON value.
+enum Json {
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Str(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Json::Bool(value)
+    }
+}
+
+impl From<i64> for Json {
+    fn from(value: i64) -> Self {
+        Json::Int(value)
+    }
+}
+
+impl From<u64> for Json {
+    fn from(value: u64) -> Self {
+        Json::UInt(value)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Json::Str(value.to_string())
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Json::Null, Into::into)
+    }
+}
+
+impl Json {
+    fn write<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        match self {
+            Json::Null => out.write_all(b"null"),
+            Json::Bool(value) => write!(out, "{}", value),
+            Json::Int(value) => write!(out, "{}", value),
+            Json::UInt(value) => write!(out, "{}", value),
+            // NaN and infinity have no JSON representation
+            Json::Float(value) if !value.is_finite() => out.write_all(b"null"),
+            Json::Float(value) => write!(out, "{:?}", value),
+            Json::Str(value) => write_string(out, value),
+            Json::Array(values) => {
+                out.write_all(b"[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        out.write_all(b",")?;
+                    }
+                    value.write(out)?;
+                }
+                out.write_all(b"]")
+            }
+            Json::Object(fields) => {
+                out.write_all(b"{")?;
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        out.write_all(b",")?;
+                    }
+                    write_string(out, name)?;
+                    out.write_all(b":")?;
+                    value.write(out)?;
+                }
+                out.write_all(b"}")
+            }
+        }
+    }
+}
+
+/// Write a quoted and escaped JSON string.
+fn write_string<W: Write>(out: &mut W, value: &str) -> io::Result<()> {
+    out.write_all(b"\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => out.write_all(b"\\\"")?,
+            '\\' => out.write_all(b"\\\\")?,
+            '\n' => out.write_all(b"\\n")?,
+            '\r' => out.write_all(b"\\r")?,
+            '\t' => out.write_all(b"\\t")?,
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32)?,
+            c => write!(out, "{}", c)?,
+        }
+    }
+    out.write_all(b"\"")
+}
+
+/// Serializes instructions with the same fields the instruction writers set on the
+/// Java objects, with register and group names resolved.
+struct JsonSerializer<'a> {
+    mode: &'a CapstoneMode,
+    capstone: &'a Capstone,
+}
+
+impl<'a> JsonSerializer<'a> {
+    /// Get the name of a register, `null` for the invalid register.
+    fn reg(&self, reg: RegId) -> Json {
+        if reg.0 == 0 {
+            return Json::Null;
+        }
+        match self.capstone.reg_name(reg) {
+            Some(name) => Json::Str(name),
+            None => Json::Int(reg.0 as i64),
+        }
+    }
+
+    /// Convert an operand value, naming registers.
+    fn value<M>(&self, value: OperandValue<M>, memory: impl FnOnce(M) -> Json) -> Json {
+        match value {
+            OperandValue::None => Json::Null,
+            OperandValue::Reg(reg) => self.reg(reg),
+            OperandValue::Int(v) => (v as i64).into(),
+            OperandValue::Long(v) => v.into(),
+            OperandValue::Byte(v) => (v as i64).into(),
+            OperandValue::Double(v) => Json::Float(v),
+            OperandValue::Mem(mem) => memory(mem),
+        }
+    }
+
+    fn common(&self, detail: &InsnDetail, insn: &Insn) -> Vec<(&'static str, Json)> {
+        let bytes: String = insn.bytes().iter().map(|b| format!("{:02x}", b)).collect();
+
+        let regs = |regs: &[RegId]| Json::Array(regs.iter().map(|r| self.reg(*r)).collect());
+        let groups = detail
+            .groups()
+            .iter()
+            .map(|g| match self.capstone.group_name(*g) {
+                Some(name) => Json::Str(name),
+                None => Json::Int(g.0 as i64),
+            })
+            .collect();
+
+        let mut fields = vec![
+            ("address", insn.address().into()),
+            ("size", (insn.len() as u64).into()),
+            ("bytes", Json::Str(bytes)),
+        ];
+        // Only ARM instructions have a fixed-size encoding word
+        if let CapstoneMode::ARM32 | CapstoneMode::Thumb | CapstoneMode::ARM64 = self.mode {
+            let thumb = matches!(self.mode, CapstoneMode::Thumb);
+            let encoding = arm::encoding(insn.bytes(), thumb);
+            fields.push(("encoding", (encoding as u64).into()));
+        }
+        fields.extend([
+            ("instructionId", (insn.id().0 as u64).into()),
+            ("mnemonic", insn.mnemonic().into()),
+            ("operand", insn.op_str().into()),
+            ("regsRead", regs(detail.regs_read())),
+            ("regsWrite", regs(detail.regs_write())),
+            ("groups", Json::Array(groups)),
+        ]);
+        fields
+    }
+
+    fn flow(&self, flow: &FlowInfo, target: Option<BranchTarget>) -> Vec<(&'static str, Json)> {
+        vec![
+            ("isBranch", flow.is_branch.into()),
+            ("isConditional", flow.is_conditional.into()),
+            ("isCall", flow.is_call.into()),
+            ("isReturn", flow.is_return.into()),
+            ("isIndirect", flow.is_indirect.into()),
+            ("isPcRelative", flow.is_pc_relative.into()),
+            ("isPrivileged", flow.is_privileged.into()),
+            ("isInterrupt", flow.is_interrupt.into()),
+            ("isTerminator", flow.is_terminator.into()),
+            ("branchTarget", target.map(|t| t.address).into()),
+            (
+                "isInterworking",
+                target.is_some_and(|t| t.is_interworking).into(),
+            ),
+        ]
+    }
+
+    fn flags(&self, effects: &FlagEffects) -> Vec<(&'static str, Json)> {
+        vec![
+            ("flagsRead", (effects.read as u64).into()),
+            ("flagsWritten", (effects.written as u64).into()),
+        ]
+    }
+
+    fn analysis(
+        &self,
+        reference: Option<&PcRelReference>,
+        table: Option<&JumpTable>,
+    ) -> Vec<(&'static str, Json)> {
+        let targets = table.map(|t| Json::Array(t.targets.iter().map(|a| (*a).into()).collect()));
+        vec![
+            ("referencedAddress", reference.map(|r| r.address).into()),
+            ("referencedValue", reference.and_then(|r| r.value).into()),
+            ("jumpTableAddress", table.map(|t| t.address).into()),
+            ("jumpTableTargets", targets.unwrap_or(Json::Null)),
+        ]
+    }
+
+    fn system_register(&self, reg: Option<SystemRegister>) -> Json {
+        let Some(reg) = reg else {
+            return Json::Null;
+        };
+        Json::Object(vec![
+            ("coprocessor", (reg.coprocessor as i64).into()),
+            ("op0", (reg.op0 as i64).into()),
+            ("op1", (reg.op1 as i64).into()),
+            ("crn", (reg.crn as i64).into()),
+            ("crm", (reg.crm as i64).into()),
+            ("op2", (reg.op2 as i64).into()),
+            ("name", reg.name.into()),
+        ])
+    }
+
+    fn addressing(&self, addressing: &Addressing) -> Vec<(&'static str, Json)> {
+        vec![
+            ("addressingMode", (addressing.mode as i64).into()),
+            ("baseUpdated", addressing.base_updated().into()),
+            ("postIndexImmediate", addressing.post_index_imm.into()),
+            ("postIndexRegister", self.reg(addressing.post_index_reg)),
+            ("indexShiftType", (addressing.shift_type as i64).into()),
+            ("indexShiftValue", (addressing.shift_value as u64).into()),
+        ]
+    }
+
+    fn arm32_memory(&self, mem: &ArmOpMem, addressing: &Addressing) -> Json {
+        let mut fields = vec![
+            ("base", self.reg(mem.base())),
+            ("index", self.reg(mem.index())),
+            ("scale", (mem.scale() as i64).into()),
+            ("disp", (mem.disp() as i64).into()),
+        ];
+        fields.extend(self.addressing(addressing));
+        fields.push(("isSubtracted", addressing.subtracted.into()));
+        Json::Object(fields)
+    }
+
+    fn arm32(&self, id: InsnId, arch: &ArmInsnDetail) -> Vec<(&'static str, Json)> {
+        let all: Vec<_> = arch.operands().collect();
+        let op_types: Vec<_> = all.iter().map(|op| op.op_type.clone()).collect();
+        let operands = all
+            .iter()
+            .enumerate()
+            .map(|(index, op)| {
+                let (shift_type, shift_value) = arm32::shift(op.shift);
+                let (op_type, value) = arm32::operand_value(&op.op_type);
+                let addressing = arm32::addressing(id, &all, index, arch.writeback());
+                let value = self.value(value, |mem| self.arm32_memory(mem, &addressing));
+                Json::Object(vec![
+                    ("vectorIndex", op.vector_index.map(|i| i as u64).into()),
+                    ("isSubtracted", op.subtracted.into()),
+                    ("shiftType", (shift_type as i64).into()),
+                    ("shiftValue", (shift_value as u64).into()),
+                    ("operandType", (op_type as i64).into()),
+                    ("value", value),
+                    (
+                        "systemRegister",
+                        self.system_register(sysreg::decode_arm32(id, &op_types, index)),
+                    ),
+                ])
+            })
+            .collect();
+
+        vec![
+            ("isUsermode", arch.usermode().into()),
+            ("vectorSize", (arch.vector_size() as i64).into()),
+            ("vectorData", (arch.vector_data() as i64).into()),
+            ("cpsMode", (arch.cps_mode() as i64).into()),
+            ("cpsFlag", (arch.cps_flag() as i64).into()),
+            ("conditionCodes", (arch.cc() as i64).into()),
+            ("updatesFlags", arch.update_flags().into()),
+            ("writebackRequired", arch.writeback().into()),
+            ("memBarrier", (arch.mem_barrier() as i64).into()),
+            ("operands", Json::Array(operands)),
+        ]
+    }
+
+    fn arm64_memory(&self, mem: &Arm64OpMem, addressing: &Addressing) -> Json {
+        let mut fields = vec![
+            ("base", self.reg(mem.base())),
+            ("index", self.reg(mem.index())),
+            ("disp", (mem.disp() as i64).into()),
+        ];
+        fields.extend(self.addressing(addressing));
+        fields.push(("indexExtend", (addressing.extend as i64).into()));
+        Json::Object(fields)
+    }
+
+    fn arm64(&self, id: InsnId, arch: &Arm64InsnDetail) -> Vec<(&'static str, Json)> {
+        let all: Vec<_> = arch.operands().collect();
+        let operands = all
+            .iter()
+            .enumerate()
+            .map(|(index, op)| {
+                let (shift_type, shift_value) = arm64::shift(op.shift);
+                let (op_type, value) = arm64::operand_value(&op.op_type);
+                let addressing = arm64::addressing(&all, index, arch.writeback());
+                let value = self.value(value, |mem| self.arm64_memory(mem, &addressing));
+                Json::Object(vec![
+                    ("vectorIndex", op.vector_index.map(|i| i as u64).into()),
+                    ("vas", (op.vas as i64).into()),
+                    ("shiftType", (shift_type as i64).into()),
+                    ("shiftValue", (shift_value as u64).into()),
+                    ("ext", (op.ext as i64).into()),
+                    ("operandType", (op_type as i64).into()),
+                    ("value", value),
+                    (
+                        "systemRegister",
+                        self.system_register(sysreg::decode_arm64(id, &op.op_type)),
+                    ),
+                ])
+            })
+            .collect();
+
+        vec![
+            ("conditionCodes", (arch.cc() as i64).into()),
+            ("updatesFlags", arch.update_flags().into()),
+            ("writebackRequired", arch.writeback().into()),
+            ("operands", Json::Array(operands)),
+        ]
+    }
+
+    fn instruction(
+        &self,
+        insn: &Insn,
+        reference: Option<&PcRelReference>,
+        table: Option<&JumpTable>,
+    ) -> Json {
+        let Ok(detail) = self.capstone.insn_detail(insn) else {
+            return Json::Null;
+        };
+        let mut fields = self.common(&detail, insn);
+
+        let arch_detail = detail.arch_detail();
+        if let Some(arch) = arch_detail.arm() {
+            let flow = flow::classify_arm(insn.id(), detail.groups(), arch);
+            fields.extend(self.flow(&flow, flow::branch_target_arm(insn.id(), arch)));
+            fields.extend(self.flags(&flags::effects_arm(insn, &detail, arch)));
+            fields.extend(self.analysis(reference, table));
+            fields.extend(self.arm32(insn.id(), arch));
+        } else if let Some(arch) = arch_detail.arm64() {
+            let flow = flow::classify_arm64(insn.id(), detail.groups(), arch);
+            fields.extend(self.flow(&flow, flow::branch_target_arm64(insn.id(), arch)));
+            fields.extend(self.flags(&flags::effects_arm64(insn, &detail, arch)));
+            fields.extend(self.analysis(reference, table));
+            fields.extend(self.arm64(insn.id(), arch));
+        }
+
+        Json::Object(fields)
+    }
+}
+
+/// Serialize the given instructions as a JSON document:
+/// `{"schemaVersion": 1, "mode": "ARM64", "instructions": [...]}`. Instruction fields
+/// are named after the fields of the Java instruction classes. Architecture detail is
+/// only included for ARM modes.
+pub fn write_json<W: Write>(
+    mut out: W,
+    mode: &CapstoneMode,
+    capstone: &Capstone,
+    instructions: &[&Insn],
+    image: &Image,
+) -> io::Result<W> {
+    let references = pcrel::resolve(mode, capstone, instructions, image);
+    let decoded = decode::classify_all(capstone, mode, image, instructions);
+    let jump_tables = references.as_deref().map(|references| {
+        jumptable::resolve(mode, capstone, instructions, references, &decoded, image)
+    });
+
+    let serializer = JsonSerializer { mode, capstone };
+    let instructions = instructions
+        .iter()
+        .enumerate()
+        .map(|(i, insn)| {
+            let reference = references.as_ref().and_then(|r| r[i].as_ref());
+            let table = jump_tables.as_ref().and_then(|t| t[i].as_ref());
+            serializer.instruction(insn, reference, table)
+        })
+        .collect();
+
+    let document = Json::Object(vec![
+        ("schemaVersion", (SCHEMA_VERSION as u64).into()),
+        ("mode", Json::Str(mode.to_string())),
+        ("instructions", Json::Array(instructions)),
+    ]);
+    document.write(&mut out)?;
+    out.flush()?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::testing::{disassemble, ARM64_CODE};
+
+    fn to_string(value: &Json) -> String {
+        let mut out = Vec::new();
+        value.write(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn escapes_strings() {
+        assert_eq!(
+            to_string(&"a\"b\\c\nd\u{1}".into()),
+            r#""a\"b\\c\nd\u0001""#
+        );
+    }
+
+    #[test]
+    fn writes_values() {
+        let value = Json::Object(vec![
+            (
+                "a",
+                Json::Array(vec![Json::Null, true.into(), (-1i64).into()]),
+            ),
+            ("b", Json::Float(0.5)),
+            ("c", Json::Float(f64::NAN)),
+            ("d", Some(u64::MAX).into()),
+        ]);
+        assert_eq!(
+            to_string(&value),
+            r#"{"a":[null,true,-1],"b":0.5,"c":null,"d":18446744073709551615}"#
+        );
+    }
+
+    #[test]
+    fn writes_instructions() {
+        disassemble(
+            CapstoneMode::ARM64,
+            ARM64_CODE,
+            0x1000,
+            |cs, mode, insns, image| {
+                let out = write_json(Vec::new(), mode, cs, insns, image).unwrap();
+                let text = String::from_utf8(out).unwrap();
+
+                assert!(text.starts_with(
+                    r#"{"schemaVersion":1,"mode":"ARM64","instructions":[{"address":4096,"#
+                ));
+                assert!(text.contains(
+                    r#""value":{"base":"sp","index":null,"disp":-16,"addressingMode":1,"baseUpdated":true,"#
+                ));
+                assert!(text.contains(r#""isCall":true,"#));
+                assert!(text.contains(r#""flagsRead":0,"flagsWritten":0,"#));
+                assert!(text.contains(r#""branchTarget":3804,"#));
+                assert!(text.ends_with("]}"));
+            },
+        );
+    }
+
+    #[test]
+    fn writes_thumb_encodings() {
+        // ldr.w r0, [r1, #4]
+        let code = [0xd1, 0xf8, 0x04, 0x00];
+        disassemble(
+            CapstoneMode::Thumb,
+            &code,
+            0x1000,
+            |cs, mode, insns, image| {
+                let out = write_json(Vec::new(), mode, cs, insns, image).unwrap();
+                let text = String::from_utf8(out).unwrap();
+                assert!(text.contains(r#""encoding":4174446596,"#));
+            },
+        );
+    }
+
+    #[test]
+    fn omits_encodings_outside_arm() {
+        // 'mov rax, 0x1122334455667788'
+        let code = [0x48, 0xb8, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+        disassemble(
+            CapstoneMode::X86_64,
+            &code,
+            0x1000,
+            |cs, mode, insns, image| {
+                let out = write_json(Vec::new(), mode, cs, insns, image).unwrap();
+                let text = String::from_utf8(out).unwrap();
+                assert!(text.contains(r#""bytes":"48b88877665544332211","instructionId":"#));
+                assert!(!text.contains(r#""encoding""#));
+            },
+        );
+    }
+}
@@ -0,0 +1,360 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use std::io::{self, Write};
+
+use capstone::{Capstone, Insn, RegId};
+
+use crate::analysis::pcrel::PcRelReference;
+use crate::analysis::symbolize::SymbolResolver;
+use crate::analysis::{decode, pcrel, Image};
+use crate::capstone::mode::CapstoneMode;
+
+/// Layout options of a listing.
+#[derive(Debug, Clone)]
+pub struct ListingOptions {
+    /// Number of bytes printed as one group, 0 for the instruction unit of the mode.
+    pub byte_grouping: usize,
+    /// Print byte groups in big-endian rather than little-endian order.
+    pub big_endian: bool,
+    /// Minimum number of hex digits of an address.
+    pub address_width: usize,
+    /// Append comments with the addresses and symbols of branch targets and
+    /// PC-relative references.
+    pub comments: bool,
+    /// Write the instruction id, accessed registers and groups below each instruction.
+    pub detail: bool,
+}
+
+impl Default for ListingOptions {
+    fn default() -> Self {
+        ListingOptions {
+            byte_grouping: 0,
+            big_endian: false,
+            address_width: 8,
+            comments: true,
+            detail: false,
+        }
+    }
+}
+
+/// Get the size of the smallest instruction in the given mode.
+fn insn_unit(mode: &CapstoneMode) -> usize {
+    match mode {
+        CapstoneMode::ARM32 | CapstoneMode::ARM64 => 4,
+        CapstoneMode::Thumb => 2,
+        CapstoneMode::X86_32 | CapstoneMode::X86_64 => 1,
+    }
+}
+
+/// Get the comment prefix used by the assembler syntax of the given mode.
+fn comment_prefix(mode: &CapstoneMode) -> &'static str {
+    match mode {
+        CapstoneMode::ARM32 | CapstoneMode::Thumb => "@",
+        CapstoneMode::ARM64 => "//",
+        CapstoneMode::X86_32 | CapstoneMode::X86_64 => "#",
+    }
+}
+
+/// Format bytes as hex, in groups of `grouping` bytes of the given byte order.
+fn format_bytes(bytes: &[u8], grouping: usize, big_endian: bool) -> String {
+    bytes
+        .chunks(grouping)
+        .map(|group| {
+            let digits = group.iter().map(|b| format!("{:02x}", b));
+            if big_endian {
+                digits.collect::<String>()
+            } else {
+                digits.rev().collect::<String>()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Renders instructions in the layout of `objdump -d`.
+pub struct ListingWriter<'a, W: Write> {
+    out: W,
+    mode: &'a CapstoneMode,
+    options: &'a ListingOptions,
+    resolver: Option<&'a mut dyn SymbolResolver>,
+}
+
+impl<'a, W: Write> ListingWriter<'a, W> {
+    pub fn new(
+        out: W,
+        mode: &'a CapstoneMode,
+        options: &'a ListingOptions,
+        resolver: Option<&'a mut dyn SymbolResolver>,
+    ) -> ListingWriter<'a, W> {
+        ListingWriter {
+            out,
+            mode,
+            options,
+            resolver,
+        }
+    }
+
+    fn resolve(&mut self, address: u64) -> Option<String> {
+        self.resolver
+            .as_mut()
+            .and_then(|resolver| resolver.resolve(address))
+    }
+
+    fn grouping(&self) -> usize {
+        match self.options.byte_grouping {
+            0 => insn_unit(self.mode),
+            grouping => grouping,
+        }
+    }
+
+    /// Write the symbol header if a symbol starts at the given address.
+    fn write_header(&mut self, address: u64) -> io::Result<()> {
+        if let Some(name) = self.resolve(address) {
+            let width = self.options.address_width;
+            writeln!(
+                self.out,
+                "\n{:0width$x} <{}>:",
+                address,
+                name,
+                width = width
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Write a line for the given bytes and instruction text.
+    fn write_line(&mut self, address: u64, bytes: &[u8], text: &str) -> io::Result<()> {
+        self.write_header(address)?;
+        let width = self.options.address_width;
+        let bytes = format_bytes(bytes, self.grouping(), self.options.big_endian);
+        writeln!(
+            self.out,
+            "{:>width$x}:\t{} \t{}",
+            address,
+            bytes,
+            text,
+            width = width
+        )
+    }
+
+    /// Write a comment naming a referenced address.
+    fn comment(&mut self, reference: &PcRelReference) -> String {
+        let mut comment = format!("\t{} {:#x}", comment_prefix(self.mode), reference.address);
+        if let Some(name) = self.resolve(reference.address) {
+            comment.push_str(&format!(" <{}>", name));
+        }
+        comment
+    }
+
+    /// Write a run of disassembled instructions.
+    pub fn write_instructions(
+        &mut self,
+        capstone: &Capstone,
+        instructions: &[&Insn],
+        image: &Image,
+    ) -> io::Result<()> {
+        let references = if self.options.comments {
+            pcrel::resolve(self.mode, capstone, instructions, image)
+        } else {
+            None
+        };
+
+        for (i, insn) in instructions.iter().enumerate() {
+            let mut text = format!(
+                "{}\t{}",
+                insn.mnemonic().unwrap_or(""),
+                insn.op_str().unwrap_or("")
+            );
+
+            if self.options.comments {
+                let target = decode::classify(capstone, self.mode, image, insn)
+                    .and_then(|decoded| decoded.target);
+                if let Some(name) = target.and_then(|target| self.resolve(target.address)) {
+                    text.push_str(&format!(" <{}>", name));
+                } else if let Some(reference) =
+                    references.as_ref().and_then(|references| references[i])
+                {
+                    let comment = self.comment(&reference);
+                    text.push_str(&comment);
+                }
+            }
+
+            self.write_line(insn.address(), insn.bytes(), text.trim_end())?;
+            if self.options.detail {
+                self.write_detail(capstone, insn)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the detail lines of an instruction, omitting empty lists.
+    fn write_detail(&mut self, capstone: &Capstone, insn: &Insn) -> io::Result<()> {
+        let Ok(detail) = capstone.insn_detail(insn) else {
+            return Ok(());
+        };
+        let regs = |regs: &[RegId]| -> Vec<String> {
+            regs.iter()
+                .map(|reg| capstone.reg_name(*reg).unwrap_or_else(|| reg.0.to_string()))
+                .collect()
+        };
+        let groups: Vec<String> = detail
+            .groups()
+            .iter()
+            .map(|group| {
+                capstone
+                    .group_name(*group)
+                    .unwrap_or_else(|| group.0.to_string())
+            })
+            .collect();
+
+        writeln!(self.out, "\t\tid: {}", insn.id().0)?;
+        for (label, names) in [
+            ("regs read", regs(detail.regs_read())),
+            ("regs write", regs(detail.regs_write())),
+            ("groups", groups),
+        ] {
+            if !names.is_empty() {
+                writeln!(self.out, "\t\t{}: {}", label, names.join(" "))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write bytes that do not decode to an instruction, one instruction unit at a time.
+    pub fn write_undefined(&mut self, address: u64, bytes: &[u8]) -> io::Result<()> {
+        let unit = insn_unit(self.mode);
+        for (i, chunk) in bytes.chunks(unit).enumerate() {
+            let value = chunk
+                .iter()
+                .rev()
+                .fold(0u64, |value, b| (value << 8) | *b as u64);
+            let text = match chunk.len() {
+                4 => format!(".inst\t{:#010x} ; undefined", value),
+                2 => format!(".short\t{:#06x}", value),
+                _ if unit == 1 => "(bad)".to_string(),
+                _ => format!(".byte\t{:#04x}", value),
+            };
+            let address = address
+                .checked_add((i * unit) as u64)
+                .ok_or_else(|| io::Error::other("address overflows the address space"))?;
+            self.write_line(address, chunk, &text)?;
+        }
+        Ok(())
+    }
+
+    /// Disassemble the whole image, listing bytes that do not decode as undefined.
+    pub fn write_image(&mut self, capstone: &Capstone, image: &Image) -> io::Result<()> {
+        let unit = insn_unit(self.mode) as u64;
+        let end = image
+            .end()
+            .ok_or_else(|| io::Error::other("image extends past the end of the address space"))?;
+        let mut address = image.address;
+
+        while address < end {
+            let code = image.tail(address).unwrap_or_default();
+            let instructions = capstone
+                .disasm_all(code, address)
+                .map_err(|e| io::Error::other(e.to_string()))?;
+            let instructions: Vec<&Insn> = (*instructions).iter().collect();
+
+            if let Some(last) = instructions.last() {
+                self.write_instructions(capstone, &instructions, image)?;
+                address = last.address() + last.len() as u64;
+            }
+
+            // Disassembly stops at the first invalid instruction
+            if address < end {
+                let size = unit.min(end - address) as usize;
+                let bytes = image.slice(address, size).unwrap_or_default();
+                self.write_undefined(address, bytes)?;
+                address += size as u64;
+            }
+        }
+        Ok(())
+    }
+
+    /// Flush and return the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// Render a listing of the whole image to `out`.
+pub fn write_listing<'a, W: Write>(
+    out: W,
+    mode: &'a CapstoneMode,
+    options: &'a ListingOptions,
+    resolver: Option<&'a mut dyn SymbolResolver>,
+    capstone: &Capstone,
+    image: &Image,
+) -> io::Result<W> {
+    let mut writer = ListingWriter::new(out, mode, options, resolver);
+    writer.write_image(capstone, image)?;
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::analysis::testing::{disassemble, ARM64_CODE};
+
+    #[test]
+    fn groups_bytes() {
+        let bytes = [0xfd, 0x7b, 0xbf, 0xa9];
+        assert_eq!(format_bytes(&bytes, 4, false), "a9bf7bfd");
+        assert_eq!(format_bytes(&bytes, 2, false), "7bfd a9bf");
+        assert_eq!(format_bytes(&bytes, 1, false), "fd 7b bf a9");
+        assert_eq!(format_bytes(&bytes, 4, true), "fd7bbfa9");
+    }
+
+    #[test]
+    fn lists_instructions() {
+        let mut code = ARM64_CODE.to_vec();
+        code.extend_from_slice(&[0xff; 4]);
+        let mut symbols =
+            BTreeMap::from([(0x1000, "main".to_string()), (0xedc, "puts".to_string())]);
+
+        disassemble(CapstoneMode::ARM64, &code, 0x1000, |cs, mode, _, image| {
+            let options = ListingOptions::default();
+            let out = write_listing(Vec::new(), mode, &options, Some(&mut symbols), cs, image);
+            let text = String::from_utf8(out.unwrap()).unwrap();
+            let lines: Vec<&str> = text.lines().collect();
+
+            assert_eq!(lines[1], "00001000 <main>:");
+            assert_eq!(
+                lines[2],
+                "    1000:\ta9bf7bfd \tstp\tx29, x30, [sp, #-0x10]!"
+            );
+            assert_eq!(
+                lines[4],
+                "    1008:\t90000000 \tadrp\tx0, #0x1000\t// 0x1000 <main>"
+            );
+            assert_eq!(lines[6], "    1010:\t97ffffb3 \tbl\t#0xedc <puts>");
+            assert_eq!(
+                lines.last(),
+                Some(&"    1020:\tffffffff \t.inst\t0xffffffff ; undefined")
+            );
+        });
+    }
+
+    #[test]
+    fn lists_detail() {
+        disassemble(CapstoneMode::X86_64, &[0xc3], 0, |cs, mode, _, image| {
+            let options = ListingOptions {
+                detail: true,
+                comments: false,
+                ..Default::default()
+            };
+            let out = write_listing(Vec::new(), mode, &options, None, cs, image);
+            let text = String::from_utf8(out.unwrap()).unwrap();
+            assert!(text.starts_with("       0:\tc3 \tret\n\t\tid: "));
+            assert!(text.contains("\t\tregs read: rsp\n"));
+        });
+    }
+}
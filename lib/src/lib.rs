@@ -3,14 +3,20 @@
  *
  * Use of this source code is governed by the MIT license found in the LICENSE file.
  */
-use jni::objects::{JByteArray, JObject};
+use jni::objects::{JByteArray, JLongArray, JObject, JString};
 use jni::sys::{jint, jlong, jshort, jstring};
 use jni::JNIEnv;
 
 use crate::capstone::context::CapstoneContext;
 
+mod analysis;
 mod capstone;
+pub mod cli;
+pub mod codegen;
+mod format;
+mod loader;
 mod obj;
+mod operand;
 mod util;
 mod writer;
 
@@ -49,6 +55,164 @@ pub extern "system" fn Java_org_native4j_capstone_Capstone_disassemble<'local>(
     0 as jstring /* null */
 }
 
+#[no_mangle]
+pub extern "system" fn Java_org_native4j_capstone_Capstone_disassembleWithSymbols<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    result_object: JObject<'local>,
+    data: JByteArray<'local>,
+    address: jlong,
+    resolver: JObject<'local>,
+) -> jstring {
+    let result =
+        capstone::disassemble_symbolized(&mut env, this, result_object, data, address, resolver);
+    check_result!(env, result);
+    0 as jstring /* null */
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_native4j_capstone_Capstone_renderListing<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    listing_object: JObject<'local>,
+    data: JByteArray<'local>,
+    address: jlong,
+    options: JObject<'local>,
+    path: JString<'local>,
+) -> jstring {
+    let result =
+        capstone::render_listing(&mut env, this, listing_object, data, address, options, path);
+    check_result!(env, result);
+    0 as jstring /* null */
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_native4j_capstone_Capstone_exportJson<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    export_object: JObject<'local>,
+    data: JByteArray<'local>,
+    address: jlong,
+    path: JString<'local>,
+) -> jstring {
+    let result = capstone::export_json(&mut env, this, export_object, data, address, path);
+    check_result!(env, result);
+    0 as jstring /* null */
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_native4j_capstone_Capstone_disassembleRecursive<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    result_object: JObject<'local>,
+    entries: JLongArray<'local>,
+    data: JByteArray<'local>,
+    address: jlong,
+) -> jstring {
+    let result =
+        capstone::disassemble_recursive(&mut env, this, result_object, data, address, entries);
+    check_result!(env, result);
+    0 as jstring /* null */
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_native4j_capstone_Capstone_buildCfg<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    graph_object: JObject<'local>,
+    data: JByteArray<'local>,
+    address: jlong,
+    entries: JLongArray<'local>,
+) -> jstring {
+    let result = capstone::build_cfg(&mut env, this, graph_object, data, address, entries);
+    check_result!(env, result);
+    0 as jstring /* null */
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_native4j_capstone_Capstone_detectFunctions<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    table_object: JObject<'local>,
+    data: JByteArray<'local>,
+    address: jlong,
+    entries: JLongArray<'local>,
+) -> jstring {
+    let result = capstone::find_functions(&mut env, this, table_object, data, address, entries);
+    check_result!(env, result);
+    0 as jstring /* null */
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_native4j_capstone_Capstone_buildXrefs<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    index_object: JObject<'local>,
+    data: JByteArray<'local>,
+    address: jlong,
+    entries: JLongArray<'local>,
+) -> jstring {
+    let result = capstone::build_xrefs(&mut env, this, index_object, data, address, entries);
+    check_result!(env, result);
+    0 as jstring /* null */
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_native4j_capstone_loader_ElfFile_parse<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    data: JByteArray<'local>,
+) -> jstring {
+    let result = loader::parse_elf(&mut env, this, data);
+    check_result!(env, result);
+    0 as jstring /* null */
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_native4j_capstone_loader_MachOFile_parse<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    data: JByteArray<'local>,
+    cpu_type: jint,
+) -> jstring {
+    let result = loader::parse_macho(&mut env, this, data, cpu_type);
+    check_result!(env, result);
+    0 as jstring /* null */
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_native4j_capstone_loader_PeFile_parse<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    data: JByteArray<'local>,
+) -> jstring {
+    let result = loader::parse_pe(&mut env, this, data);
+    check_result!(env, result);
+    0 as jstring /* null */
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_native4j_capstone_Capstone_loadNames<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    table_object: JObject<'local>,
+) -> jstring {
+    let result = capstone::load_names(&mut env, this, table_object);
+    check_result!(env, result);
+    0 as jstring /* null */
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_native4j_capstone_Capstone_loadRegisters<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    table_object: JObject<'local>,
+) -> jstring {
+    let result = capstone::load_registers(&mut env, this, table_object);
+    check_result!(env, result);
+    0 as jstring /* null */
+}
+
 #[no_mangle]
 pub extern "system" fn Java_org_native4j_capstone_Capstone_getInsnName<'local>(
     mut env: JNIEnv<'local>,
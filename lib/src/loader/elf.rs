@@ -0,0 +1,331 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use crate::capstone::mode::CapstoneMode;
+use crate::loader::reader::Reader;
+use crate::loader::Result;
+
+const ELF_MAGIC: &[u8] = b"\x7fELF";
+
+const ELFCLASS32: u8 = 1;
+const ELFCLASS64: u8 = 2;
+const ELFDATA2LSB: u8 = 1;
+const ELFDATA2MSB: u8 = 2;
+
+pub const EM_386: u16 = 3;
+pub const EM_ARM: u16 = 40;
+pub const EM_X86_64: u16 = 62;
+pub const EM_AARCH64: u16 = 183;
+
+const SHT_SYMTAB: u32 = 2;
+const SHT_NOBITS: u32 = 8;
+const SHT_DYNSYM: u32 = 11;
+
+const SHF_EXECINSTR: u64 = 0x4;
+
+const SHN_UNDEF: u16 = 0;
+const SHN_XINDEX: u16 = 0xffff;
+
+pub const STT_FUNC: u8 = 2;
+const STB_LOCAL: u8 = 0;
+
+/// An ELF section.
+#[derive(Debug, Clone)]
+pub struct ElfSection {
+    pub name: String,
+    /// Offset of the name in the section name table.
+    name_offset: u32,
+    pub kind: u32,
+    pub flags: u64,
+    pub address: u64,
+    pub offset: u64,
+    pub size: u64,
+    pub link: u32,
+    pub entry_size: u64,
+}
+
+impl ElfSection {
+    /// Number of bytes the section occupies in the file.
+    pub fn file_size(&self) -> u64 {
+        if self.kind == SHT_NOBITS {
+            0
+        } else {
+            self.size
+        }
+    }
+
+    pub fn is_executable(&self) -> bool {
+        self.flags & SHF_EXECINSTR != 0
+    }
+}
+
+/// An ELF program header.
+#[derive(Debug, Clone)]
+pub struct ElfSegment {
+    pub kind: u32,
+    pub flags: u32,
+    pub offset: u64,
+    pub address: u64,
+    pub file_size: u64,
+    pub memory_size: u64,
+}
+
+/// An entry of the static or dynamic symbol table.
+#[derive(Debug, Clone)]
+pub struct ElfSymbol {
+    pub name: String,
+    /// The symbol value with the Thumb bit cleared.
+    pub address: u64,
+    pub size: u64,
+    pub kind: u8,
+    pub binding: u8,
+    pub section_index: u16,
+    /// The symbol is an ARM function in Thumb state.
+    pub is_thumb: bool,
+}
+
+impl ElfSymbol {
+    pub fn is_function(&self) -> bool {
+        self.kind == STT_FUNC
+    }
+
+    pub fn is_global(&self) -> bool {
+        self.binding != STB_LOCAL
+    }
+
+    pub fn is_defined(&self) -> bool {
+        self.section_index != SHN_UNDEF
+    }
+}
+
+/// A parsed ELF file.
+#[derive(Debug)]
+pub struct Elf {
+    pub is_64: bool,
+    pub big_endian: bool,
+    /// The object file type, e.g. executable or shared object.
+    pub kind: u16,
+    pub machine: u16,
+    pub entry: u64,
+    pub sections: Vec<ElfSection>,
+    pub segments: Vec<ElfSegment>,
+    pub symbols: Vec<ElfSymbol>,
+}
+
+impl Elf {
+    /// Parse the headers and symbol tables of an ELF file.
+    pub fn parse(data: &[u8]) -> Result<Elf> {
+        if !data.starts_with(ELF_MAGIC) {
+            return Err("not an ELF file".into());
+        }
+
+        let ident = Reader::new(data, false);
+        let is_64 = match ident.u8(4)? {
+            ELFCLASS32 => false,
+            ELFCLASS64 => true,
+            class => return Err(format!("invalid ELF class {}", class).into()),
+        };
+        let big_endian = match ident.u8(5)? {
+            ELFDATA2LSB => false,
+            ELFDATA2MSB => true,
+            encoding => return Err(format!("invalid ELF data encoding {}", encoding).into()),
+        };
+
+        let r = Reader::new(data, big_endian);
+        let (entry, phoff, shoff, rest) = if is_64 {
+            (r.u64(24)?, r.u64(32)?, r.u64(40)?, 48)
+        } else {
+            (r.u32(24)? as u64, r.u32(28)? as u64, r.u32(32)? as u64, 36)
+        };
+
+        let mut elf = Elf {
+            is_64,
+            big_endian,
+            kind: r.u16(16)?,
+            machine: r.u16(18)?,
+            entry,
+            sections: Vec::new(),
+            segments: Vec::new(),
+            symbols: Vec::new(),
+        };
+
+        let phentsize = r.u16(rest + 6)? as u64;
+        let phnum = r.u16(rest + 8)? as u64;
+        let shentsize = r.u16(rest + 10)? as u64;
+        let shnum = r.u16(rest + 12)? as u64;
+        let shstrndx = r.u16(rest + 14)?;
+
+        let (min_phentsize, min_shentsize) = if is_64 { (56, 64) } else { (32, 40) };
+
+        if phnum != 0 {
+            if phentsize < min_phentsize {
+                return Err(format!("invalid program header size {}", phentsize).into());
+            }
+            // Check the table lies within the file before parsing its entries
+            r.bytes(phoff, phnum * phentsize)?;
+        }
+        for i in 0..phnum {
+            elf.segments
+                .push(elf.parse_segment(&r, phoff + i * phentsize)?);
+        }
+
+        if shoff != 0 {
+            if shentsize < min_shentsize {
+                return Err(format!("invalid section header size {}", shentsize).into());
+            }
+            r.bytes(shoff, shentsize)?;
+
+            // Large section counts and indices are stored in the first section header
+            let first = elf.parse_section(&r, shoff)?;
+            let shnum = if shnum == 0 { first.size } else { shnum };
+            let shstrndx = if shstrndx == SHN_XINDEX {
+                first.link as u64
+            } else {
+                shstrndx as u64
+            };
+
+            let table_size = shnum
+                .checked_mul(shentsize)
+                .ok_or_else(|| format!("invalid section count {}", shnum))?;
+            r.bytes(shoff, table_size)?;
+            for i in 0..shnum {
+                elf.sections
+                    .push(elf.parse_section(&r, shoff + i * shentsize)?);
+            }
+
+            if let Some(strtab) = elf.sections.get(shstrndx as usize).cloned() {
+                for section in &mut elf.sections {
+                    section.name = read_name(&r, &strtab, section.name_offset)?;
+                }
+            }
+        }
+
+        for index in 0..elf.sections.len() {
+            let section = &elf.sections[index];
+            if section.kind == SHT_SYMTAB || section.kind == SHT_DYNSYM {
+                let symbols = elf.parse_symbols(&r, section)?;
+                elf.symbols.extend(symbols);
+            }
+        }
+
+        Ok(elf)
+    }
+
+    fn parse_segment(&self, r: &Reader, offset: u64) -> Result<ElfSegment> {
+        Ok(if self.is_64 {
+            ElfSegment {
+                kind: r.u32(offset)?,
+                flags: r.u32(offset + 4)?,
+                offset: r.u64(offset + 8)?,
+                address: r.u64(offset + 16)?,
+                file_size: r.u64(offset + 32)?,
+                memory_size: r.u64(offset + 40)?,
+            }
+        } else {
+            ElfSegment {
+                kind: r.u32(offset)?,
+                offset: r.u32(offset + 4)? as u64,
+                address: r.u32(offset + 8)? as u64,
+                file_size: r.u32(offset + 16)? as u64,
+                memory_size: r.u32(offset + 20)? as u64,
+                flags: r.u32(offset + 24)?,
+            }
+        })
+    }
+
+    fn parse_section(&self, r: &Reader, offset: u64) -> Result<ElfSection> {
+        let wide = self.is_64;
+        let word = |index: u64| r.word(offset + 8 + index * if wide { 8 } else { 4 }, wide);
+
+        // The name is resolved once the section name table is known
+        Ok(ElfSection {
+            name: String::new(),
+            name_offset: r.u32(offset)?,
+            kind: r.u32(offset + 4)?,
+            flags: word(0)?,
+            address: word(1)?,
+            offset: word(2)?,
+            size: word(3)?,
+            link: r.u32(offset + if wide { 40 } else { 24 })?,
+            entry_size: r.word(offset + if wide { 56 } else { 36 }, wide)?,
+        })
+    }
+
+    fn parse_symbols(&self, r: &Reader, section: &ElfSection) -> Result<Vec<ElfSymbol>> {
+        let entry_size = match section.entry_size {
+            0 if self.is_64 => 24,
+            0 => 16,
+            size => size,
+        };
+        let strtab = self
+            .sections
+            .get(section.link as usize)
+            .ok_or("symbol table links to a missing string table")?;
+
+        // The first entry is always the undefined symbol
+        let count = section.size / entry_size;
+        // Check the table lies within the file before sizing anything by its count
+        r.bytes(section.offset, count * entry_size)?;
+        let mut symbols = Vec::with_capacity(count.saturating_sub(1) as usize);
+        for i in 1..count {
+            let offset = section.offset + i * entry_size;
+            let (name, value, size, info, shndx) = if self.is_64 {
+                (
+                    r.u32(offset)?,
+                    r.u64(offset + 8)?,
+                    r.u64(offset + 16)?,
+                    r.u8(offset + 4)?,
+                    r.u16(offset + 6)?,
+                )
+            } else {
+                (
+                    r.u32(offset)?,
+                    r.u32(offset + 4)? as u64,
+                    r.u32(offset + 8)? as u64,
+                    r.u8(offset + 12)?,
+                    r.u16(offset + 14)?,
+                )
+            };
+
+            let kind = info & 0xf;
+            let is_thumb = self.machine == EM_ARM && kind == STT_FUNC && value & 1 == 1;
+            symbols.push(ElfSymbol {
+                name: read_name(r, strtab, name)?,
+                address: if is_thumb { value & !1 } else { value },
+                size,
+                kind,
+                binding: info >> 4,
+                section_index: shndx,
+                is_thumb,
+            });
+        }
+        Ok(symbols)
+    }
+
+    /// Select the disassembly mode from the machine. ARM files start in Thumb state if
+    /// the entry point has its low bit set. Big-endian files have no mode.
+    pub fn mode(&self) -> Option<CapstoneMode> {
+        match (self.machine, self.big_endian) {
+            (EM_ARM, false) if self.entry & 1 == 1 => Some(CapstoneMode::Thumb),
+            (EM_ARM, false) => Some(CapstoneMode::ARM32),
+            (EM_AARCH64, false) => Some(CapstoneMode::ARM64),
+            (EM_386, false) => Some(CapstoneMode::X86_32),
+            (EM_X86_64, false) => Some(CapstoneMode::X86_64),
+            _ => None,
+        }
+    }
+}
+
+/// Read a name from a string table section.
+fn read_name(r: &Reader, strtab: &ElfSection, offset: u32) -> Result<String> {
+    if offset as u64 >= strtab.file_size() {
+        return Ok(String::new());
+    }
+    let start = strtab
+        .offset
+        .checked_add(offset as u64)
+        .ok_or_else(|| format!("string offset {:#x} out of bounds", offset))?;
+    r.str(start, (strtab.file_size() - offset as u64) as usize)
+}
@@ -0,0 +1,420 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use std::collections::BTreeSet;
+use std::ops::Bound;
+
+use crate::capstone::mode::CapstoneMode;
+use crate::loader::reader::Reader;
+use crate::loader::Result;
+
+const FAT_MAGIC: u32 = 0xcafebabe;
+const FAT_MAGIC_64: u32 = 0xcafebabf;
+const MH_MAGIC: u32 = 0xfeedface;
+const MH_MAGIC_64: u32 = 0xfeedfacf;
+const MH_CIGAM: u32 = 0xcefaedfe;
+const MH_CIGAM_64: u32 = 0xcffaedfe;
+
+/// Select the preferred supported slice of a fat binary.
+pub const CPU_TYPE_ANY: i32 = -1;
+pub const CPU_TYPE_X86: i32 = 7;
+pub const CPU_TYPE_X86_64: i32 = 0x01000007;
+pub const CPU_TYPE_ARM: i32 = 12;
+pub const CPU_TYPE_ARM64: i32 = 0x0100000c;
+
+const LC_SEGMENT: u32 = 0x1;
+const LC_SYMTAB: u32 = 0x2;
+const LC_SEGMENT_64: u32 = 0x19;
+const LC_FUNCTION_STARTS: u32 = 0x26;
+const LC_MAIN: u32 = 0x80000028;
+
+const S_ATTR_PURE_INSTRUCTIONS: u32 = 0x80000000;
+const S_ATTR_SOME_INSTRUCTIONS: u32 = 0x400;
+const S_ZEROFILL: u32 = 0x1;
+
+const N_STAB: u8 = 0xe0;
+const N_TYPE: u8 = 0x0e;
+const N_EXT: u8 = 0x01;
+const N_SECT: u8 = 0x0e;
+const N_ARM_THUMB_DEF: u16 = 0x0008;
+
+/// A section of a Mach-O segment.
+#[derive(Debug, Clone)]
+pub struct MachSection {
+    /// The segment and section name, e.g. `__TEXT,__text`.
+    pub name: String,
+    pub flags: u32,
+    pub address: u64,
+    /// Offset in the file, including the offset of the slice.
+    pub offset: u64,
+    pub size: u64,
+}
+
+impl MachSection {
+    /// The section type, stored in the low byte of the flags.
+    pub fn kind(&self) -> u32 {
+        self.flags & 0xff
+    }
+
+    /// Number of bytes the section occupies in the file.
+    pub fn file_size(&self) -> u64 {
+        if self.kind() == S_ZEROFILL {
+            0
+        } else {
+            self.size
+        }
+    }
+
+    pub fn is_executable(&self) -> bool {
+        self.flags & (S_ATTR_PURE_INSTRUCTIONS | S_ATTR_SOME_INSTRUCTIONS) != 0
+    }
+}
+
+/// A Mach-O segment load command.
+#[derive(Debug, Clone)]
+pub struct MachSegment {
+    pub name: String,
+    pub command: u32,
+    pub protection: u32,
+    /// Offset in the file, including the offset of the slice.
+    pub offset: u64,
+    pub address: u64,
+    pub file_size: u64,
+    pub memory_size: u64,
+}
+
+/// An entry of the symbol table.
+#[derive(Debug, Clone)]
+pub struct MachSymbol {
+    pub name: String,
+    pub address: u64,
+    /// The distance to the next symbol or function start in the same section, since
+    /// Mach-O does not record symbol sizes.
+    pub size: u64,
+    pub kind: u8,
+    /// One-based index of the section defining the symbol, 0 if not in a section.
+    pub section: u8,
+    pub is_thumb: bool,
+}
+
+impl MachSymbol {
+    pub fn is_global(&self) -> bool {
+        self.kind & N_EXT != 0
+    }
+
+    pub fn is_defined(&self) -> bool {
+        self.kind & N_TYPE == N_SECT
+    }
+}
+
+/// A parsed Mach-O file, or the selected slice of a fat binary.
+#[derive(Debug)]
+pub struct MachO {
+    pub is_64: bool,
+    pub big_endian: bool,
+    pub cpu_type: i32,
+    pub cpu_subtype: i32,
+    /// The file type, e.g. executable or dynamic library.
+    pub kind: u32,
+    /// The entry point, 0 if the file has no `LC_MAIN` command.
+    pub entry: u64,
+    /// Offset of the slice in the file.
+    pub slice_offset: u64,
+    pub sections: Vec<MachSection>,
+    pub segments: Vec<MachSegment>,
+    pub symbols: Vec<MachSymbol>,
+    /// The addresses of `LC_FUNCTION_STARTS`, ordered by address.
+    pub function_starts: Vec<u64>,
+}
+
+/// Get the preference of a CPU type when selecting a slice. Lower is better.
+fn slice_rank(cpu_type: i32) -> Option<usize> {
+    [CPU_TYPE_ARM64, CPU_TYPE_ARM, CPU_TYPE_X86_64, CPU_TYPE_X86]
+        .iter()
+        .position(|t| *t == cpu_type)
+}
+
+/// Read a fixed-size, NUL-padded name.
+fn read_fixed_name(r: &Reader, offset: u64) -> Result<String> {
+    r.str(offset, 16)
+}
+
+/// Read an unsigned LEB128 value, returning the value and the number of bytes read.
+fn read_uleb128(data: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0u64;
+    for (i, byte) in data.iter().enumerate().take(10) {
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+impl MachO {
+    /// Parse a Mach-O file. For fat binaries the slice matching `cpu_type` is parsed,
+    /// or the preferred supported slice for [CPU_TYPE_ANY].
+    pub fn parse(data: &[u8], cpu_type: i32) -> Result<MachO> {
+        let r = Reader::new(data, true);
+        let magic = r.u32(0)?;
+        if magic != FAT_MAGIC && magic != FAT_MAGIC_64 {
+            return MachO::parse_slice(data, 0, cpu_type);
+        }
+
+        // Fat headers are always big-endian
+        let wide = magic == FAT_MAGIC_64;
+        let entry_size = if wide { 32 } else { 20 };
+        let count = r.u32(4)? as u64;
+
+        let mut selected: Option<(usize, u64, u64)> = None;
+        for i in 0..count {
+            let offset = 8 + i * entry_size;
+            let slice_type = r.u32(offset)? as i32;
+            let (slice_offset, slice_size) = if wide {
+                (r.u64(offset + 8)?, r.u64(offset + 16)?)
+            } else {
+                (r.u32(offset + 8)? as u64, r.u32(offset + 12)? as u64)
+            };
+
+            let rank = if cpu_type == CPU_TYPE_ANY {
+                slice_rank(slice_type)
+            } else if slice_type == cpu_type {
+                Some(0)
+            } else {
+                None
+            };
+            if let Some(rank) = rank {
+                if selected.is_none_or(|(best, _, _)| rank < best) {
+                    selected = Some((rank, slice_offset, slice_size));
+                }
+            }
+        }
+
+        let (_, slice_offset, slice_size) =
+            selected.ok_or_else(|| format!("no slice for CPU type {:#x}", cpu_type))?;
+        let slice = r.bytes(slice_offset, slice_size)?;
+        MachO::parse_slice(slice, slice_offset, cpu_type)
+    }
+
+    /// Parse a single-architecture Mach-O file at `slice_offset` of the file.
+    fn parse_slice(data: &[u8], slice_offset: u64, cpu_type: i32) -> Result<MachO> {
+        let (is_64, big_endian) = match Reader::new(data, false).u32(0)? {
+            MH_MAGIC => (false, false),
+            MH_MAGIC_64 => (true, false),
+            MH_CIGAM => (false, true),
+            MH_CIGAM_64 => (true, true),
+            _ => return Err("not a Mach-O file".into()),
+        };
+
+        let r = Reader::new(data, big_endian);
+        let mut macho = MachO {
+            is_64,
+            big_endian,
+            cpu_type: r.u32(4)? as i32,
+            cpu_subtype: r.u32(8)? as i32,
+            kind: r.u32(12)?,
+            entry: 0,
+            slice_offset,
+            sections: Vec::new(),
+            segments: Vec::new(),
+            symbols: Vec::new(),
+            function_starts: Vec::new(),
+        };
+        if cpu_type != CPU_TYPE_ANY && macho.cpu_type != cpu_type {
+            return Err(format!("no slice for CPU type {:#x}", cpu_type).into());
+        }
+
+        let count = r.u32(16)?;
+        let mut offset = if is_64 { 32 } else { 28 };
+        let mut entry_offset = None;
+        let mut symtab = None;
+        let mut function_starts = None;
+
+        for _ in 0..count {
+            let command = r.u32(offset)?;
+            let size = r.u32(offset + 4)? as u64;
+            if size < 8 {
+                return Err(format!("invalid load command size {} at {:#x}", size, offset).into());
+            }
+
+            match command {
+                LC_SEGMENT | LC_SEGMENT_64 => macho.parse_segment(&r, offset)?,
+                LC_SYMTAB => symtab = Some(offset),
+                LC_FUNCTION_STARTS => function_starts = Some(offset),
+                LC_MAIN => entry_offset = Some(r.u64(offset + 8)?),
+                _ => {}
+            }
+            offset += size;
+        }
+
+        // Addresses in the function starts and the entry point are relative to __TEXT
+        let text_address = macho
+            .segments
+            .iter()
+            .find(|segment| segment.name == "__TEXT")
+            .map_or(0, |segment| segment.address);
+        if let Some(entry_offset) = entry_offset {
+            macho.entry = text_address
+                .checked_add(entry_offset)
+                .ok_or("entry point overflows the address space")?;
+        }
+        if let Some(offset) = function_starts {
+            macho.parse_function_starts(&r, offset, text_address)?;
+        }
+        if let Some(offset) = symtab {
+            macho.parse_symbols(&r, offset)?;
+        }
+
+        Ok(macho)
+    }
+
+    fn parse_segment(&mut self, r: &Reader, offset: u64) -> Result<()> {
+        let wide = self.is_64;
+        let word = |index: u64| r.word(offset + 24 + index * if wide { 8 } else { 4 }, wide);
+
+        let segment = MachSegment {
+            name: read_fixed_name(r, offset + 8)?,
+            command: r.u32(offset)?,
+            address: word(0)?,
+            memory_size: word(1)?,
+            offset: self
+                .slice_offset
+                .checked_add(word(2)?)
+                .ok_or("segment file offset overflows")?,
+            file_size: word(3)?,
+            protection: r.u32(offset + if wide { 60 } else { 44 })?,
+        };
+
+        let count = r.u32(offset + if wide { 64 } else { 48 })? as u64;
+        let (first, entry_size) = if wide { (72, 80) } else { (56, 68) };
+        for i in 0..count {
+            let section = offset + first + i * entry_size;
+            let (address, size, rest) = if wide {
+                (r.u64(section + 32)?, r.u64(section + 40)?, section + 48)
+            } else {
+                (
+                    r.u32(section + 32)? as u64,
+                    r.u32(section + 36)? as u64,
+                    section + 40,
+                )
+            };
+            self.sections.push(MachSection {
+                name: format!(
+                    "{},{}",
+                    read_fixed_name(r, section + 16)?,
+                    read_fixed_name(r, section)?
+                ),
+                flags: r.u32(rest + 16)?,
+                address,
+                offset: self.slice_offset + r.u32(rest)? as u64,
+                size,
+            });
+        }
+
+        self.segments.push(segment);
+        Ok(())
+    }
+
+    fn parse_function_starts(&mut self, r: &Reader, offset: u64, text_address: u64) -> Result<()> {
+        let data = r.bytes(r.u32(offset + 8)? as u64, r.u32(offset + 12)? as u64)?;
+
+        // A list of ULEB128 deltas from the previous function, terminated by 0
+        let mut address = text_address;
+        let mut position = 0;
+        while let Some((delta, size)) = read_uleb128(&data[position..]) {
+            if delta == 0 {
+                break;
+            }
+            address = address
+                .checked_add(delta)
+                .ok_or("function start overflows the address space")?;
+            position += size;
+            self.function_starts.push(address & !1);
+        }
+        Ok(())
+    }
+
+    fn parse_symbols(&mut self, r: &Reader, offset: u64) -> Result<()> {
+        let symoff = r.u32(offset + 8)? as u64;
+        let count = r.u32(offset + 12)? as u64;
+        let stroff = r.u32(offset + 16)? as u64;
+        let strsize = r.u32(offset + 20)? as u64;
+        let entry_size = if self.is_64 { 16 } else { 12 };
+
+        for i in 0..count {
+            let entry = symoff + i * entry_size;
+            let kind = r.u8(entry + 4)?;
+            // Debugging entries are not symbols
+            if kind & N_STAB != 0 {
+                continue;
+            }
+
+            let name = r.u32(entry)? as u64;
+            let desc = r.u16(entry + 6)?;
+            let value = r.word(entry + 8, self.is_64)?;
+            self.symbols.push(MachSymbol {
+                name: if name < strsize {
+                    r.str(stroff + name, (strsize - name) as usize)?
+                } else {
+                    String::new()
+                },
+                address: value,
+                size: 0,
+                kind,
+                section: r.u8(entry + 5)?,
+                is_thumb: self.cpu_type == CPU_TYPE_ARM && desc & N_ARM_THUMB_DEF != 0,
+            });
+        }
+
+        self.compute_symbol_sizes();
+        Ok(())
+    }
+
+    /// Estimate symbol sizes from the next symbol or function start in the section.
+    fn compute_symbol_sizes(&mut self) {
+        let boundaries: BTreeSet<u64> = self
+            .symbols
+            .iter()
+            .filter(|symbol| symbol.is_defined())
+            .map(|symbol| symbol.address)
+            .chain(self.function_starts.iter().copied())
+            .collect();
+
+        for symbol in &mut self.symbols {
+            if !symbol.is_defined() {
+                continue;
+            }
+            let Some(section) = self.sections.get((symbol.section as usize).wrapping_sub(1)) else {
+                continue;
+            };
+            let section_end = section.address.saturating_add(section.size);
+            let end = boundaries
+                .range((Bound::Excluded(symbol.address), Bound::Unbounded))
+                .next()
+                .map_or(section_end, |next| (*next).min(section_end));
+            symbol.size = end.saturating_sub(symbol.address);
+        }
+    }
+
+    /// Check whether a defined symbol is in a section containing instructions.
+    pub fn is_function(&self, symbol: &MachSymbol) -> bool {
+        symbol.is_defined()
+            && self
+                .sections
+                .get((symbol.section as usize).wrapping_sub(1))
+                .is_some_and(MachSection::is_executable)
+    }
+
+    /// Select the disassembly mode from the CPU type.
+    pub fn mode(&self) -> Option<CapstoneMode> {
+        match (self.cpu_type, self.big_endian) {
+            (CPU_TYPE_ARM, false) => Some(CapstoneMode::ARM32),
+            (CPU_TYPE_ARM64, false) => Some(CapstoneMode::ARM64),
+            (CPU_TYPE_X86, false) => Some(CapstoneMode::X86_32),
+            (CPU_TYPE_X86_64, false) => Some(CapstoneMode::X86_64),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,71 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use jni::objects::{JByteArray, JObject};
+use jni::sys::jint;
+use jni::JNIEnv;
+use std::sync::OnceLock;
+
+use crate::capstone::read_bytes;
+use crate::loader::elf::Elf;
+use crate::loader::macho::MachO;
+use crate::loader::pe::Pe;
+use crate::writer::layout;
+
+pub mod elf;
+pub mod macho;
+pub mod output;
+pub mod pe;
+mod reader;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Result of validating the Java classes written by the loaders.
+static VALIDATED: OnceLock<std::result::Result<(), String>> = OnceLock::new();
+
+/// Fail early if the Java classes do not match what the loaders write.
+fn validate_layout(env: &mut JNIEnv) -> Result<()> {
+    Ok(layout::validate_once(&VALIDATED, env, output::get_layout)?)
+}
+
+pub fn parse_elf<'local>(
+    env: &mut JNIEnv<'local>,
+    this: JObject<'local>,
+    data: JByteArray<'local>,
+) -> Result<()> {
+    validate_layout(env)?;
+    let bytes = read_bytes(env, &data)?;
+    let elf = Elf::parse(&bytes)?;
+
+    output::write_elf(env, &this, &elf)?;
+    Ok(())
+}
+
+pub fn parse_macho<'local>(
+    env: &mut JNIEnv<'local>,
+    this: JObject<'local>,
+    data: JByteArray<'local>,
+    cpu_type: jint,
+) -> Result<()> {
+    validate_layout(env)?;
+    let bytes = read_bytes(env, &data)?;
+    let macho = MachO::parse(&bytes, cpu_type)?;
+
+    output::write_macho(env, &this, &macho)?;
+    Ok(())
+}
+
+pub fn parse_pe<'local>(
+    env: &mut JNIEnv<'local>,
+    this: JObject<'local>,
+    data: JByteArray<'local>,
+) -> Result<()> {
+    validate_layout(env)?;
+    let bytes = read_bytes(env, &data)?;
+    let pe = Pe::parse(&bytes)?;
+
+    output::write_pe(env, &this, &pe)?;
+    Ok(())
+}
@@ -0,0 +1,394 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use jni::objects::JObject;
+use jni::JNIEnv;
+
+use crate::capstone::mode::CapstoneMode;
+use crate::loader::elf::{Elf, ElfSection, ElfSegment, ElfSymbol};
+use crate::loader::macho::{MachO, MachSection, MachSegment, MachSymbol};
+use crate::loader::pe::{Pe, PeExport, PeImport, PeSection, RuntimeFunction};
+use crate::obj::obj::Obj;
+use crate::util::{AutoObject, JResult};
+use crate::writer::layout::{ClassLayout, Member};
+
+const SECTION_CLASS: &str = "Lorg/native4j/capstone/loader/Section;";
+const SEGMENT_CLASS: &str = "Lorg/native4j/capstone/loader/Segment;";
+const SYMBOL_CLASS: &str = "Lorg/native4j/capstone/loader/Symbol;";
+const EXPORT_CLASS: &str = "Lorg/native4j/capstone/loader/Export;";
+const IMPORT_CLASS: &str = "Lorg/native4j/capstone/loader/Import;";
+const RUNTIME_FUNCTION_CLASS: &str = "Lorg/native4j/capstone/loader/RuntimeFunction;";
+const MODE_CLASS: &str = "Lorg/native4j/capstone/CapstoneMode;";
+
+/// Fields written to every file object.
+const FILE_FIELDS: &[Member] = &[
+    Member::Field("mode", MODE_CLASS),
+    Member::Field("entryPoint", "J"),
+    Member::Field("sections", "[Lorg/native4j/capstone/loader/Section;"),
+    Member::Field("symbols", "[Lorg/native4j/capstone/loader/Symbol;"),
+];
+
+/// Get the layout of the Java classes written by the loaders.
+pub fn get_layout() -> Vec<ClassLayout<'static>> {
+    let segments = Member::Field("segments", "[Lorg/native4j/capstone/loader/Segment;");
+    vec![
+        ClassLayout::new(
+            SECTION_CLASS,
+            &[&[
+                Member::Constructor("()V"),
+                Member::Field("name", "Ljava/lang/String;"),
+                Member::Field("type", "I"),
+                Member::Field("flags", "J"),
+                Member::Field("address", "J"),
+                Member::Field("offset", "J"),
+                Member::Field("size", "J"),
+                Member::Field("fileSize", "J"),
+                Member::Field("isExecutable", "Z"),
+            ]],
+        ),
+        ClassLayout::new(
+            SEGMENT_CLASS,
+            &[&[
+                Member::Constructor("()V"),
+                Member::Field("name", "Ljava/lang/String;"),
+                Member::Field("type", "I"),
+                Member::Field("flags", "I"),
+                Member::Field("offset", "J"),
+                Member::Field("address", "J"),
+                Member::Field("fileSize", "J"),
+                Member::Field("memorySize", "J"),
+            ]],
+        ),
+        ClassLayout::new(
+            SYMBOL_CLASS,
+            &[&[
+                Member::Constructor("()V"),
+                Member::Field("name", "Ljava/lang/String;"),
+                Member::Field("address", "J"),
+                Member::Field("size", "J"),
+                Member::Field("type", "I"),
+                Member::Field("isFunction", "Z"),
+                Member::Field("isThumb", "Z"),
+                Member::Field("isGlobal", "Z"),
+                Member::Field("isDefined", "Z"),
+            ]],
+        ),
+        ClassLayout::new(
+            EXPORT_CLASS,
+            &[&[
+                Member::Constructor("()V"),
+                Member::Field("name", "Ljava/lang/String;"),
+                Member::Field("ordinal", "I"),
+                Member::Field("address", "J"),
+                Member::Field("forwarder", "Ljava/lang/String;"),
+            ]],
+        ),
+        ClassLayout::new(
+            IMPORT_CLASS,
+            &[&[
+                Member::Constructor("()V"),
+                Member::Field("library", "Ljava/lang/String;"),
+                Member::Field("name", "Ljava/lang/String;"),
+                Member::Field("ordinal", "I"),
+                Member::Field("address", "J"),
+            ]],
+        ),
+        ClassLayout::new(
+            RUNTIME_FUNCTION_CLASS,
+            &[&[
+                Member::Constructor("()V"),
+                Member::Field("startAddress", "J"),
+                Member::Field("endAddress", "J"),
+                Member::Field("unwindAddress", "J"),
+            ]],
+        ),
+        ClassLayout::new(
+            "org/native4j/capstone/loader/ElfFile",
+            &[
+                FILE_FIELDS,
+                &[
+                    Member::Field("machine", "I"),
+                    Member::Field("fileType", "I"),
+                    Member::Field("is64Bit", "Z"),
+                    Member::Field("isBigEndian", "Z"),
+                    segments,
+                ],
+            ],
+        ),
+        ClassLayout::new(
+            "org/native4j/capstone/loader/MachOFile",
+            &[
+                FILE_FIELDS,
+                &[
+                    Member::Field("cpuType", "I"),
+                    Member::Field("cpuSubtype", "I"),
+                    Member::Field("fileType", "I"),
+                    Member::Field("is64Bit", "Z"),
+                    Member::Field("sliceOffset", "J"),
+                    Member::Field("functionStarts", "[J"),
+                    segments,
+                ],
+            ],
+        ),
+        ClassLayout::new(
+            "org/native4j/capstone/loader/PeFile",
+            &[
+                FILE_FIELDS,
+                &[
+                    Member::Field("machine", "I"),
+                    Member::Field("characteristics", "I"),
+                    Member::Field("is64Bit", "Z"),
+                    Member::Field("imageBase", "J"),
+                    Member::Field("exports", "[Lorg/native4j/capstone/loader/Export;"),
+                    Member::Field("imports", "[Lorg/native4j/capstone/loader/Import;"),
+                    Member::Field(
+                        "runtimeFunctions",
+                        "[Lorg/native4j/capstone/loader/RuntimeFunction;",
+                    ),
+                ],
+            ],
+        ),
+    ]
+}
+
+/// Create a Java object for each element and populate it with `write`.
+fn new_objects<'jni, T>(
+    env: &mut JNIEnv<'jni>,
+    class: &str,
+    elements: &[T],
+    write: impl Fn(&mut Obj<'jni, '_>, &T) -> JResult<()>,
+) -> JResult<Vec<AutoObject<'jni>>> {
+    let mut objects: Vec<AutoObject<'jni>> = Vec::with_capacity(elements.len());
+    for element in elements {
+        let object = {
+            let obj = env.new_object(class, "()V", &[])?;
+            env.auto_local(obj)
+        };
+        write(&mut Obj::from(env, &object), element)?;
+        objects.push(object);
+    }
+    Ok(objects)
+}
+
+fn write_section(dst: &mut Obj, section: &ElfSection) -> JResult<()> {
+    dst.set_str("name", section.name.as_str())?;
+    dst.set_int("type", section.kind as i32)?;
+    dst.set_long("flags", section.flags as i64)?;
+    dst.set_long("address", section.address as i64)?;
+    dst.set_long("offset", section.offset as i64)?;
+    dst.set_long("size", section.size as i64)?;
+    dst.set_long("fileSize", section.file_size() as i64)?;
+    dst.set_bool("isExecutable", section.is_executable())
+}
+
+fn write_segment(dst: &mut Obj, segment: &ElfSegment) -> JResult<()> {
+    dst.set_int("type", segment.kind as i32)?;
+    dst.set_int("flags", segment.flags as i32)?;
+    dst.set_long("offset", segment.offset as i64)?;
+    dst.set_long("address", segment.address as i64)?;
+    dst.set_long("fileSize", segment.file_size as i64)?;
+    dst.set_long("memorySize", segment.memory_size as i64)
+}
+
+fn write_symbol(dst: &mut Obj, symbol: &ElfSymbol) -> JResult<()> {
+    dst.set_str("name", symbol.name.as_str())?;
+    dst.set_long("address", symbol.address as i64)?;
+    dst.set_long("size", symbol.size as i64)?;
+    dst.set_int("type", symbol.kind as i32)?;
+    dst.set_bool("isFunction", symbol.is_function())?;
+    dst.set_bool("isThumb", symbol.is_thumb)?;
+    dst.set_bool("isGlobal", symbol.is_global())?;
+    dst.set_bool("isDefined", symbol.is_defined())
+}
+
+fn write_mach_section(dst: &mut Obj, section: &MachSection) -> JResult<()> {
+    dst.set_str("name", section.name.as_str())?;
+    dst.set_int("type", section.kind() as i32)?;
+    dst.set_long("flags", section.flags as i64)?;
+    dst.set_long("address", section.address as i64)?;
+    dst.set_long("offset", section.offset as i64)?;
+    dst.set_long("size", section.size as i64)?;
+    dst.set_long("fileSize", section.file_size() as i64)?;
+    dst.set_bool("isExecutable", section.is_executable())
+}
+
+fn write_mach_segment(dst: &mut Obj, segment: &MachSegment) -> JResult<()> {
+    dst.set_str("name", segment.name.as_str())?;
+    dst.set_int("type", segment.command as i32)?;
+    dst.set_int("flags", segment.protection as i32)?;
+    dst.set_long("offset", segment.offset as i64)?;
+    dst.set_long("address", segment.address as i64)?;
+    dst.set_long("fileSize", segment.file_size as i64)?;
+    dst.set_long("memorySize", segment.memory_size as i64)
+}
+
+/// Write the mode selected for a file, or null if the architecture is not supported.
+fn write_mode(dst: &mut Obj, mode: Option<CapstoneMode>) -> JResult<()> {
+    let mode = match mode {
+        Some(mode) => {
+            let object = mode.to_object(dst.env())?;
+            dst.env().auto_local(object)
+        }
+        None => dst.env().auto_local(JObject::null()),
+    };
+    dst.set_object("mode", MODE_CLASS, &mode)
+}
+
+/// Write a parsed ELF file to a Java `ElfFile` object.
+pub fn write_elf<'jni>(
+    env: &mut JNIEnv<'jni>,
+    elf_object: &JObject<'jni>,
+    elf: &Elf,
+) -> JResult<()> {
+    let sections = new_objects(env, SECTION_CLASS, &elf.sections, write_section)?;
+    let segments = new_objects(env, SEGMENT_CLASS, &elf.segments, write_segment)?;
+    let symbols = new_objects(env, SYMBOL_CLASS, &elf.symbols, write_symbol)?;
+
+    let mut dst = Obj::from(env, elf_object);
+    write_mode(&mut dst, elf.mode())?;
+    dst.set_long("entryPoint", elf.entry as i64)?;
+    dst.set_int("machine", elf.machine as i32)?;
+    dst.set_int("fileType", elf.kind as i32)?;
+    dst.set_bool("is64Bit", elf.is_64)?;
+    dst.set_bool("isBigEndian", elf.big_endian)?;
+    dst.set_object_array("sections", SECTION_CLASS, &sections)?;
+    dst.set_object_array("segments", SEGMENT_CLASS, &segments)?;
+    dst.set_object_array("symbols", SYMBOL_CLASS, &symbols)
+}
+
+/// Write a parsed Mach-O file to a Java `MachOFile` object.
+pub fn write_macho<'jni>(
+    env: &mut JNIEnv<'jni>,
+    macho_object: &JObject<'jni>,
+    macho: &MachO,
+) -> JResult<()> {
+    let sections = new_objects(env, SECTION_CLASS, &macho.sections, write_mach_section)?;
+    let segments = new_objects(env, SEGMENT_CLASS, &macho.segments, write_mach_segment)?;
+    let symbols = new_objects(
+        env,
+        SYMBOL_CLASS,
+        &macho.symbols,
+        |dst, symbol: &MachSymbol| {
+            dst.set_str("name", symbol.name.as_str())?;
+            dst.set_long("address", symbol.address as i64)?;
+            dst.set_long("size", symbol.size as i64)?;
+            dst.set_int("type", symbol.kind as i32)?;
+            dst.set_bool("isFunction", macho.is_function(symbol))?;
+            dst.set_bool("isThumb", symbol.is_thumb)?;
+            dst.set_bool("isGlobal", symbol.is_global())?;
+            dst.set_bool("isDefined", symbol.is_defined())
+        },
+    )?;
+    let function_starts: Vec<i64> = macho.function_starts.iter().map(|a| *a as i64).collect();
+
+    let mut dst = Obj::from(env, macho_object);
+    write_mode(&mut dst, macho.mode())?;
+    dst.set_long("entryPoint", macho.entry as i64)?;
+    dst.set_int("cpuType", macho.cpu_type)?;
+    dst.set_int("cpuSubtype", macho.cpu_subtype)?;
+    dst.set_int("fileType", macho.kind as i32)?;
+    dst.set_bool("is64Bit", macho.is_64)?;
+    dst.set_long("sliceOffset", macho.slice_offset as i64)?;
+    dst.set_long_array("functionStarts", &function_starts)?;
+    dst.set_object_array("sections", SECTION_CLASS, &sections)?;
+    dst.set_object_array("segments", SEGMENT_CLASS, &segments)?;
+    dst.set_object_array("symbols", SYMBOL_CLASS, &symbols)
+}
+
+fn write_pe_section(dst: &mut Obj, section: &PeSection) -> JResult<()> {
+    dst.set_str("name", section.name.as_str())?;
+    dst.set_int("type", 0)?;
+    dst.set_long("flags", section.characteristics as i64)?;
+    dst.set_long("address", section.address as i64)?;
+    dst.set_long("offset", section.offset as i64)?;
+    dst.set_long("size", section.size as i64)?;
+    dst.set_long("fileSize", section.file_size as i64)?;
+    dst.set_bool("isExecutable", section.is_executable())
+}
+
+fn write_export(dst: &mut Obj, export: &PeExport) -> JResult<()> {
+    if !export.name.is_empty() {
+        dst.set_str("name", export.name.as_str())?;
+    }
+    dst.set_int("ordinal", export.ordinal as i32)?;
+    dst.set_long("address", export.address as i64)?;
+    if let Some(forwarder) = &export.forwarder {
+        dst.set_str("forwarder", forwarder.as_str())?;
+    }
+    Ok(())
+}
+
+fn write_import(dst: &mut Obj, import: &PeImport) -> JResult<()> {
+    dst.set_str("library", import.library.as_str())?;
+    if !import.name.is_empty() {
+        dst.set_str("name", import.name.as_str())?;
+    }
+    dst.set_int("ordinal", import.ordinal as i32)?;
+    dst.set_long("address", import.address as i64)
+}
+
+fn write_runtime_function(dst: &mut Obj, function: &RuntimeFunction) -> JResult<()> {
+    dst.set_long("startAddress", function.start as i64)?;
+    dst.set_long("endAddress", function.end as i64)?;
+    dst.set_long("unwindAddress", function.unwind_address as i64)
+}
+
+/// Write a parsed PE image to a Java `PeFile` object. Named exports become defined
+/// symbols and imports become undefined symbols at their address table slot.
+pub fn write_pe<'jni>(env: &mut JNIEnv<'jni>, pe_object: &JObject<'jni>, pe: &Pe) -> JResult<()> {
+    let sections = new_objects(env, SECTION_CLASS, &pe.sections, write_pe_section)?;
+    let exports = new_objects(env, EXPORT_CLASS, &pe.exports, write_export)?;
+    let imports = new_objects(env, IMPORT_CLASS, &pe.imports, write_import)?;
+    let runtime_functions = new_objects(
+        env,
+        RUNTIME_FUNCTION_CLASS,
+        &pe.runtime_functions,
+        write_runtime_function,
+    )?;
+
+    let defined: Vec<&PeExport> = pe
+        .exports
+        .iter()
+        .filter(|export| !export.name.is_empty() && export.forwarder.is_none())
+        .collect();
+    let mut symbols = new_objects(env, SYMBOL_CLASS, &defined, |dst, export| {
+        dst.set_str("name", export.name.as_str())?;
+        dst.set_long("address", export.address as i64)?;
+        dst.set_long("size", export.size as i64)?;
+        dst.set_bool("isFunction", pe.is_code(export.address))?;
+        dst.set_bool("isThumb", export.is_thumb)?;
+        dst.set_bool("isGlobal", true)?;
+        dst.set_bool("isDefined", true)
+    })?;
+    let named: Vec<&PeImport> = pe
+        .imports
+        .iter()
+        .filter(|import| !import.name.is_empty())
+        .collect();
+    symbols.extend(new_objects(env, SYMBOL_CLASS, &named, |dst, import| {
+        dst.set_str("name", import.name.as_str())?;
+        dst.set_long("address", import.address as i64)?;
+        dst.set_long("size", if pe.is_64 { 8 } else { 4 })?;
+        dst.set_bool("isFunction", true)?;
+        dst.set_bool("isGlobal", true)
+    })?);
+
+    let mut dst = Obj::from(env, pe_object);
+    write_mode(&mut dst, pe.mode())?;
+    dst.set_long("entryPoint", pe.entry as i64)?;
+    dst.set_int("machine", pe.machine as i32)?;
+    dst.set_int("characteristics", pe.characteristics as i32)?;
+    dst.set_bool("is64Bit", pe.is_64)?;
+    dst.set_long("imageBase", pe.image_base as i64)?;
+    dst.set_object_array("sections", SECTION_CLASS, &sections)?;
+    dst.set_object_array("symbols", SYMBOL_CLASS, &symbols)?;
+    dst.set_object_array("exports", EXPORT_CLASS, &exports)?;
+    dst.set_object_array("imports", IMPORT_CLASS, &imports)?;
+    dst.set_object_array(
+        "runtimeFunctions",
+        RUNTIME_FUNCTION_CLASS,
+        &runtime_functions,
+    )
+}
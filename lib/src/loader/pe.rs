@@ -0,0 +1,453 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use std::collections::BTreeSet;
+use std::ops::Bound;
+
+use crate::capstone::mode::CapstoneMode;
+use crate::loader::reader::Reader;
+use crate::loader::Result;
+
+const PE_SIGNATURE: &[u8] = b"PE\0\0";
+const PE32_MAGIC: u16 = 0x10b;
+const PE32_PLUS_MAGIC: u16 = 0x20b;
+
+pub const IMAGE_FILE_MACHINE_I386: u16 = 0x14c;
+pub const IMAGE_FILE_MACHINE_ARM: u16 = 0x1c0;
+pub const IMAGE_FILE_MACHINE_ARMNT: u16 = 0x1c4;
+pub const IMAGE_FILE_MACHINE_AMD64: u16 = 0x8664;
+pub const IMAGE_FILE_MACHINE_ARM64: u16 = 0xaa64;
+
+const IMAGE_DIRECTORY_ENTRY_EXPORT: u64 = 0;
+const IMAGE_DIRECTORY_ENTRY_IMPORT: u64 = 1;
+const IMAGE_DIRECTORY_ENTRY_EXCEPTION: u64 = 3;
+
+const IMAGE_SCN_CNT_CODE: u32 = 0x20;
+const IMAGE_SCN_MEM_EXECUTE: u32 = 0x20000000;
+
+/// A section, mapped at `ImageBase + VirtualAddress`.
+#[derive(Debug, Clone)]
+pub struct PeSection {
+    pub name: String,
+    pub characteristics: u32,
+    pub address: u64,
+    pub offset: u64,
+    /// The size of the section in memory.
+    pub size: u64,
+    /// The number of initialized bytes in the file.
+    pub file_size: u64,
+}
+
+impl PeSection {
+    pub fn is_executable(&self) -> bool {
+        self.characteristics & (IMAGE_SCN_CNT_CODE | IMAGE_SCN_MEM_EXECUTE) != 0
+    }
+}
+
+/// An entry of the export directory.
+#[derive(Debug, Clone)]
+pub struct PeExport {
+    /// The export name, empty if only exported by ordinal.
+    pub name: String,
+    pub ordinal: u32,
+    /// The exported address with the Thumb bit cleared, 0 for forwarders.
+    pub address: u64,
+    /// The distance to the end of the runtime function or the next export.
+    pub size: u64,
+    /// The target of a forwarded export, e.g. `NTDLL.RtlAllocateHeap`.
+    pub forwarder: Option<String>,
+    pub is_thumb: bool,
+}
+
+/// A function imported through the import directory.
+#[derive(Debug, Clone)]
+pub struct PeImport {
+    pub library: String,
+    /// The imported name, empty if imported by ordinal.
+    pub name: String,
+    /// The ordinal for imports by ordinal, otherwise the name hint.
+    pub ordinal: u16,
+    /// The address of the import address table slot.
+    pub address: u64,
+}
+
+/// An entry of the `.pdata` function table.
+#[derive(Debug, Clone)]
+pub struct RuntimeFunction {
+    pub start: u64,
+    pub end: u64,
+    /// The address of the unwind information, 0 if it is packed into the entry.
+    pub unwind_address: u64,
+}
+
+/// A parsed PE image.
+#[derive(Debug)]
+pub struct Pe {
+    /// The image uses the PE32+ optional header.
+    pub is_64: bool,
+    pub machine: u16,
+    pub characteristics: u16,
+    pub image_base: u64,
+    /// The entry point, 0 if the image has none.
+    pub entry: u64,
+    pub sections: Vec<PeSection>,
+    pub exports: Vec<PeExport>,
+    pub imports: Vec<PeImport>,
+    /// The `.pdata` table, ordered by address.
+    pub runtime_functions: Vec<RuntimeFunction>,
+}
+
+impl Pe {
+    /// Parse the headers, exports, imports and exception table of a PE image.
+    pub fn parse(data: &[u8]) -> Result<Pe> {
+        if !data.starts_with(b"MZ") {
+            return Err("not a PE file".into());
+        }
+
+        let r = Reader::new(data, false);
+        let header = r.u32(0x3c)? as u64;
+        if r.bytes(header, 4)? != PE_SIGNATURE {
+            return Err("invalid PE signature".into());
+        }
+
+        let coff = header + 4;
+        let section_count = r.u16(coff + 2)? as u64;
+        let optional = coff + 20;
+        let optional_size = r.u16(coff + 16)? as u64;
+
+        let is_64 = match r.u16(optional)? {
+            PE32_MAGIC => false,
+            PE32_PLUS_MAGIC => true,
+            magic => return Err(format!("invalid optional header magic {:#x}", magic).into()),
+        };
+        let (image_base, directories) = if is_64 {
+            (r.u64(optional + 24)?, optional + 108)
+        } else {
+            (r.u32(optional + 28)? as u64, optional + 92)
+        };
+
+        let mut pe = Pe {
+            is_64,
+            machine: r.u16(coff)?,
+            characteristics: r.u16(coff + 18)?,
+            image_base,
+            entry: 0,
+            sections: Vec::new(),
+            exports: Vec::new(),
+            imports: Vec::new(),
+            runtime_functions: Vec::new(),
+        };
+
+        let sections = optional + optional_size;
+        for i in 0..section_count {
+            let offset = sections + i * 40;
+            let virtual_size = r.u32(offset + 8)? as u64;
+            let raw_size = r.u32(offset + 16)? as u64;
+            let size = if virtual_size == 0 {
+                raw_size
+            } else {
+                virtual_size
+            };
+            let address = pe.address(r.u32(offset + 12)? as u64)?;
+            if address.checked_add(size).is_none() {
+                return Err(
+                    format!("section at {:#x} overflows the address space", address).into(),
+                );
+            }
+            pe.sections.push(PeSection {
+                name: r.str(offset, 8)?,
+                characteristics: r.u32(offset + 36)?,
+                address,
+                offset: r.u32(offset + 20)? as u64,
+                size,
+                file_size: if virtual_size == 0 {
+                    raw_size
+                } else {
+                    raw_size.min(virtual_size)
+                },
+            });
+        }
+
+        let entry = r.u32(optional + 16)? as u64;
+        if entry != 0 {
+            pe.entry = pe.clear_thumb_bit(pe.address(entry)?);
+        }
+
+        // Data directories beyond the declared count are absent
+        let directory_count = r.u32(directories)? as u64;
+        let directory = |index: u64| -> Result<Option<(u64, u64)>> {
+            if index >= directory_count {
+                return Ok(None);
+            }
+            let offset = directories + 4 + index * 8;
+            let (rva, size) = (r.u32(offset)? as u64, r.u32(offset + 4)? as u64);
+            Ok((rva != 0).then_some((rva, size)))
+        };
+
+        if let Some((rva, size)) = directory(IMAGE_DIRECTORY_ENTRY_EXCEPTION)? {
+            pe.parse_runtime_functions(&r, rva, size)?;
+        }
+        if let Some((rva, size)) = directory(IMAGE_DIRECTORY_ENTRY_EXPORT)? {
+            pe.parse_exports(&r, rva, size)?;
+        }
+        if let Some((rva, _)) = directory(IMAGE_DIRECTORY_ENTRY_IMPORT)? {
+            pe.parse_imports(&r, rva)?;
+        }
+
+        Ok(pe)
+    }
+
+    /// Translate a relative virtual address to a virtual address.
+    fn address(&self, rva: u64) -> Result<u64> {
+        self.image_base
+            .checked_add(rva)
+            .ok_or_else(|| format!("RVA {:#x} overflows the address space", rva).into())
+    }
+
+    /// Translate a relative virtual address to a file offset.
+    fn file_offset(&self, rva: u64) -> Result<u64> {
+        let address = self.address(rva)?;
+        self.sections
+            .iter()
+            .find(|section| {
+                (section.address..section.address.saturating_add(section.file_size))
+                    .contains(&address)
+            })
+            .map(|section| section.offset + (address - section.address))
+            .ok_or_else(|| format!("RVA {:#x} is not mapped from the file", rva).into())
+    }
+
+    fn read_rva_str(&self, r: &Reader, rva: u64) -> Result<String> {
+        r.str(self.file_offset(rva)?, usize::MAX)
+    }
+
+    fn clear_thumb_bit(&self, address: u64) -> u64 {
+        if self.machine == IMAGE_FILE_MACHINE_ARMNT {
+            address & !1
+        } else {
+            address
+        }
+    }
+
+    fn parse_runtime_functions(&mut self, r: &Reader, rva: u64, size: u64) -> Result<()> {
+        let offset = self.file_offset(rva)?;
+        let arm = matches!(
+            self.machine,
+            IMAGE_FILE_MACHINE_ARM64 | IMAGE_FILE_MACHINE_ARMNT
+        );
+        // Function lengths are counted in instructions of 4 bytes, or halfwords on ARMv7
+        let unit = if self.machine == IMAGE_FILE_MACHINE_ARMNT {
+            2
+        } else {
+            4
+        };
+        let entry_size = if arm { 8 } else { 12 };
+
+        for i in 0..size / entry_size {
+            let entry = offset + i * entry_size;
+            let start = self.clear_thumb_bit(r.u32(entry)? as u64);
+
+            let (end, unwind) = if !arm {
+                (Some(r.u32(entry + 4)? as u64), r.u32(entry + 8)? as u64)
+            } else {
+                let unwind = r.u32(entry + 4)?;
+                if unwind & 0x3 != 0 {
+                    // Packed unwind data holds the length in bits 2-12
+                    let length = ((unwind >> 2) & 0x7ff) as u64;
+                    (start.checked_add(length * unit), 0)
+                } else {
+                    // The first word of .xdata holds the length in bits 0-17
+                    let xdata = r.u32(self.file_offset(unwind as u64)?)?;
+                    let length = (xdata & 0x3ffff) as u64;
+                    (start.checked_add(length * unit), unwind as u64)
+                }
+            };
+
+            // Skip entries that cannot be mapped rather than rejecting the image
+            let (Some(start), Some(end)) = (
+                self.image_base.checked_add(start),
+                end.and_then(|end| self.image_base.checked_add(end)),
+            ) else {
+                continue;
+            };
+            let unwind_address = if unwind == 0 {
+                0
+            } else {
+                match self.image_base.checked_add(unwind) {
+                    Some(address) => address,
+                    None => continue,
+                }
+            };
+            self.runtime_functions.push(RuntimeFunction {
+                start,
+                end,
+                unwind_address,
+            });
+        }
+
+        self.runtime_functions
+            .sort_by_key(|function| function.start);
+        Ok(())
+    }
+
+    fn parse_exports(&mut self, r: &Reader, rva: u64, size: u64) -> Result<()> {
+        let directory = self.file_offset(rva)?;
+        let base = r.u32(directory + 16)?;
+        let function_count = r.u32(directory + 20)? as u64;
+        let name_count = r.u32(directory + 24)? as u64;
+        let functions = self.file_offset(r.u32(directory + 28)? as u64)?;
+
+        // Check the address table lies within the file before sizing anything by its count
+        r.bytes(functions, function_count * 4)?;
+
+        let mut names = vec![String::new(); function_count as usize];
+        if name_count > 0 {
+            let name_table = self.file_offset(r.u32(directory + 32)? as u64)?;
+            let ordinal_table = self.file_offset(r.u32(directory + 36)? as u64)?;
+            for i in 0..name_count {
+                let index = r.u16(ordinal_table + i * 2)? as usize;
+                let name = self.read_rva_str(r, r.u32(name_table + i * 4)? as u64)?;
+                if let Some(slot) = names.get_mut(index) {
+                    *slot = name;
+                }
+            }
+        }
+
+        for (i, name) in names.into_iter().enumerate() {
+            let function = r.u32(functions + i as u64 * 4)? as u64;
+            if function == 0 {
+                continue;
+            }
+
+            // Addresses inside the export directory name a function of another library
+            let forwarder = if (rva..rva.saturating_add(size)).contains(&function) {
+                Some(self.read_rva_str(r, function)?)
+            } else {
+                None
+            };
+            let address = match forwarder {
+                Some(_) => 0,
+                None => self.address(function)?,
+            };
+            let is_thumb = self.machine == IMAGE_FILE_MACHINE_ARMNT && address & 1 == 1;
+
+            self.exports.push(PeExport {
+                name,
+                ordinal: base + i as u32,
+                address: address & !(is_thumb as u64),
+                size: 0,
+                forwarder,
+                is_thumb,
+            });
+        }
+
+        self.compute_export_sizes();
+        Ok(())
+    }
+
+    /// Take export sizes from the runtime function table, or from the next export or
+    /// function start in the same section.
+    fn compute_export_sizes(&mut self) {
+        let boundaries: BTreeSet<u64> = self
+            .exports
+            .iter()
+            .filter(|export| export.forwarder.is_none())
+            .map(|export| export.address)
+            .chain(self.runtime_functions.iter().map(|function| function.start))
+            .collect();
+
+        for export in &mut self.exports {
+            if export.forwarder.is_some() {
+                continue;
+            }
+            if let Some(function) = self
+                .runtime_functions
+                .iter()
+                .find(|function| function.start == export.address)
+            {
+                export.size = function.end.saturating_sub(function.start);
+                continue;
+            }
+
+            let Some(section) = self.sections.iter().find(|section| {
+                (section.address..section.address.saturating_add(section.size))
+                    .contains(&export.address)
+            }) else {
+                continue;
+            };
+            let section_end = section.address.saturating_add(section.size);
+            let end = boundaries
+                .range((Bound::Excluded(export.address), Bound::Unbounded))
+                .next()
+                .map_or(section_end, |next| (*next).min(section_end));
+            export.size = end - export.address;
+        }
+    }
+
+    fn parse_imports(&mut self, r: &Reader, rva: u64) -> Result<()> {
+        let word_size = if self.is_64 { 8 } else { 4 };
+        let ordinal_flag = 1u64 << (word_size * 8 - 1);
+
+        // Descriptors are terminated by an all-zero entry
+        let mut descriptor = self.file_offset(rva)?;
+        loop {
+            let lookup = r.u32(descriptor)? as u64;
+            let name = r.u32(descriptor + 12)? as u64;
+            let thunks = r.u32(descriptor + 16)? as u64;
+            if name == 0 && thunks == 0 {
+                break;
+            }
+            let library = self.read_rva_str(r, name)?;
+
+            // Bound images overwrite the address table, so prefer the lookup table
+            let table = self.file_offset(if lookup != 0 { lookup } else { thunks })?;
+            for i in 0.. {
+                let thunk = r.word(table + i * word_size, self.is_64)?;
+                if thunk == 0 {
+                    break;
+                }
+                let (name, ordinal) = if thunk & ordinal_flag != 0 {
+                    (String::new(), thunk as u16)
+                } else {
+                    let hint = self.file_offset(thunk & 0x7fffffff)?;
+                    (r.str(hint + 2, usize::MAX)?, r.u16(hint)?)
+                };
+                let Some(address) = self.image_base.checked_add(thunks + i * word_size) else {
+                    continue;
+                };
+                self.imports.push(PeImport {
+                    library: library.clone(),
+                    name,
+                    ordinal,
+                    address,
+                });
+            }
+
+            descriptor += 20;
+        }
+        Ok(())
+    }
+
+    /// Check whether an address is in an executable section.
+    pub fn is_code(&self, address: u64) -> bool {
+        self.sections.iter().any(|section| {
+            section.is_executable()
+                && (section.address..section.address.saturating_add(section.size))
+                    .contains(&address)
+        })
+    }
+
+    /// Select the disassembly mode from the machine. ARMv7 images are always Thumb-2.
+    pub fn mode(&self) -> Option<CapstoneMode> {
+        match self.machine {
+            IMAGE_FILE_MACHINE_ARM => Some(CapstoneMode::ARM32),
+            IMAGE_FILE_MACHINE_ARMNT => Some(CapstoneMode::Thumb),
+            IMAGE_FILE_MACHINE_ARM64 => Some(CapstoneMode::ARM64),
+            IMAGE_FILE_MACHINE_I386 => Some(CapstoneMode::X86_32),
+            IMAGE_FILE_MACHINE_AMD64 => Some(CapstoneMode::X86_64),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,114 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use crate::loader::Result;
+
+/// Bounds-checked reader for binary file structures.
+#[derive(Clone, Copy)]
+pub struct Reader<'a> {
+    data: &'a [u8],
+    big_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8], big_endian: bool) -> Reader<'a> {
+        Reader { data, big_endian }
+    }
+
+    /// Get `size` bytes starting at the given offset.
+    pub fn bytes(&self, offset: u64, size: u64) -> Result<&'a [u8]> {
+        let start = usize::try_from(offset)?;
+        let end = start.checked_add(usize::try_from(size)?);
+        end.and_then(|end| self.data.get(start..end))
+            .ok_or_else(|| {
+                format!(
+                    "truncated file, cannot read {} bytes at {:#x}",
+                    size, offset
+                )
+                .into()
+            })
+    }
+
+    fn array<const N: usize>(&self, offset: u64) -> Result<[u8; N]> {
+        let mut bytes: [u8; N] = self.bytes(offset, N as u64)?.try_into()?;
+        if self.big_endian {
+            bytes.reverse();
+        }
+        Ok(bytes)
+    }
+
+    pub fn u8(&self, offset: u64) -> Result<u8> {
+        Ok(self.bytes(offset, 1)?[0])
+    }
+
+    pub fn u16(&self, offset: u64) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.array(offset)?))
+    }
+
+    pub fn u32(&self, offset: u64) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.array(offset)?))
+    }
+
+    pub fn u64(&self, offset: u64) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.array(offset)?))
+    }
+
+    /// Read a 64-bit value if `wide` is set, otherwise a 32-bit value.
+    pub fn word(&self, offset: u64, wide: bool) -> Result<u64> {
+        if wide {
+            self.u64(offset)
+        } else {
+            self.u32(offset).map(u64::from)
+        }
+    }
+
+    /// Read a NUL-terminated string, or up to `max` bytes if no terminator is found.
+    pub fn str(&self, offset: u64, max: usize) -> Result<String> {
+        let start = usize::try_from(offset)?;
+        let bytes = self
+            .data
+            .get(start..)
+            .ok_or_else(|| format!("string offset {:#x} out of bounds", offset))?;
+        let bytes = &bytes[..bytes.len().min(max)];
+        let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+        Ok(String::from_utf8_lossy(&bytes[..end]).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_both_byte_orders() {
+        let data = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08];
+        let le = Reader::new(&data, false);
+        let be = Reader::new(&data, true);
+
+        assert_eq!(le.u16(0).unwrap(), 0x0201);
+        assert_eq!(be.u16(0).unwrap(), 0x0102);
+        assert_eq!(le.u32(4).unwrap(), 0x08070605);
+        assert_eq!(be.u32(4).unwrap(), 0x05060708);
+        assert_eq!(le.u64(0).unwrap(), 0x0807060504030201);
+        assert_eq!(le.word(0, false).unwrap(), 0x04030201);
+    }
+
+    #[test]
+    fn rejects_truncated_reads() {
+        let reader = Reader::new(&[0x01, 0x02], false);
+        assert!(reader.u32(0).is_err());
+        assert!(reader.u16(1).is_err());
+        assert!(reader.bytes(u64::MAX, 2).is_err());
+    }
+
+    #[test]
+    fn reads_strings() {
+        let reader = Reader::new(b"main\0puts", false);
+        assert_eq!(reader.str(0, 16).unwrap(), "main");
+        assert_eq!(reader.str(5, 16).unwrap(), "puts");
+        assert_eq!(reader.str(5, 2).unwrap(), "pu");
+        assert!(reader.str(10, 16).is_err());
+    }
+}
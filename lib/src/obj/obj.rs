@@ -107,6 +107,44 @@ impl<'jni, 'a> Obj<'jni, 'a> {
         );
     }
 
+    /// Create a byte array and set it to a field.
+    pub fn set_byte_array<S>(&mut self, field: S, src: &[u8]) -> JResult<()>
+    where
+        S: Into<JNIString>,
+    {
+        let array = {
+            let array = self.env.byte_array_from_slice(src)?;
+            self.env.auto_local(array)
+        };
+        self.set_field(field, "[B", JValue::Object(&array))
+    }
+
+    /// Create an int array and set it to a field.
+    pub fn set_int_array<S>(&mut self, field: S, src: &[i32]) -> JResult<()>
+    where
+        S: Into<JNIString>,
+    {
+        let array = {
+            let array = self.env.new_int_array(src.len() as i32)?;
+            self.env.auto_local(array)
+        };
+        self.env.set_int_array_region(&array, 0, src)?;
+        self.set_field(field, "[I", JValue::Object(&array))
+    }
+
+    /// Create a long array and set it to a field.
+    pub fn set_long_array<S>(&mut self, field: S, src: &[i64]) -> JResult<()>
+    where
+        S: Into<JNIString>,
+    {
+        let array = {
+            let array = self.env.new_long_array(src.len() as i32)?;
+            self.env.auto_local(array)
+        };
+        self.env.set_long_array_region(&array, 0, src)?;
+        self.set_field(field, "[J", JValue::Object(&array))
+    }
+
     /// Create an object array and set it to a field.
     pub fn set_object_array<'b, S, O>(
         &mut self,
@@ -136,6 +174,29 @@ impl<'jni, 'a> Obj<'jni, 'a> {
         )
     }
 
+    /// Create a string array and set it to a field. `None` elements are left null.
+    pub fn set_str_array<S>(&mut self, field: S, src: &[Option<&str>]) -> JResult<()>
+    where
+        S: Into<JNIString>,
+    {
+        let array = {
+            let array =
+                self.env
+                    .new_object_array(src.len() as i32, "java/lang/String", JObject::null())?;
+            self.env.auto_local(array)
+        };
+
+        // Strings are released as they are stored, so large arrays do not exhaust local refs
+        for (i, str) in src.iter().enumerate() {
+            if let Some(str) = str {
+                let str = self.env.auto_local(self.env.new_string(str)?);
+                self.env.set_object_array_element(&array, i as i32, str)?;
+            }
+        }
+
+        self.set_field(field, "[Ljava/lang/String;", JValue::Object(&array))
+    }
+
     /// Set a primitive field.
     fn set_primitive_field<S>(&mut self, field: S, value: JValue) -> JResult<()>
     where
@@ -0,0 +1,260 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use capstone::arch::arm::{ArmInsn, ArmOpMem, ArmOperand, ArmOperandType, ArmShift};
+use capstone::{InsnId, InsnIdInt, RegId};
+
+use crate::operand::{
+    Addressing, OperandValue, ShiftType, MEM_OFFSET, MEM_POST_INDEXED, MEM_PRE_INDEXED,
+};
+
+/// Java `operandType` codes.
+pub const OP_INVALID: i8 = 0;
+pub const OP_REG: i8 = 1;
+pub const OP_IMM: i8 = 2;
+pub const OP_MEM: i8 = 3;
+pub const OP_FP: i8 = 4;
+pub const OP_CIMM: i8 = 5;
+pub const OP_PIMM: i8 = 6;
+pub const OP_SETEND: i8 = 7;
+pub const OP_SYSREG: i8 = 8;
+
+/// Java `shiftType` codes.
+pub const SFT_INVALID: ShiftType = 0;
+pub const SFT_ASR: ShiftType = 1;
+pub const SFT_LSL: ShiftType = 2;
+pub const SFT_LSR: ShiftType = 3;
+pub const SFT_ROR: ShiftType = 4;
+pub const SFT_RRX: ShiftType = 5;
+pub const SFT_ASR_REG: ShiftType = 6;
+pub const SFT_LSL_REG: ShiftType = 7;
+pub const SFT_LSR_REG: ShiftType = 8;
+pub const SFT_ROR_REG: ShiftType = 9;
+pub const SFT_RRX_REG: ShiftType = 10;
+
+/// Operand type codes with the names of their Java constants.
+pub const OPERAND_TYPES: &[(&str, i8)] = &[
+    ("ARM_OP_INVALID", OP_INVALID),
+    ("ARM_OP_REG", OP_REG),
+    ("ARM_OP_IMM", OP_IMM),
+    ("ARM_OP_MEM", OP_MEM),
+    ("ARM_OP_FP", OP_FP),
+    ("ARM_OP_CIMM", OP_CIMM),
+    ("ARM_OP_PIMM", OP_PIMM),
+    ("ARM_OP_SETEND", OP_SETEND),
+    ("ARM_OP_SYSREG", OP_SYSREG),
+];
+
+/// Shift type codes with the names of their Java constants.
+pub const SHIFT_TYPES: &[(&str, ShiftType)] = &[
+    ("ARM_SFT_INVALID", SFT_INVALID),
+    ("ARM_SFT_ASR", SFT_ASR),
+    ("ARM_SFT_LSL", SFT_LSL),
+    ("ARM_SFT_LSR", SFT_LSR),
+    ("ARM_SFT_ROR", SFT_ROR),
+    ("ARM_SFT_RRX", SFT_RRX),
+    ("ARM_SFT_ASR_REG", SFT_ASR_REG),
+    ("ARM_SFT_LSL_REG", SFT_LSL_REG),
+    ("ARM_SFT_LSR_REG", SFT_LSR_REG),
+    ("ARM_SFT_ROR_REG", SFT_ROR_REG),
+    ("ARM_SFT_RRX_REG", SFT_RRX_REG),
+];
+
+/// Get the shift type code and the shift value, or register id, of an ARM shift.
+pub fn shift(shift: ArmShift) -> (ShiftType, u32) {
+    match shift {
+        ArmShift::Invalid => (SFT_INVALID, 0),
+        ArmShift::Asr(v) => (SFT_ASR, v),
+        ArmShift::Lsl(v) => (SFT_LSL, v),
+        ArmShift::Lsr(v) => (SFT_LSR, v),
+        ArmShift::Ror(v) => (SFT_ROR, v),
+        ArmShift::Rrx(v) => (SFT_RRX, v),
+        ArmShift::AsrReg(v) => (SFT_ASR_REG, v.0 as u32),
+        ArmShift::LslReg(v) => (SFT_LSL_REG, v.0 as u32),
+        ArmShift::LsrReg(v) => (SFT_LSR_REG, v.0 as u32),
+        ArmShift::RorReg(v) => (SFT_ROR_REG, v.0 as u32),
+        ArmShift::RrxReg(v) => (SFT_RRX_REG, v.0 as u32),
+    }
+}
+
+/// Get the Java `operandType` code and the value of an ARM operand.
+pub fn operand_value(op: &ArmOperandType) -> (i8, OperandValue<&ArmOpMem>) {
+    match op {
+        ArmOperandType::Invalid => (OP_INVALID, OperandValue::None),
+        ArmOperandType::Reg(v) => (OP_REG, OperandValue::Reg(*v)),
+        ArmOperandType::Imm(v) => (OP_IMM, OperandValue::Int(*v)),
+        ArmOperandType::Mem(v) => (OP_MEM, OperandValue::Mem(v)),
+        ArmOperandType::Fp(v) => (OP_FP, OperandValue::Double(*v)),
+        ArmOperandType::Cimm(v) => (OP_CIMM, OperandValue::Int(*v)),
+        ArmOperandType::Pimm(v) => (OP_PIMM, OperandValue::Int(*v)),
+        ArmOperandType::Setend(v) => (OP_SETEND, OperandValue::Byte(*v as i8)),
+        ArmOperandType::SysReg(v) => (OP_SYSREG, OperandValue::Int(v.0 as i32)),
+    }
+}
+
+/// Whether an instruction is a NEON element or structure load or store, which
+/// post-increments its base register by the transfer size when written `[rn]!`.
+fn is_structure_access(id: InsnId) -> bool {
+    [
+        ArmInsn::ARM_INS_VLD1,
+        ArmInsn::ARM_INS_VLD2,
+        ArmInsn::ARM_INS_VLD3,
+        ArmInsn::ARM_INS_VLD4,
+        ArmInsn::ARM_INS_VST1,
+        ArmInsn::ARM_INS_VST2,
+        ArmInsn::ARM_INS_VST3,
+        ArmInsn::ARM_INS_VST4,
+    ]
+    .iter()
+    .any(|insn| id.0 == *insn as InsnIdInt)
+}
+
+/// Get the addressing of the memory operand at `index`, given the writeback flag of the
+/// instruction. Capstone reports the offset of a post-indexed access as an extra
+/// operand after the memory operand, and a pre-indexed access only by the writeback flag.
+/// The writeback flag is missing on NEON loads and stores with a post-index register,
+/// and an operand after the memory operand of `ldc`/`stc` is an option rather than an
+/// offset unless the base is written back. The implicit offset of NEON `[rn]!` is not
+/// reported.
+pub fn addressing(
+    id: InsnId,
+    operands: &[ArmOperand],
+    index: usize,
+    writeback: bool,
+) -> Addressing {
+    let structure_access = is_structure_access(id);
+    let post_index = operands
+        .get(index + 1)
+        .filter(|_| writeback || structure_access)
+        .filter(|next| {
+            matches!(
+                next.op_type,
+                ArmOperandType::Imm(_) | ArmOperandType::Reg(_)
+            )
+        });
+
+    let (mode, index_op) = match post_index {
+        Some(next) => (MEM_POST_INDEXED, next),
+        None if writeback && structure_access => (MEM_POST_INDEXED, &operands[index]),
+        None if writeback => (MEM_PRE_INDEXED, &operands[index]),
+        None => (MEM_OFFSET, &operands[index]),
+    };
+
+    let (shift_type, shift_value) = shift(index_op.shift);
+    let mut addressing = Addressing {
+        mode,
+        post_index_imm: 0,
+        post_index_reg: RegId(0),
+        shift_type,
+        shift_value,
+        extend: 0,
+        subtracted: index_op.subtracted,
+    };
+
+    match index_op.op_type {
+        ArmOperandType::Imm(imm) if post_index.is_some() => {
+            // The sign of a subtracted immediate is folded into the offset
+            addressing.post_index_imm = if index_op.subtracted {
+                -(imm as i64)
+            } else {
+                imm as i64
+            };
+            addressing.subtracted = false;
+        }
+        ArmOperandType::Reg(reg) if post_index.is_some() => addressing.post_index_reg = reg,
+        _ => {}
+    }
+
+    addressing
+}
+
+#[cfg(test)]
+mod tests {
+    use capstone::arch::DetailsArchInsn;
+    use capstone::RegId;
+
+    use super::*;
+    use crate::analysis::testing::disassemble;
+    use crate::capstone::mode::CapstoneMode;
+
+    #[test]
+    fn shift_codes() {
+        assert_eq!(shift(ArmShift::Invalid), (0, 0));
+        assert_eq!(shift(ArmShift::Lsl(3)), (2, 3));
+        assert_eq!(shift(ArmShift::Rrx(1)), (5, 1));
+        assert_eq!(shift(ArmShift::RorReg(RegId(66))), (9, 66));
+    }
+
+    #[test]
+    fn operand_codes() {
+        assert_eq!(
+            operand_value(&ArmOperandType::Invalid),
+            (0, OperandValue::None)
+        );
+        assert_eq!(
+            operand_value(&ArmOperandType::Reg(RegId(12))),
+            (1, OperandValue::Reg(RegId(12)))
+        );
+        assert_eq!(
+            operand_value(&ArmOperandType::Imm(-4)),
+            (2, OperandValue::Int(-4))
+        );
+        assert_eq!(
+            operand_value(&ArmOperandType::Fp(0.5)),
+            (4, OperandValue::Double(0.5))
+        );
+        assert_eq!(
+            operand_value(&ArmOperandType::Pimm(15)),
+            (6, OperandValue::Int(15))
+        );
+    }
+
+    #[test]
+    fn addressing_modes() {
+        let code = [
+            0x04, 0x00, 0x31, 0xe5, // ldr r0, [r1, #-4]!
+            0x04, 0x00, 0x11, 0xe4, // ldr r0, [r1], #-4
+            0x02, 0x01, 0x11, 0xe6, // ldr r0, [r1], -r2, lsl #2
+            0x02, 0x07, 0x20, 0xf4, // vld1.8 {d0}, [r0], r2
+            0x0d, 0x07, 0x20, 0xf4, // vld1.8 {d0}, [r0]!
+        ];
+        let addressing = disassemble(CapstoneMode::ARM32, &code, 0x1000, |cs, _, insns, _| {
+            insns
+                .iter()
+                .map(|insn| {
+                    let detail = cs.insn_detail(insn).unwrap();
+                    let arch = detail.arch_detail();
+                    let arch = arch.arm().unwrap();
+                    let operands: Vec<_> = arch.operands().collect();
+                    let index = operands
+                        .iter()
+                        .position(|op| matches!(op.op_type, ArmOperandType::Mem(_)))
+                        .unwrap();
+                    addressing(insn.id(), &operands, index, arch.writeback())
+                })
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(addressing[0].mode, MEM_PRE_INDEXED);
+        assert_eq!(
+            (addressing[1].mode, addressing[1].post_index_imm),
+            (MEM_POST_INDEXED, -4)
+        );
+        assert_eq!(addressing[2].mode, MEM_POST_INDEXED);
+        assert_eq!(addressing[2].post_index_reg, RegId(68));
+        assert_eq!(
+            (addressing[2].shift_type, addressing[2].shift_value),
+            (SFT_LSL, 2)
+        );
+        assert!(addressing[2].subtracted);
+        assert_eq!(addressing[3].mode, MEM_POST_INDEXED);
+        assert_eq!(addressing[3].post_index_reg, RegId(68));
+        assert_eq!(
+            (addressing[4].mode, addressing[4].post_index_imm),
+            (MEM_POST_INDEXED, 0)
+        );
+        assert!(addressing[4].base_updated());
+    }
+}
@@ -0,0 +1,225 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use capstone::arch::arm64::{Arm64OpMem, Arm64Operand, Arm64OperandType, Arm64Shift};
+use capstone::RegId;
+
+use crate::operand::{
+    Addressing, OperandValue, ShiftType, MEM_OFFSET, MEM_POST_INDEXED, MEM_PRE_INDEXED,
+};
+
+/// Java `operandType` codes.
+pub const OP_INVALID: i8 = 0;
+pub const OP_REG: i8 = 1;
+pub const OP_IMM: i8 = 2;
+pub const OP_MEM: i8 = 3;
+pub const OP_FP: i8 = 4;
+pub const OP_CIMM: i8 = 5;
+pub const OP_REGMRS: i8 = 6;
+pub const OP_REGMSR: i8 = 7;
+pub const OP_PSTATE: i8 = 8;
+pub const OP_SYS: i8 = 9;
+pub const OP_PREFETCH: i8 = 10;
+pub const OP_BARRIER: i8 = 11;
+
+/// Java `shiftType` codes.
+pub const SFT_INVALID: ShiftType = 0;
+pub const SFT_LSL: ShiftType = 1;
+pub const SFT_MSL: ShiftType = 2;
+pub const SFT_LSR: ShiftType = 3;
+pub const SFT_ASR: ShiftType = 4;
+pub const SFT_ROR: ShiftType = 5;
+
+/// Operand type codes with the names of their Java constants.
+pub const OPERAND_TYPES: &[(&str, i8)] = &[
+    ("ARM64_OP_INVALID", OP_INVALID),
+    ("ARM64_OP_REG", OP_REG),
+    ("ARM64_OP_IMM", OP_IMM),
+    ("ARM64_OP_MEM", OP_MEM),
+    ("ARM64_OP_FP", OP_FP),
+    ("ARM64_OP_CIMM", OP_CIMM),
+    ("ARM64_OP_REGMRS", OP_REGMRS),
+    ("ARM64_OP_REGMSR", OP_REGMSR),
+    ("ARM64_OP_PSTATE", OP_PSTATE),
+    ("ARM64_OP_SYS", OP_SYS),
+    ("ARM64_OP_PREFETCH", OP_PREFETCH),
+    ("ARM64_OP_BARRIER", OP_BARRIER),
+];
+
+/// Shift type codes with the names of their Java constants.
+pub const SHIFT_TYPES: &[(&str, ShiftType)] = &[
+    ("ARM64_SFT_INVALID", SFT_INVALID),
+    ("ARM64_SFT_LSL", SFT_LSL),
+    ("ARM64_SFT_MSL", SFT_MSL),
+    ("ARM64_SFT_LSR", SFT_LSR),
+    ("ARM64_SFT_ASR", SFT_ASR),
+    ("ARM64_SFT_ROR", SFT_ROR),
+];
+
+/// Get the shift type code and the shift value of an ARM64 shift.
+pub fn shift(shift: Arm64Shift) -> (ShiftType, u32) {
+    match shift {
+        Arm64Shift::Invalid => (SFT_INVALID, 0),
+        Arm64Shift::Lsl(v) => (SFT_LSL, v),
+        Arm64Shift::Msl(v) => (SFT_MSL, v),
+        Arm64Shift::Lsr(v) => (SFT_LSR, v),
+        Arm64Shift::Asr(v) => (SFT_ASR, v),
+        Arm64Shift::Ror(v) => (SFT_ROR, v),
+    }
+}
+
+/// Get the Java `operandType` code and the value of an ARM64 operand.
+pub fn operand_value(op: &Arm64OperandType) -> (i8, OperandValue<&Arm64OpMem>) {
+    match op {
+        Arm64OperandType::Invalid => (OP_INVALID, OperandValue::None),
+        Arm64OperandType::Reg(v) => (OP_REG, OperandValue::Reg(*v)),
+        Arm64OperandType::Imm(v) => (OP_IMM, OperandValue::Long(*v)),
+        Arm64OperandType::Mem(v) => (OP_MEM, OperandValue::Mem(v)),
+        Arm64OperandType::Fp(v) => (OP_FP, OperandValue::Double(*v)),
+        Arm64OperandType::Cimm(v) => (OP_CIMM, OperandValue::Long(*v)),
+        Arm64OperandType::RegMrs(v) => (OP_REGMRS, OperandValue::Int(*v as i32)),
+        Arm64OperandType::RegMsr(v) => (OP_REGMSR, OperandValue::Int(*v as i32)),
+        Arm64OperandType::Pstate(v) => (OP_PSTATE, OperandValue::Byte(*v as i8)),
+        Arm64OperandType::Sys(v) => (OP_SYS, OperandValue::Long(*v as i64)),
+        Arm64OperandType::Prefetch(v) => (OP_PREFETCH, OperandValue::Byte(*v as i8)),
+        Arm64OperandType::Barrier(v) => (OP_BARRIER, OperandValue::Byte(*v as i8)),
+    }
+}
+
+/// Get the addressing of the memory operand at `index`, given the writeback flag of the
+/// instruction. Capstone reports the offset of a post-indexed access as an extra
+/// operand after the memory operand, and a pre-indexed access only by the writeback flag.
+/// An operand after the memory operand is only an offset if the base is written back.
+pub fn addressing(operands: &[Arm64Operand], index: usize, writeback: bool) -> Addressing {
+    let op = &operands[index];
+    let (shift_type, shift_value) = shift(op.shift);
+    let mut addressing = Addressing {
+        mode: MEM_OFFSET,
+        post_index_imm: 0,
+        post_index_reg: RegId(0),
+        shift_type,
+        shift_value,
+        extend: op.ext as i8,
+        subtracted: false,
+    };
+
+    if !writeback {
+        return addressing;
+    }
+
+    match operands.get(index + 1).map(|op| &op.op_type) {
+        Some(Arm64OperandType::Imm(imm)) => {
+            addressing.mode = MEM_POST_INDEXED;
+            addressing.post_index_imm = *imm;
+        }
+        Some(Arm64OperandType::Reg(reg)) => {
+            addressing.mode = MEM_POST_INDEXED;
+            addressing.post_index_reg = *reg;
+        }
+        _ => addressing.mode = MEM_PRE_INDEXED,
+    }
+
+    addressing
+}
+
+#[cfg(test)]
+mod tests {
+    use capstone::arch::DetailsArchInsn;
+    use capstone::RegId;
+
+    use super::*;
+    use crate::analysis::testing::disassemble;
+    use crate::capstone::mode::CapstoneMode;
+
+    #[test]
+    fn shift_codes() {
+        assert_eq!(shift(Arm64Shift::Invalid), (0, 0));
+        assert_eq!(shift(Arm64Shift::Lsl(12)), (1, 12));
+        assert_eq!(shift(Arm64Shift::Msl(8)), (2, 8));
+        assert_eq!(shift(Arm64Shift::Ror(7)), (5, 7));
+    }
+
+    #[test]
+    fn operand_codes() {
+        assert_eq!(
+            operand_value(&Arm64OperandType::Invalid),
+            (0, OperandValue::None)
+        );
+        assert_eq!(
+            operand_value(&Arm64OperandType::Reg(RegId(1))),
+            (1, OperandValue::Reg(RegId(1)))
+        );
+        assert_eq!(
+            operand_value(&Arm64OperandType::Imm(1 << 40)),
+            (2, OperandValue::Long(1 << 40))
+        );
+        assert_eq!(
+            operand_value(&Arm64OperandType::Cimm(3)),
+            (5, OperandValue::Long(3))
+        );
+    }
+
+    #[test]
+    fn addressing_modes() {
+        let code = [
+            0xfd, 0x7b, 0xbf, 0xa9, // stp x29, x30, [sp, #-0x10]!
+            0xfd, 0x7b, 0xc1, 0xa8, // ldp x29, x30, [sp], #0x10
+            0x20, 0xd8, 0x62, 0xf8, // ldr x0, [x1, w2, sxtw #3]
+            0x00, 0x70, 0xc2, 0x4c, // ld1 {v0.16b}, [x0], x2
+        ];
+        let addressing = disassemble(CapstoneMode::ARM64, &code, 0x1000, |cs, _, insns, _| {
+            insns
+                .iter()
+                .map(|insn| {
+                    let detail = cs.insn_detail(insn).unwrap();
+                    let arch = detail.arch_detail();
+                    let arch = arch.arm64().unwrap();
+                    let operands: Vec<_> = arch.operands().collect();
+                    let index = operands
+                        .iter()
+                        .position(|op| matches!(op.op_type, Arm64OperandType::Mem(_)))
+                        .unwrap();
+                    addressing(&operands, index, arch.writeback())
+                })
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(addressing[0].mode, MEM_PRE_INDEXED);
+        assert!(addressing[0].base_updated());
+        assert_eq!(
+            (addressing[1].mode, addressing[1].post_index_imm),
+            (MEM_POST_INDEXED, 16)
+        );
+        assert_eq!(addressing[2].mode, MEM_OFFSET);
+        assert!(!addressing[2].base_updated());
+        assert_eq!(
+            (addressing[2].shift_type, addressing[2].shift_value),
+            (SFT_LSL, 3)
+        );
+        assert_ne!(addressing[2].extend, 0);
+        assert_eq!(addressing[3].mode, MEM_POST_INDEXED);
+        assert_ne!(addressing[3].post_index_reg, RegId(0));
+    }
+
+    #[test]
+    fn trailing_operands_without_writeback() {
+        let operands = [
+            Arm64Operand::default(),
+            Arm64Operand {
+                op_type: Arm64OperandType::Imm(8),
+                ..Default::default()
+            },
+        ];
+        let offset = addressing(&operands, 0, false);
+        assert_eq!((offset.mode, offset.post_index_imm), (MEM_OFFSET, 0));
+        assert!(!offset.base_updated());
+
+        let post_indexed = addressing(&operands, 0, true);
+        assert_eq!(
+            (post_indexed.mode, post_indexed.post_index_imm),
+            (MEM_POST_INDEXED, 8)
+        );
+    }
+}
@@ -0,0 +1,67 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use capstone::RegId;
+
+/// Shift type code, as stored in the Java `shiftType` field.
+pub type ShiftType = i8;
+
+/// The value of an operand, in the representation of the Java `rawOperandValue` field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OperandValue<M> {
+    /// No value, for invalid operands.
+    None,
+    /// A register, boxed as an `Integer` register id.
+    Reg(RegId),
+    Int(i32),
+    Long(i64),
+    Byte(i8),
+    Double(f64),
+    /// A memory operand, converted by the writer of the architecture.
+    Mem(M),
+}
+
+/// Java `addressingMode` codes of memory operands.
+pub const MEM_OFFSET: i8 = 0;
+pub const MEM_PRE_INDEXED: i8 = 1;
+pub const MEM_POST_INDEXED: i8 = 2;
+
+/// Addressing mode codes with the names of their Java constants.
+pub const ADDRESSING_MODES: &[(&str, i8)] = &[
+    ("ARM_MEM_OFFSET", MEM_OFFSET),
+    ("ARM_MEM_PRE_INDEXED", MEM_PRE_INDEXED),
+    ("ARM_MEM_POST_INDEXED", MEM_POST_INDEXED),
+];
+
+/// How a memory operand computes its address and updates its base register. Capstone
+/// reports these details across the operand list and the writeback flag of the
+/// instruction; this gathers them for the memory operand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Addressing {
+    /// The addressing mode code.
+    pub mode: i8,
+    /// The immediate added to the base register after a post-indexed access.
+    pub post_index_imm: i64,
+    /// The register added to the base register after a post-indexed access, or 0.
+    pub post_index_reg: RegId,
+    /// The shift of the index register, or of the post-index register.
+    pub shift_type: ShiftType,
+    pub shift_value: u32,
+    /// The extender of the index register, as the ARM64 `ext` code. Always 0 on ARM32.
+    pub extend: i8,
+    /// Whether the index or post-index register is subtracted. Always false on ARM64.
+    pub subtracted: bool,
+}
+
+impl Addressing {
+    /// Whether the access writes the updated address back to the base register.
+    pub fn base_updated(&self) -> bool {
+        self.mode != MEM_OFFSET
+    }
+}
+
+pub mod arm32;
+pub mod arm64;
+pub mod sysreg;
@@ -0,0 +1,468 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use capstone::arch::arm::{ArmInsn, ArmOperandType};
+use capstone::arch::arm64::{Arm64Insn, Arm64OperandType};
+use capstone::{InsnId, InsnIdInt};
+
+/// A decoded system register. Fields that do not apply to the kind of access are -1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SystemRegister {
+    /// The coprocessor of an AArch32 `mrc`, `mcr`, `mrrc` or `mcrr`.
+    pub coprocessor: i32,
+    pub op0: i32,
+    pub op1: i32,
+    pub crn: i32,
+    pub crm: i32,
+    pub op2: i32,
+    /// The architectural name, e.g. `TTBR0_EL1`, if the register is known.
+    pub name: Option<&'static str>,
+}
+
+impl SystemRegister {
+    /// Decode an AArch64 `mrs`/`msr` register, encoded as `op0:op1:CRn:CRm:op2`.
+    pub fn arm64(encoding: u32) -> SystemRegister {
+        let op0 = (encoding >> 14 & 0x3) as i32;
+        let op1 = (encoding >> 11 & 0x7) as i32;
+        let crn = (encoding >> 7 & 0xf) as i32;
+        let crm = (encoding >> 3 & 0xf) as i32;
+        let op2 = (encoding & 0x7) as i32;
+        let name = ARM64_REGISTERS
+            .iter()
+            .find(|(_, reg)| *reg == (op0, op1, crn, crm, op2))
+            .map(|(name, _)| *name);
+        SystemRegister {
+            coprocessor: -1,
+            op0,
+            op1,
+            crn,
+            crm,
+            op2,
+            name,
+        }
+    }
+
+    /// Decode an AArch32 32-bit coprocessor register.
+    pub fn arm32(coprocessor: i32, opc1: i32, crn: i32, crm: i32, opc2: i32) -> SystemRegister {
+        let name = ARM32_REGISTERS
+            .iter()
+            .find(|(_, reg)| *reg == (coprocessor, opc1, crn, crm, opc2))
+            .map(|(name, _)| *name);
+        SystemRegister {
+            coprocessor,
+            op0: -1,
+            op1: opc1,
+            crn,
+            crm,
+            op2: opc2,
+            name,
+        }
+    }
+
+    /// Decode an AArch32 64-bit coprocessor register, accessed with `mrrc` and `mcrr`.
+    pub fn arm32_wide(coprocessor: i32, opc1: i32, crm: i32) -> SystemRegister {
+        let name = ARM32_WIDE_REGISTERS
+            .iter()
+            .find(|(_, reg)| *reg == (coprocessor, opc1, crm))
+            .map(|(name, _)| *name);
+        SystemRegister {
+            coprocessor,
+            op0: -1,
+            op1: opc1,
+            crn: -1,
+            crm,
+            op2: -1,
+            name,
+        }
+    }
+
+    /// Name an AArch32 special register of `mrs` and `msr`. The `cpsr`/`spsr` field
+    /// masks are not named, since Capstone does not tell the two apart.
+    pub fn arm32_special(value: u32) -> Option<SystemRegister> {
+        let name = ARM32_SPECIAL_REGISTERS.get(value.checked_sub(ARM32_SPECIAL_FIRST)? as usize)?;
+        Some(SystemRegister {
+            coprocessor: -1,
+            op0: -1,
+            op1: -1,
+            crn: -1,
+            crm: -1,
+            op2: -1,
+            name: Some(name),
+        })
+    }
+}
+
+/// Decode the system register of an ARM64 operand, if the instruction is `mrs` or `msr`.
+/// Capstone reports known registers as system operands and implementation-defined ones
+/// as MRS/MSR registers.
+pub fn decode_arm64(id: InsnId, op: &Arm64OperandType) -> Option<SystemRegister> {
+    if id.0 != Arm64Insn::ARM64_INS_MRS as InsnIdInt
+        && id.0 != Arm64Insn::ARM64_INS_MSR as InsnIdInt
+    {
+        return None;
+    }
+    match op {
+        Arm64OperandType::RegMrs(reg) | Arm64OperandType::RegMsr(reg) => {
+            Some(SystemRegister::arm64(*reg as u32))
+        }
+        Arm64OperandType::Sys(reg) => Some(SystemRegister::arm64(*reg)),
+        _ => None,
+    }
+}
+
+/// Decode the system register accessed by an ARM instruction, attached to the operand
+/// at `index`: the coprocessor operand of a coprocessor access, or the special register
+/// of `mrs` and `msr`.
+pub fn decode_arm32(
+    id: InsnId,
+    operands: &[ArmOperandType],
+    index: usize,
+) -> Option<SystemRegister> {
+    use ArmOperandType::*;
+
+    let is = |insn: ArmInsn| id.0 == insn as InsnIdInt;
+    match (operands, index) {
+        ([Pimm(cp), Imm(opc1), Reg(_), Cimm(crn), Cimm(crm), Imm(opc2)], 0)
+            if is(ArmInsn::ARM_INS_MRC) || is(ArmInsn::ARM_INS_MCR) =>
+        {
+            Some(SystemRegister::arm32(*cp, *opc1, *crn, *crm, *opc2))
+        }
+        ([Pimm(cp), Imm(opc1), Reg(_), Reg(_), Cimm(crm)], 0)
+            if is(ArmInsn::ARM_INS_MRRC) || is(ArmInsn::ARM_INS_MCRR) =>
+        {
+            Some(SystemRegister::arm32_wide(*cp, *opc1, *crm))
+        }
+        _ => match operands.get(index)? {
+            SysReg(reg) if is(ArmInsn::ARM_INS_MRS) || is(ArmInsn::ARM_INS_MSR) => {
+                SystemRegister::arm32_special(reg.0 as u32)
+            }
+            _ => None,
+        },
+    }
+}
+
+/// The encoding of a 32-bit system register: `(op0, op1, CRn, CRm, op2)` in AArch64 and
+/// `(coproc, opc1, CRn, CRm, opc2)` in AArch32.
+type Encoding = (i32, i32, i32, i32, i32);
+
+/// Known AArch64 system registers by `(op0, op1, CRn, CRm, op2)`.
+#[rustfmt::skip]
+const ARM64_REGISTERS: &[(&str, Encoding)] = &[
+    // Identification
+    ("MIDR_EL1", (3, 0, 0, 0, 0)),
+    ("MPIDR_EL1", (3, 0, 0, 0, 5)),
+    ("REVIDR_EL1", (3, 0, 0, 0, 6)),
+    ("ID_AA64PFR0_EL1", (3, 0, 0, 4, 0)),
+    ("ID_AA64PFR1_EL1", (3, 0, 0, 4, 1)),
+    ("ID_AA64DFR0_EL1", (3, 0, 0, 5, 0)),
+    ("ID_AA64ISAR0_EL1", (3, 0, 0, 6, 0)),
+    ("ID_AA64ISAR1_EL1", (3, 0, 0, 6, 1)),
+    ("ID_AA64MMFR0_EL1", (3, 0, 0, 7, 0)),
+    ("ID_AA64MMFR1_EL1", (3, 0, 0, 7, 1)),
+    ("ID_AA64MMFR2_EL1", (3, 0, 0, 7, 2)),
+    ("CCSIDR_EL1", (3, 1, 0, 0, 0)),
+    ("CLIDR_EL1", (3, 1, 0, 0, 1)),
+    ("CSSELR_EL1", (3, 2, 0, 0, 0)),
+    ("CTR_EL0", (3, 3, 0, 0, 1)),
+    ("DCZID_EL0", (3, 3, 0, 0, 7)),
+    ("VPIDR_EL2", (3, 4, 0, 0, 0)),
+    ("VMPIDR_EL2", (3, 4, 0, 0, 5)),
+    // System control
+    ("SCTLR_EL1", (3, 0, 1, 0, 0)),
+    ("ACTLR_EL1", (3, 0, 1, 0, 1)),
+    ("CPACR_EL1", (3, 0, 1, 0, 2)),
+    ("SCTLR_EL2", (3, 4, 1, 0, 0)),
+    ("ACTLR_EL2", (3, 4, 1, 0, 1)),
+    ("HCR_EL2", (3, 4, 1, 1, 0)),
+    ("MDCR_EL2", (3, 4, 1, 1, 1)),
+    ("CPTR_EL2", (3, 4, 1, 1, 2)),
+    ("HSTR_EL2", (3, 4, 1, 1, 3)),
+    ("HACR_EL2", (3, 4, 1, 1, 7)),
+    ("SCTLR_EL3", (3, 6, 1, 0, 0)),
+    ("ACTLR_EL3", (3, 6, 1, 0, 1)),
+    ("SCR_EL3", (3, 6, 1, 1, 0)),
+    ("CPTR_EL3", (3, 6, 1, 1, 2)),
+    ("MDCR_EL3", (3, 6, 1, 3, 1)),
+    // Memory management
+    ("TTBR0_EL1", (3, 0, 2, 0, 0)),
+    ("TTBR1_EL1", (3, 0, 2, 0, 1)),
+    ("TCR_EL1", (3, 0, 2, 0, 2)),
+    ("TTBR0_EL2", (3, 4, 2, 0, 0)),
+    ("TCR_EL2", (3, 4, 2, 0, 2)),
+    ("VTTBR_EL2", (3, 4, 2, 1, 0)),
+    ("VTCR_EL2", (3, 4, 2, 1, 2)),
+    ("TTBR0_EL3", (3, 6, 2, 0, 0)),
+    ("TCR_EL3", (3, 6, 2, 0, 2)),
+    ("MAIR_EL1", (3, 0, 10, 2, 0)),
+    ("AMAIR_EL1", (3, 0, 10, 3, 0)),
+    ("MAIR_EL2", (3, 4, 10, 2, 0)),
+    ("MAIR_EL3", (3, 6, 10, 2, 0)),
+    ("PAR_EL1", (3, 0, 7, 4, 0)),
+    ("CONTEXTIDR_EL1", (3, 0, 13, 0, 1)),
+    // Exceptions
+    ("SPSR_EL1", (3, 0, 4, 0, 0)),
+    ("ELR_EL1", (3, 0, 4, 0, 1)),
+    ("SP_EL0", (3, 0, 4, 1, 0)),
+    ("SPSEL", (3, 0, 4, 2, 0)),
+    ("CURRENTEL", (3, 0, 4, 2, 2)),
+    ("PAN", (3, 0, 4, 2, 3)),
+    ("UAO", (3, 0, 4, 2, 4)),
+    ("SPSR_EL2", (3, 4, 4, 0, 0)),
+    ("ELR_EL2", (3, 4, 4, 0, 1)),
+    ("SP_EL1", (3, 4, 4, 1, 0)),
+    ("SPSR_EL3", (3, 6, 4, 0, 0)),
+    ("ELR_EL3", (3, 6, 4, 0, 1)),
+    ("SP_EL2", (3, 6, 4, 1, 0)),
+    ("AFSR0_EL1", (3, 0, 5, 1, 0)),
+    ("AFSR1_EL1", (3, 0, 5, 1, 1)),
+    ("ESR_EL1", (3, 0, 5, 2, 0)),
+    ("ESR_EL2", (3, 4, 5, 2, 0)),
+    ("ESR_EL3", (3, 6, 5, 2, 0)),
+    ("FAR_EL1", (3, 0, 6, 0, 0)),
+    ("FAR_EL2", (3, 4, 6, 0, 0)),
+    ("HPFAR_EL2", (3, 4, 6, 0, 4)),
+    ("FAR_EL3", (3, 6, 6, 0, 0)),
+    ("VBAR_EL1", (3, 0, 12, 0, 0)),
+    ("RVBAR_EL1", (3, 0, 12, 0, 1)),
+    ("ISR_EL1", (3, 0, 12, 1, 0)),
+    ("VBAR_EL2", (3, 4, 12, 0, 0)),
+    ("VBAR_EL3", (3, 6, 12, 0, 0)),
+    ("RVBAR_EL3", (3, 6, 12, 0, 1)),
+    // Processor state
+    ("NZCV", (3, 3, 4, 2, 0)),
+    ("DAIF", (3, 3, 4, 2, 1)),
+    ("DIT", (3, 3, 4, 2, 5)),
+    ("SSBS", (3, 3, 4, 2, 6)),
+    ("FPCR", (3, 3, 4, 4, 0)),
+    ("FPSR", (3, 3, 4, 4, 1)),
+    // Thread IDs
+    ("TPIDR_EL0", (3, 3, 13, 0, 2)),
+    ("TPIDRRO_EL0", (3, 3, 13, 0, 3)),
+    ("TPIDR_EL1", (3, 0, 13, 0, 4)),
+    ("TPIDR_EL2", (3, 4, 13, 0, 2)),
+    ("TPIDR_EL3", (3, 6, 13, 0, 2)),
+    // Generic timer
+    ("CNTKCTL_EL1", (3, 0, 14, 1, 0)),
+    ("CNTFRQ_EL0", (3, 3, 14, 0, 0)),
+    ("CNTPCT_EL0", (3, 3, 14, 0, 1)),
+    ("CNTVCT_EL0", (3, 3, 14, 0, 2)),
+    ("CNTP_TVAL_EL0", (3, 3, 14, 2, 0)),
+    ("CNTP_CTL_EL0", (3, 3, 14, 2, 1)),
+    ("CNTP_CVAL_EL0", (3, 3, 14, 2, 2)),
+    ("CNTV_TVAL_EL0", (3, 3, 14, 3, 0)),
+    ("CNTV_CTL_EL0", (3, 3, 14, 3, 1)),
+    ("CNTV_CVAL_EL0", (3, 3, 14, 3, 2)),
+    ("CNTVOFF_EL2", (3, 4, 14, 0, 3)),
+    ("CNTHCTL_EL2", (3, 4, 14, 1, 0)),
+    // Interrupt controller
+    ("ICC_PMR_EL1", (3, 0, 4, 6, 0)),
+    ("ICC_SGI1R_EL1", (3, 0, 12, 11, 5)),
+    ("ICC_IAR1_EL1", (3, 0, 12, 12, 0)),
+    ("ICC_EOIR1_EL1", (3, 0, 12, 12, 1)),
+    ("ICC_BPR1_EL1", (3, 0, 12, 12, 3)),
+    ("ICC_CTLR_EL1", (3, 0, 12, 12, 4)),
+    ("ICC_SRE_EL1", (3, 0, 12, 12, 5)),
+    ("ICC_IGRPEN1_EL1", (3, 0, 12, 12, 7)),
+    // Debug and performance monitors
+    ("MDSCR_EL1", (2, 0, 0, 2, 2)),
+    ("OSLAR_EL1", (2, 0, 1, 0, 4)),
+    ("OSDLR_EL1", (2, 0, 1, 3, 4)),
+    ("MDCCSR_EL0", (2, 3, 0, 1, 0)),
+    ("PMCR_EL0", (3, 3, 9, 12, 0)),
+    ("PMCCNTR_EL0", (3, 3, 9, 13, 0)),
+    ("PMUSERENR_EL0", (3, 3, 9, 14, 0)),
+];
+
+/// Known AArch32 coprocessor registers by `(coproc, opc1, CRn, CRm, opc2)`.
+#[rustfmt::skip]
+const ARM32_REGISTERS: &[(&str, Encoding)] = &[
+    // Identification
+    ("MIDR", (15, 0, 0, 0, 0)),
+    ("CTR", (15, 0, 0, 0, 1)),
+    ("TCMTR", (15, 0, 0, 0, 2)),
+    ("TLBTR", (15, 0, 0, 0, 3)),
+    ("MPIDR", (15, 0, 0, 0, 5)),
+    ("REVIDR", (15, 0, 0, 0, 6)),
+    ("ID_PFR0", (15, 0, 0, 1, 0)),
+    ("ID_PFR1", (15, 0, 0, 1, 1)),
+    ("ID_DFR0", (15, 0, 0, 1, 2)),
+    ("ID_MMFR0", (15, 0, 0, 1, 4)),
+    ("ID_ISAR0", (15, 0, 0, 2, 0)),
+    ("CCSIDR", (15, 1, 0, 0, 0)),
+    ("CLIDR", (15, 1, 0, 0, 1)),
+    ("CSSELR", (15, 2, 0, 0, 0)),
+    ("VPIDR", (15, 4, 0, 0, 0)),
+    ("VMPIDR", (15, 4, 0, 0, 5)),
+    // System control
+    ("SCTLR", (15, 0, 1, 0, 0)),
+    ("ACTLR", (15, 0, 1, 0, 1)),
+    ("CPACR", (15, 0, 1, 0, 2)),
+    ("SCR", (15, 0, 1, 1, 0)),
+    ("SDER", (15, 0, 1, 1, 1)),
+    ("NSACR", (15, 0, 1, 1, 2)),
+    ("HSCTLR", (15, 4, 1, 0, 0)),
+    ("HCR", (15, 4, 1, 1, 0)),
+    ("HDCR", (15, 4, 1, 1, 1)),
+    ("HCPTR", (15, 4, 1, 1, 2)),
+    ("HSTR", (15, 4, 1, 1, 3)),
+    // Memory management
+    ("TTBR0", (15, 0, 2, 0, 0)),
+    ("TTBR1", (15, 0, 2, 0, 1)),
+    ("TTBCR", (15, 0, 2, 0, 2)),
+    ("HTCR", (15, 4, 2, 0, 2)),
+    ("VTCR", (15, 4, 2, 1, 2)),
+    ("DACR", (15, 0, 3, 0, 0)),
+    ("PAR", (15, 0, 7, 4, 0)),
+    ("PRRR", (15, 0, 10, 2, 0)),
+    ("NMRR", (15, 0, 10, 2, 1)),
+    ("FCSEIDR", (15, 0, 13, 0, 0)),
+    ("CONTEXTIDR", (15, 0, 13, 0, 1)),
+    // Faults and exceptions
+    ("DFSR", (15, 0, 5, 0, 0)),
+    ("IFSR", (15, 0, 5, 0, 1)),
+    ("ADFSR", (15, 0, 5, 1, 0)),
+    ("AIFSR", (15, 0, 5, 1, 1)),
+    ("HSR", (15, 4, 5, 2, 0)),
+    ("DFAR", (15, 0, 6, 0, 0)),
+    ("IFAR", (15, 0, 6, 0, 2)),
+    ("HDFAR", (15, 4, 6, 0, 0)),
+    ("HIFAR", (15, 4, 6, 0, 2)),
+    ("HPFAR", (15, 4, 6, 0, 4)),
+    ("VBAR", (15, 0, 12, 0, 0)),
+    ("MVBAR", (15, 0, 12, 0, 1)),
+    ("ISR", (15, 0, 12, 1, 0)),
+    ("HVBAR", (15, 4, 12, 0, 0)),
+    // Cache, branch predictor and barrier operations
+    ("ICIALLUIS", (15, 0, 7, 1, 0)),
+    ("BPIALLIS", (15, 0, 7, 1, 6)),
+    ("ICIALLU", (15, 0, 7, 5, 0)),
+    ("ICIMVAU", (15, 0, 7, 5, 1)),
+    ("CP15ISB", (15, 0, 7, 5, 4)),
+    ("BPIALL", (15, 0, 7, 5, 6)),
+    ("DCIMVAC", (15, 0, 7, 6, 1)),
+    ("DCISW", (15, 0, 7, 6, 2)),
+    ("DCCMVAC", (15, 0, 7, 10, 1)),
+    ("DCCSW", (15, 0, 7, 10, 2)),
+    ("CP15DSB", (15, 0, 7, 10, 4)),
+    ("CP15DMB", (15, 0, 7, 10, 5)),
+    ("DCCMVAU", (15, 0, 7, 11, 1)),
+    ("DCCIMVAC", (15, 0, 7, 14, 1)),
+    ("DCCISW", (15, 0, 7, 14, 2)),
+    // TLB maintenance
+    ("TLBIALLIS", (15, 0, 8, 3, 0)),
+    ("TLBIMVAIS", (15, 0, 8, 3, 1)),
+    ("TLBIASIDIS", (15, 0, 8, 3, 2)),
+    ("TLBIALL", (15, 0, 8, 7, 0)),
+    ("TLBIMVA", (15, 0, 8, 7, 1)),
+    ("TLBIASID", (15, 0, 8, 7, 2)),
+    // Thread IDs
+    ("TPIDRURW", (15, 0, 13, 0, 2)),
+    ("TPIDRURO", (15, 0, 13, 0, 3)),
+    ("TPIDRPRW", (15, 0, 13, 0, 4)),
+    ("HTPIDR", (15, 4, 13, 0, 2)),
+    // Generic timer and performance monitors
+    ("CNTFRQ", (15, 0, 14, 0, 0)),
+    ("CNTKCTL", (15, 0, 14, 1, 0)),
+    ("CNTP_TVAL", (15, 0, 14, 2, 0)),
+    ("CNTP_CTL", (15, 0, 14, 2, 1)),
+    ("CNTV_TVAL", (15, 0, 14, 3, 0)),
+    ("CNTV_CTL", (15, 0, 14, 3, 1)),
+    ("CNTHCTL", (15, 4, 14, 1, 0)),
+    ("PMCR", (15, 0, 9, 12, 0)),
+    ("PMCCNTR", (15, 0, 9, 13, 0)),
+    ("PMUSERENR", (15, 0, 9, 14, 0)),
+    // Debug
+    ("DBGDIDR", (14, 0, 0, 0, 0)),
+    ("DBGDSCRINT", (14, 0, 0, 1, 0)),
+    ("DBGDSCREXT", (14, 0, 0, 2, 2)),
+    ("DBGOSLAR", (14, 0, 1, 0, 4)),
+];
+
+/// Known AArch32 64-bit coprocessor registers by `(coproc, opc1, CRm)`.
+#[rustfmt::skip]
+const ARM32_WIDE_REGISTERS: &[(&str, (i32, i32, i32))] = &[
+    ("TTBR0", (15, 0, 2)),
+    ("TTBR1", (15, 1, 2)),
+    ("HTTBR", (15, 4, 2)),
+    ("VTTBR", (15, 6, 2)),
+    ("PAR", (15, 0, 7)),
+    ("CNTPCT", (15, 0, 14)),
+    ("CNTVCT", (15, 1, 14)),
+    ("CNTP_CVAL", (15, 2, 14)),
+    ("CNTV_CVAL", (15, 3, 14)),
+    ("CNTVOFF", (15, 4, 14)),
+    ("CNTHP_CVAL", (15, 6, 14)),
+];
+
+/// Capstone's id of the first special register in `ARM32_SPECIAL_REGISTERS`.
+const ARM32_SPECIAL_FIRST: u32 = 256;
+
+/// AArch32 and M-profile special registers of `mrs` and `msr`, in Capstone's order.
+#[rustfmt::skip]
+const ARM32_SPECIAL_REGISTERS: &[&str] = &[
+    "APSR", "APSR_G", "APSR_NZCVQ", "APSR_NZCVQG",
+    "IAPSR", "IAPSR_G", "IAPSR_NZCVQG", "IAPSR_NZCVQ",
+    "EAPSR", "EAPSR_G", "EAPSR_NZCVQG", "EAPSR_NZCVQ",
+    "XPSR", "XPSR_G", "XPSR_NZCVQG", "XPSR_NZCVQ",
+    "IPSR", "EPSR", "IEPSR", "MSP", "PSP", "PRIMASK", "BASEPRI", "BASEPRI_MAX",
+    "FAULTMASK", "CONTROL", "MSPLIM", "PSPLIM", "MSP_NS", "PSP_NS", "MSPLIM_NS",
+    "PSPLIM_NS", "PRIMASK_NS", "BASEPRI_NS", "FAULTMASK_NS", "CONTROL_NS", "SP_NS",
+    "R8_USR", "R9_USR", "R10_USR", "R11_USR", "R12_USR", "SP_USR", "LR_USR",
+    "R8_FIQ", "R9_FIQ", "R10_FIQ", "R11_FIQ", "R12_FIQ", "SP_FIQ", "LR_FIQ",
+    "LR_IRQ", "SP_IRQ", "LR_SVC", "SP_SVC", "LR_ABT", "SP_ABT", "LR_UND", "SP_UND",
+    "LR_MON", "SP_MON", "ELR_HYP", "SP_HYP",
+    "SPSR_FIQ", "SPSR_IRQ", "SPSR_SVC", "SPSR_ABT", "SPSR_UND", "SPSR_MON", "SPSR_HYP",
+];
+
+#[cfg(test)]
+mod tests {
+    use capstone::RegId;
+
+    use super::*;
+
+    #[test]
+    fn decodes_arm64_registers() {
+        let mrs = InsnId(Arm64Insn::ARM64_INS_MRS as InsnIdInt);
+        let reg = decode_arm64(mrs, &Arm64OperandType::Sys(0xc100)).unwrap();
+        assert_eq!(
+            (reg.op0, reg.op1, reg.crn, reg.crm, reg.op2),
+            (3, 0, 2, 0, 0)
+        );
+        assert_eq!(reg.name, Some("TTBR0_EL1"));
+        assert_eq!(SystemRegister::arm64(0xc780).name, None);
+
+        let dc = InsnId(Arm64Insn::ARM64_INS_DC as InsnIdInt);
+        assert_eq!(decode_arm64(dc, &Arm64OperandType::Sys(0xc100)), None);
+    }
+
+    #[test]
+    fn decodes_arm32_registers() {
+        use ArmOperandType::*;
+
+        // 'mrc p15, #0, r0, c1, c0, #2'
+        let mrc = InsnId(ArmInsn::ARM_INS_MRC as InsnIdInt);
+        let operands = [Pimm(15), Imm(0), Reg(RegId(66)), Cimm(1), Cimm(0), Imm(2)];
+        let reg = decode_arm32(mrc, &operands, 0).unwrap();
+        assert_eq!(reg.name, Some("CPACR"));
+        assert_eq!((reg.coprocessor, reg.op0, reg.op2), (15, -1, 2));
+        assert_eq!(decode_arm32(mrc, &operands, 3), None);
+
+        // 'mcrr p15, #1, r0, r1, c2'
+        let mcrr = InsnId(ArmInsn::ARM_INS_MCRR as InsnIdInt);
+        let operands = [Pimm(15), Imm(1), Reg(RegId(66)), Reg(RegId(67)), Cimm(2)];
+        assert_eq!(
+            decode_arm32(mcrr, &operands, 0).unwrap().name,
+            Some("TTBR1")
+        );
+
+        // 'msr apsr_nzcvq, r0'
+        let msr = InsnId(ArmInsn::ARM_INS_MSR as InsnIdInt);
+        let operands = [SysReg(RegId(258)), Reg(RegId(66))];
+        let reg = decode_arm32(msr, &operands, 0).unwrap();
+        assert_eq!(reg.name, Some("APSR_NZCVQ"));
+        let operands = [SysReg(RegId(9)), Reg(RegId(66))];
+        assert_eq!(decode_arm32(msr, &operands, 0), None);
+    }
+}
@@ -6,43 +6,63 @@
 use std::error::Error;
 use std::sync::MutexGuard;
 
-use capstone::arch::arm::{ArmInsnDetail, ArmOpMem, ArmOperandType, ArmShift};
+use capstone::arch::arm::{ArmInsnDetail, ArmOpMem, ArmOperandType};
 use capstone::arch::DetailsArchInsn;
-use capstone::{Capstone, Insn};
+use capstone::{Capstone, Insn, InsnId};
 use jni::objects::{JObject, JValue};
 use jni::sys::jint;
 use jni::JNIEnv;
 
-use crate::obj::boxing::{boxed_byte, boxed_double, boxed_int};
+use crate::analysis::jumptable::JumpTable;
+use crate::analysis::pcrel::PcRelReference;
+use crate::analysis::{flags, flow};
 use crate::obj::obj::Obj;
+use crate::operand::{arm32, sysreg, Addressing};
 use crate::util::{AutoObject, JObjectResult, JResult};
-use crate::writer::arm::{ArmInstructionWriter, ShiftType};
+use crate::writer::arm::ArmInstructionWriter;
+use crate::writer::layout::{ClassLayout, Member};
 use crate::writer::InstructionWriter;
 
+/// Fields written to ARM instruction objects.
+const INSN_FIELDS: &[Member] = &[
+    Member::Field("isUsermode", "Z"),
+    Member::Field("vectorSize", "I"),
+    Member::Field("vectorData", "B"),
+    Member::Field("cpsMode", "B"),
+    Member::Field("cpsFlag", "B"),
+    Member::Field("conditionCodes", "B"),
+    Member::Field("updatesFlags", "Z"),
+    Member::Field("writebackRequired", "Z"),
+    Member::Field("memBarrier", "B"),
+    Member::Field(
+        "operands",
+        "[Lorg/native4j/capstone/insn/arm/CsOperandArm32;",
+    ),
+];
+
+/// Fields written to ARM operand objects.
+const OPERAND_FIELDS: &[Member] = &[
+    Member::Field("vectorIndex", "I"),
+    Member::Field("isSubtracted", "Z"),
+    Member::Field("shiftType", "B"),
+    Member::Field("shiftValue", "J"),
+];
+
+/// Fields written to ARM memory operand objects.
+const MEMORY_FIELDS: &[Member] = &[Member::Field("isSubtracted", "Z")];
+
 /// Writer for ARM instructions.
-pub struct Arm32Writer;
-
-impl ArmInstructionWriter<ArmShift, ArmOpMem, ArmOperandType, ArmInsnDetail<'_>> for Arm32Writer {
-    fn get_shift(&self, shift: ArmShift) -> (ShiftType, u32) {
-        match shift {
-            ArmShift::Invalid => (0, 0),
-            ArmShift::Asr(v) => (1, v),
-            ArmShift::Lsl(v) => (2, v),
-            ArmShift::Lsr(v) => (3, v),
-            ArmShift::Ror(v) => (4, v),
-            ArmShift::Rrx(v) => (5, v),
-            ArmShift::AsrReg(v) => (6, v.0 as u32),
-            ArmShift::LslReg(v) => (7, v.0 as u32),
-            ArmShift::LsrReg(v) => (8, v.0 as u32),
-            ArmShift::RorReg(v) => (9, v.0 as u32),
-            ArmShift::RrxReg(v) => (10, v.0 as u32),
-        }
-    }
+pub struct Arm32Writer {
+    /// Whether instructions are decoded in Thumb mode.
+    pub thumb: bool,
+}
 
+impl ArmInstructionWriter<ArmOpMem, ArmOperandType, ArmInsnDetail<'_>> for Arm32Writer {
     fn create_memory_operand<'jni>(
         &self,
         env: &mut JNIEnv<'jni>,
         operand: &ArmOpMem,
+        addressing: &Addressing,
     ) -> JObjectResult<'jni> {
         let obj = env.new_object(
             self.get_memory_operand_class(),
@@ -54,42 +74,36 @@ impl ArmInstructionWriter<ArmShift, ArmOpMem, ArmOperandType, ArmInsnDetail<'_>>
                 JValue::Int(operand.disp()),
             ],
         )?;
-        Ok(env.auto_local(obj))
-    }
+        let obj = env.auto_local(obj);
 
-    fn write_operand_value(&self, dst: &mut Obj, op: &ArmOperandType) -> JResult<()> {
-        let env = dst.env();
-
-        let value = match op {
-            ArmOperandType::Invalid => None,
-            ArmOperandType::Reg(v) => Some((1, boxed_int(env, v.0 as i32)?)),
-            ArmOperandType::Imm(v) => Some((2, boxed_int(env, *v)?)),
-            ArmOperandType::Mem(v) => Some((3, self.create_memory_operand(env, v)?)),
-            ArmOperandType::Fp(v) => Some((4, boxed_double(env, *v)?)),
-            ArmOperandType::Cimm(v) => Some((5, boxed_int(env, *v)?)),
-            ArmOperandType::Pimm(v) => Some((6, boxed_int(env, *v)?)),
-            ArmOperandType::Setend(v) => Some((7, boxed_byte(env, *v as i8)?)),
-            ArmOperandType::SysReg(v) => Some((8, boxed_int(env, v.0 as i32)?)),
-        };
-
-        if let Some((op_type, value)) = value {
-            dst.set_byte("operandType", op_type)?;
-            dst.set_object("rawOperandValue", "Ljava/lang/Object;", &value)?;
-        } else {
-            dst.set_byte("operandType", 0)?;
-        }
+        let mut mem_dst = Obj::from(env, &obj);
+        self.write_addressing(&mut mem_dst, addressing)?;
+        mem_dst.set_bool("isSubtracted", addressing.subtracted)?;
 
-        Ok(())
+        Ok(obj)
+    }
+
+    fn write_operand_value(
+        &self,
+        dst: &mut Obj,
+        op: &ArmOperandType,
+        addressing: &Addressing,
+    ) -> JResult<()> {
+        let (op_type, value) = arm32::operand_value(op);
+        self.write_mapped_value(dst, op_type, value, addressing)
     }
 
     fn create_operands<'jni>(
         &self,
         env: &mut JNIEnv<'jni>,
+        id: InsnId,
         detail: &ArmInsnDetail<'_>,
     ) -> JResult<Vec<AutoObject<'jni>>> {
+        let operands: Vec<_> = detail.operands().collect();
+        let op_types: Vec<_> = operands.iter().map(|op| op.op_type.clone()).collect();
         let mut objects = Vec::new();
 
-        for ref op in detail.operands() {
+        for (index, op) in operands.iter().enumerate() {
             let object = self.create_operand_object(env)?;
 
             let mut op_dst = Obj::from(env, &object);
@@ -99,11 +113,15 @@ impl ArmInstructionWriter<ArmShift, ArmOpMem, ArmOperandType, ArmInsnDetail<'_>>
 
             op_dst.set_bool("isSubtracted", op.subtracted)?;
 
-            let (shift_type, value) = self.get_shift(op.shift);
+            let (shift_type, value) = arm32::shift(op.shift);
             op_dst.set_byte("shiftType", shift_type)?;
             op_dst.set_long("shiftValue", value as i64)?;
 
-            self.write_operand_value(&mut op_dst, &op.op_type)?;
+            let addressing = arm32::addressing(id, &operands, index, detail.writeback());
+            self.write_operand_value(&mut op_dst, &op.op_type, &addressing)?;
+
+            let reg = sysreg::decode_arm32(id, &op_types, index);
+            self.write_system_register(&mut op_dst, reg.as_ref())?;
 
             objects.push(object);
         }
@@ -133,7 +151,7 @@ impl InstructionWriter for Arm32Writer {
             .expect("detail mode should be on");
 
         let mut dst = Obj::from(env, insn_object);
-        self.write_common(&mut dst, &detail, insn)?;
+        self.write_common(&mut dst, &detail, insn, self.thumb)?;
 
         let arch_detail = detail.arch_detail();
         let arch = arch_detail.arm().ok_or("no detail")?;
@@ -148,12 +166,55 @@ impl InstructionWriter for Arm32Writer {
         dst.set_bool("writebackRequired", arch.writeback())?;
         dst.set_byte("memBarrier", arch.mem_barrier() as i8)?;
 
-        self.write_operands(&mut dst, arch)?;
+        let flow = flow::classify_arm(insn.id(), detail.groups(), arch);
+        self.write_flow(&mut dst, &flow)?;
+        self.write_branch_target(&mut dst, flow::branch_target_arm(insn.id(), arch))?;
+
+        self.write_flag_effects(&mut dst, &flags::effects_arm(insn, &detail, arch))?;
+
+        self.write_operands(&mut dst, insn.id(), arch)?;
+
+        Ok(())
+    }
+
+    fn write_reference<'jni>(
+        &self,
+        env: &mut JNIEnv<'jni>,
+        insn_object: &JObject<'jni>,
+        reference: Option<&PcRelReference>,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut dst = Obj::from(env, insn_object);
+        self.write_pc_reference(&mut dst, reference)?;
+        Ok(())
+    }
+
+    fn write_jump_table<'jni>(
+        &self,
+        env: &mut JNIEnv<'jni>,
+        insn_object: &JObject<'jni>,
+        table: Option<&JumpTable>,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut dst = Obj::from(env, insn_object);
+        self.write_jump_table_fields(&mut dst, table)?;
+        Ok(())
+    }
 
+    fn write_symbolized_operand<'jni>(
+        &self,
+        env: &mut JNIEnv<'jni>,
+        insn_object: &JObject<'jni>,
+        operand: Option<&str>,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut dst = Obj::from(env, insn_object);
+        self.write_symbolized_operand_field(&mut dst, operand)?;
         Ok(())
     }
 
     fn get_instruction_class(&self) -> &'static str {
         "Lorg/native4j/capstone/insn/arm/CsInsnArm32;"
     }
+
+    fn get_layout(&self) -> Vec<ClassLayout<'_>> {
+        self.get_arm_layout(INSN_FIELDS, OPERAND_FIELDS, MEMORY_FIELDS, "(IIII)V")
+    }
 }
@@ -6,40 +6,55 @@
 use std::error::Error;
 use std::sync::MutexGuard;
 
-use capstone::arch::arm64::{Arm64InsnDetail, Arm64OpMem, Arm64OperandType, Arm64Shift};
+use capstone::arch::arm64::{Arm64InsnDetail, Arm64OpMem, Arm64OperandType};
 use capstone::arch::DetailsArchInsn;
-use capstone::{Capstone, Insn};
+use capstone::{Capstone, Insn, InsnId};
 use jni::objects::{JObject, JValue};
 use jni::sys::jint;
 use jni::JNIEnv;
 
-use crate::obj::boxing::{boxed_byte, boxed_double, boxed_int, boxed_long};
+use crate::analysis::jumptable::JumpTable;
+use crate::analysis::pcrel::PcRelReference;
+use crate::analysis::{flags, flow};
 use crate::obj::obj::Obj;
+use crate::operand::{arm64, sysreg, Addressing};
 use crate::util::{AutoObject, JObjectResult, JResult};
-use crate::writer::arm::{ArmInstructionWriter, ShiftType};
+use crate::writer::arm::ArmInstructionWriter;
+use crate::writer::layout::{ClassLayout, Member};
 use crate::writer::InstructionWriter;
 
+/// Fields written to ARM64 instruction objects.
+const INSN_FIELDS: &[Member] = &[
+    Member::Field("conditionCodes", "B"),
+    Member::Field("updatesFlags", "Z"),
+    Member::Field("writebackRequired", "Z"),
+    Member::Field(
+        "operands",
+        "[Lorg/native4j/capstone/insn/arm/CsOperandArm64;",
+    ),
+];
+
+/// Fields written to ARM64 operand objects.
+const OPERAND_FIELDS: &[Member] = &[
+    Member::Field("vectorIndex", "I"),
+    Member::Field("vas", "B"),
+    Member::Field("shiftType", "B"),
+    Member::Field("shiftValue", "J"),
+    Member::Field("ext", "B"),
+];
+
+/// Fields written to ARM64 memory operand objects.
+const MEMORY_FIELDS: &[Member] = &[Member::Field("indexExtend", "B")];
+
 /// Writer for ARM64 instructions.
 pub struct Arm64Writer;
 
-impl ArmInstructionWriter<Arm64Shift, Arm64OpMem, Arm64OperandType, Arm64InsnDetail<'_>>
-    for Arm64Writer
-{
-    fn get_shift(&self, shift: Arm64Shift) -> (ShiftType, u32) {
-        match shift {
-            Arm64Shift::Invalid => (0, 0),
-            Arm64Shift::Lsl(v) => (1, v),
-            Arm64Shift::Msl(v) => (2, v),
-            Arm64Shift::Lsr(v) => (3, v),
-            Arm64Shift::Asr(v) => (4, v),
-            Arm64Shift::Ror(v) => (5, v),
-        }
-    }
-
+impl ArmInstructionWriter<Arm64OpMem, Arm64OperandType, Arm64InsnDetail<'_>> for Arm64Writer {
     fn create_memory_operand<'jni>(
         &self,
         env: &mut JNIEnv<'jni>,
         operand: &Arm64OpMem,
+        addressing: &Addressing,
     ) -> JObjectResult<'jni> {
         let obj = env.new_object(
             self.get_memory_operand_class(),
@@ -50,45 +65,35 @@ impl ArmInstructionWriter<Arm64Shift, Arm64OpMem, Arm64OperandType, Arm64InsnDet
                 JValue::Int(operand.disp()),
             ],
         )?;
-        Ok(env.auto_local(obj))
-    }
+        let obj = env.auto_local(obj);
 
-    fn write_operand_value(&self, dst: &mut Obj, op: &Arm64OperandType) -> JResult<()> {
-        let env = dst.env();
-
-        let value = match op {
-            Arm64OperandType::Invalid => None,
-            Arm64OperandType::Reg(v) => Some((1, boxed_int(env, v.0 as i32)?)),
-            Arm64OperandType::Imm(v) => Some((2, boxed_long(env, *v)?)),
-            Arm64OperandType::Mem(v) => Some((3, self.create_memory_operand(env, v)?)),
-            Arm64OperandType::Fp(v) => Some((4, boxed_double(env, *v)?)),
-            Arm64OperandType::Cimm(v) => Some((5, boxed_long(env, *v)?)),
-            Arm64OperandType::RegMrs(v) => Some((6, boxed_int(env, *v as i32)?)),
-            Arm64OperandType::RegMsr(v) => Some((7, boxed_int(env, *v as i32)?)),
-            Arm64OperandType::Pstate(v) => Some((8, boxed_byte(env, *v as i8)?)),
-            Arm64OperandType::Sys(v) => Some((9, boxed_long(env, *v as i64)?)),
-            Arm64OperandType::Prefetch(v) => Some((10, boxed_byte(env, *v as i8)?)),
-            Arm64OperandType::Barrier(v) => Some((11, boxed_byte(env, *v as i8)?)),
-        };
-
-        if let Some((op_type, value)) = value {
-            dst.set_byte("operandType", op_type)?;
-            dst.set_object("rawOperandValue", "Ljava/lang/Object;", &value)?;
-        } else {
-            dst.set_byte("operandType", 0)?;
-        }
+        let mut mem_dst = Obj::from(env, &obj);
+        self.write_addressing(&mut mem_dst, addressing)?;
+        mem_dst.set_byte("indexExtend", addressing.extend)?;
 
-        Ok(())
+        Ok(obj)
+    }
+
+    fn write_operand_value(
+        &self,
+        dst: &mut Obj,
+        op: &Arm64OperandType,
+        addressing: &Addressing,
+    ) -> JResult<()> {
+        let (op_type, value) = arm64::operand_value(op);
+        self.write_mapped_value(dst, op_type, value, addressing)
     }
 
     fn create_operands<'jni>(
         &self,
         env: &mut JNIEnv<'jni>,
+        id: InsnId,
         detail: &Arm64InsnDetail<'_>,
     ) -> JResult<Vec<AutoObject<'jni>>> {
+        let operands: Vec<_> = detail.operands().collect();
         let mut objects = Vec::new();
 
-        for ref op in detail.operands() {
+        for (index, op) in operands.iter().enumerate() {
             let object = self.create_operand_object(env)?;
 
             let mut op_dst = Obj::from(env, &object);
@@ -98,13 +103,17 @@ impl ArmInstructionWriter<Arm64Shift, Arm64OpMem, Arm64OperandType, Arm64InsnDet
 
             op_dst.set_byte("vas", op.vas as i8)?;
 
-            let (shift_type, value) = self.get_shift(op.shift);
+            let (shift_type, value) = arm64::shift(op.shift);
             op_dst.set_byte("shiftType", shift_type)?;
             op_dst.set_long("shiftValue", value as i64)?;
 
             op_dst.set_byte("ext", op.ext as i8)?;
 
-            self.write_operand_value(&mut op_dst, &op.op_type)?;
+            let addressing = arm64::addressing(&operands, index, detail.writeback());
+            self.write_operand_value(&mut op_dst, &op.op_type, &addressing)?;
+
+            let reg = sysreg::decode_arm64(id, &op.op_type);
+            self.write_system_register(&mut op_dst, reg.as_ref())?;
 
             objects.push(object);
         }
@@ -134,7 +143,7 @@ impl InstructionWriter for Arm64Writer {
             .expect("detail mode should be on");
 
         let mut dst = Obj::from(env, insn_object);
-        self.write_common(&mut dst, &detail, insn)?;
+        self.write_common(&mut dst, &detail, insn, false)?;
 
         let arch_detail = detail.arch_detail();
         let arch = arch_detail.arm64().ok_or("no detail")?;
@@ -143,12 +152,55 @@ impl InstructionWriter for Arm64Writer {
         dst.set_bool("updatesFlags", arch.update_flags())?;
         dst.set_bool("writebackRequired", arch.writeback())?;
 
-        self.write_operands(&mut dst, arch)?;
+        let flow = flow::classify_arm64(insn.id(), detail.groups(), arch);
+        self.write_flow(&mut dst, &flow)?;
+        self.write_branch_target(&mut dst, flow::branch_target_arm64(insn.id(), arch))?;
+
+        self.write_flag_effects(&mut dst, &flags::effects_arm64(insn, &detail, arch))?;
+
+        self.write_operands(&mut dst, insn.id(), arch)?;
+
+        Ok(())
+    }
+
+    fn write_reference<'jni>(
+        &self,
+        env: &mut JNIEnv<'jni>,
+        insn_object: &JObject<'jni>,
+        reference: Option<&PcRelReference>,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut dst = Obj::from(env, insn_object);
+        self.write_pc_reference(&mut dst, reference)?;
+        Ok(())
+    }
+
+    fn write_jump_table<'jni>(
+        &self,
+        env: &mut JNIEnv<'jni>,
+        insn_object: &JObject<'jni>,
+        table: Option<&JumpTable>,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut dst = Obj::from(env, insn_object);
+        self.write_jump_table_fields(&mut dst, table)?;
+        Ok(())
+    }
 
+    fn write_symbolized_operand<'jni>(
+        &self,
+        env: &mut JNIEnv<'jni>,
+        insn_object: &JObject<'jni>,
+        operand: Option<&str>,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut dst = Obj::from(env, insn_object);
+        self.write_symbolized_operand_field(&mut dst, operand)?;
         Ok(())
     }
 
     fn get_instruction_class(&self) -> &'static str {
         "Lorg/native4j/capstone/insn/arm/CsInsnArm64;"
     }
+
+    fn get_layout(&self) -> Vec<ClassLayout<'_>> {
+        self.get_arm_layout(INSN_FIELDS, OPERAND_FIELDS, MEMORY_FIELDS, "(III)V")
+    }
 }
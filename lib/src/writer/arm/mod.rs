@@ -3,34 +3,156 @@
  *
  * Use of this source code is governed by the MIT license found in the LICENSE file.
  */
-use capstone::{Insn, InsnDetail};
+use capstone::{Insn, InsnDetail, InsnId};
+use jni::objects::{JObject, JValue};
 use jni::JNIEnv;
 
+use crate::analysis::flags::FlagEffects;
+use crate::analysis::flow::{BranchTarget, FlowInfo};
+use crate::analysis::jumptable::JumpTable;
+use crate::analysis::pcrel::PcRelReference;
+use crate::obj::boxing::{boxed_byte, boxed_double, boxed_int, boxed_long};
 use crate::obj::obj::Obj;
+use crate::operand::sysreg::SystemRegister;
+use crate::operand::{Addressing, OperandValue};
 use crate::util::{AutoObject, JObjectResult, JResult};
+use crate::writer::layout::{ClassLayout, Member, INSN_FIELDS, OPERAND_FIELDS};
 use crate::writer::InstructionWriter;
 
-type ShiftType = i8;
+/// Fields written to every ARM instruction object in addition to the common ones.
+const ARM_INSN_FIELDS: &[Member] = &[
+    Member::Field("encoding", "I"),
+    Member::Field("isBranch", "Z"),
+    Member::Field("isConditional", "Z"),
+    Member::Field("isCall", "Z"),
+    Member::Field("isReturn", "Z"),
+    Member::Field("isIndirect", "Z"),
+    Member::Field("isPcRelative", "Z"),
+    Member::Field("isPrivileged", "Z"),
+    Member::Field("isInterrupt", "Z"),
+    Member::Field("isTerminator", "Z"),
+    Member::Field("flagsRead", "B"),
+    Member::Field("flagsWritten", "B"),
+    Member::Field("branchTarget", "Ljava/lang/Long;"),
+    Member::Field("isInterworking", "Z"),
+    Member::Field("referencedAddress", "Ljava/lang/Long;"),
+    Member::Field("referencedValue", "Ljava/lang/Long;"),
+    Member::Field("jumpTableAddress", "Ljava/lang/Long;"),
+    Member::Field("jumpTableTargets", "[J"),
+    Member::Field("symbolizedOperand", "Ljava/lang/String;"),
+];
 
-/// ARM-specific instruction writer.
-trait ArmInstructionWriter<Shift, OpMem, OpType, ArchInsnDetail>: InstructionWriter {
-    /// Get the shift type and value for the given shift object.
-    fn get_shift(&self, shift: Shift) -> (ShiftType, u32);
+/// Fields written to every ARM operand object in addition to the common ones.
+const ARM_OPERAND_FIELDS: &[Member] = &[Member::Field(
+    "systemRegister",
+    "Lorg/native4j/capstone/insn/arm/SystemRegister;",
+)];
+
+/// Fields written to every ARM memory operand object in addition to those set by its
+/// constructor.
+const ARM_MEMORY_FIELDS: &[Member] = &[
+    Member::Field("addressingMode", "B"),
+    Member::Field("baseUpdated", "Z"),
+    Member::Field("postIndexImmediate", "J"),
+    Member::Field("postIndexRegister", "I"),
+    Member::Field("indexShiftType", "B"),
+    Member::Field("indexShiftValue", "I"),
+];
+
+/// Class of decoded system register objects.
+const SYSTEM_REGISTER_CLASS: &str = "Lorg/native4j/capstone/insn/arm/SystemRegister;";
 
+/// Constructor of decoded system register objects.
+const SYSTEM_REGISTER_CONSTRUCTOR: &str = "(IIIIIILjava/lang/String;)V";
+
+/// ARM-specific instruction writer.
+trait ArmInstructionWriter<OpMem, OpType, ArchInsnDetail>: InstructionWriter {
     /// Create a memory operand Java object.
     fn create_memory_operand<'jni>(
         &self,
         env: &mut JNIEnv<'jni>,
         operand: &OpMem,
+        addressing: &Addressing,
     ) -> JObjectResult<'jni>;
 
-    /// Write the value of the given operand to the given object.
-    fn write_operand_value(&self, dst: &mut Obj, op: &OpType) -> JResult<()>;
+    /// Write the value of the given operand to the given object. The addressing is only
+    /// used for memory operands.
+    fn write_operand_value(
+        &self,
+        dst: &mut Obj,
+        op: &OpType,
+        addressing: &Addressing,
+    ) -> JResult<()>;
+
+    /// Write a mapped operand type and value to the given object.
+    fn write_mapped_value(
+        &self,
+        dst: &mut Obj,
+        op_type: i8,
+        value: OperandValue<&OpMem>,
+        addressing: &Addressing,
+    ) -> JResult<()> {
+        let env = dst.env();
+        let value = match value {
+            OperandValue::None => {
+                return dst.set_byte("operandType", 0);
+            }
+            OperandValue::Reg(v) => boxed_int(env, v.0 as i32)?,
+            OperandValue::Int(v) => boxed_int(env, v)?,
+            OperandValue::Long(v) => boxed_long(env, v)?,
+            OperandValue::Byte(v) => boxed_byte(env, v)?,
+            OperandValue::Double(v) => boxed_double(env, v)?,
+            OperandValue::Mem(v) => self.create_memory_operand(env, v, addressing)?,
+        };
+
+        dst.set_byte("operandType", op_type)?;
+        dst.set_object("rawOperandValue", "Ljava/lang/Object;", &value)
+    }
+
+    /// Write the addressing fields shared by ARM memory operands to the given object.
+    fn write_addressing(&self, dst: &mut Obj, addressing: &Addressing) -> JResult<()> {
+        dst.set_byte("addressingMode", addressing.mode)?;
+        dst.set_bool("baseUpdated", addressing.base_updated())?;
+        dst.set_long("postIndexImmediate", addressing.post_index_imm)?;
+        dst.set_int("postIndexRegister", addressing.post_index_reg.0 as i32)?;
+        dst.set_byte("indexShiftType", addressing.shift_type)?;
+        dst.set_int("indexShiftValue", addressing.shift_value as i32)
+    }
+
+    /// Write the decoded system register of an operand to the given object.
+    fn write_system_register(&self, dst: &mut Obj, reg: Option<&SystemRegister>) -> JResult<()> {
+        let Some(reg) = reg else {
+            return dst.set_object("systemRegister", SYSTEM_REGISTER_CLASS, &JObject::null());
+        };
+
+        let env = dst.env();
+        let name = match reg.name {
+            Some(name) => env.new_string(name)?.into(),
+            None => JObject::null(),
+        };
+        let name = env.auto_local(name);
+        let obj = env.new_object(
+            SYSTEM_REGISTER_CLASS,
+            SYSTEM_REGISTER_CONSTRUCTOR,
+            &[
+                JValue::Int(reg.coprocessor),
+                JValue::Int(reg.op0),
+                JValue::Int(reg.op1),
+                JValue::Int(reg.crn),
+                JValue::Int(reg.crm),
+                JValue::Int(reg.op2),
+                JValue::Object(&name),
+            ],
+        )?;
+        let obj = env.auto_local(obj);
+        dst.set_object("systemRegister", SYSTEM_REGISTER_CLASS, &obj)
+    }
 
     /// Create a list of operand objects for the given instruction.
     fn create_operands<'jni>(
         &self,
         env: &mut JNIEnv<'jni>,
+        id: InsnId,
         detail: &ArchInsnDetail,
     ) -> JResult<Vec<AutoObject<'jni>>>;
 
@@ -47,18 +169,143 @@ trait ArmInstructionWriter<Shift, OpMem, OpType, ArchInsnDetail>: InstructionWri
     }
 
     /// Write the operands of the given instruction to the given object.
-    fn write_operands(&self, dst: &mut Obj, detail: &ArchInsnDetail) -> JResult<()> {
-        let operands = self.create_operands(dst.env(), detail)?;
+    fn write_operands(&self, dst: &mut Obj, id: InsnId, detail: &ArchInsnDetail) -> JResult<()> {
+        let operands = self.create_operands(dst.env(), id, detail)?;
         dst.set_object_array("operands", self.get_operand_class(), &operands)
     }
 
+    /// Write the control-flow classification of an instruction to the given object.
+    fn write_flow(&self, dst: &mut Obj, flow: &FlowInfo) -> JResult<()> {
+        dst.set_bool("isBranch", flow.is_branch)?;
+        dst.set_bool("isConditional", flow.is_conditional)?;
+        dst.set_bool("isCall", flow.is_call)?;
+        dst.set_bool("isReturn", flow.is_return)?;
+        dst.set_bool("isIndirect", flow.is_indirect)?;
+        dst.set_bool("isPcRelative", flow.is_pc_relative)?;
+        dst.set_bool("isPrivileged", flow.is_privileged)?;
+        dst.set_bool("isInterrupt", flow.is_interrupt)?;
+        dst.set_bool("isTerminator", flow.is_terminator)
+    }
+
+    /// Write the condition flags read and written by an instruction to the given object.
+    fn write_flag_effects(&self, dst: &mut Obj, effects: &FlagEffects) -> JResult<()> {
+        dst.set_byte("flagsRead", effects.read as i8)?;
+        dst.set_byte("flagsWritten", effects.written as i8)
+    }
+
+    /// Write the target of a direct branch to the given object.
+    fn write_branch_target(&self, dst: &mut Obj, target: Option<BranchTarget>) -> JResult<()> {
+        match target {
+            Some(target) => {
+                let address = boxed_long(dst.env(), target.address as i64)?;
+                dst.set_object("branchTarget", "Ljava/lang/Long;", &address)?;
+                dst.set_bool("isInterworking", target.is_interworking)
+            }
+            None => {
+                dst.set_object("branchTarget", "Ljava/lang/Long;", &JObject::null())?;
+                dst.set_bool("isInterworking", false)
+            }
+        }
+    }
+
+    /// Write a PC-relative reference to the given object.
+    fn write_pc_reference(&self, dst: &mut Obj, reference: Option<&PcRelReference>) -> JResult<()> {
+        let (address, value) = match reference {
+            Some(reference) => (Some(reference.address), reference.value),
+            None => (None, None),
+        };
+
+        for (field, value) in [("referencedAddress", address), ("referencedValue", value)] {
+            match value {
+                Some(value) => {
+                    let value = boxed_long(dst.env(), value as i64)?;
+                    dst.set_object(field, "Ljava/lang/Long;", &value)?;
+                }
+                None => dst.set_object(field, "Ljava/lang/Long;", &JObject::null())?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the jump table of an indirect branch to the given object.
+    fn write_jump_table_fields(&self, dst: &mut Obj, table: Option<&JumpTable>) -> JResult<()> {
+        match table {
+            Some(table) => {
+                let address = boxed_long(dst.env(), table.address as i64)?;
+                dst.set_object("jumpTableAddress", "Ljava/lang/Long;", &address)?;
+                let targets: Vec<i64> = table.targets.iter().map(|t| *t as i64).collect();
+                dst.set_long_array("jumpTableTargets", &targets)
+            }
+            None => {
+                dst.set_object("jumpTableAddress", "Ljava/lang/Long;", &JObject::null())?;
+                dst.set_object("jumpTableTargets", "[J", &JObject::null())
+            }
+        }
+    }
+
+    /// Write the symbolized operand string to the given object.
+    fn write_symbolized_operand_field(&self, dst: &mut Obj, operand: Option<&str>) -> JResult<()> {
+        match operand {
+            Some(operand) => dst.set_str("symbolizedOperand", operand),
+            None => dst.set_object("symbolizedOperand", "Ljava/lang/String;", &JObject::null()),
+        }
+    }
+
+    /// Get the layout of the instruction, operand and memory operand classes, given the
+    /// architecture-specific members of each.
+    fn get_arm_layout(
+        &self,
+        insn_fields: &[Member],
+        operand_fields: &[Member],
+        memory_fields: &[Member],
+        memory_constructor: &'static str,
+    ) -> Vec<ClassLayout<'_>> {
+        vec![
+            ClassLayout::new(
+                self.get_instruction_class(),
+                &[INSN_FIELDS, ARM_INSN_FIELDS, insn_fields],
+            ),
+            ClassLayout::new(
+                self.get_operand_class(),
+                &[
+                    OPERAND_FIELDS,
+                    ARM_OPERAND_FIELDS,
+                    operand_fields,
+                    &[Member::Constructor("()V")],
+                ],
+            ),
+            ClassLayout::new(
+                self.get_memory_operand_class(),
+                &[
+                    ARM_MEMORY_FIELDS,
+                    memory_fields,
+                    &[Member::Constructor(memory_constructor)],
+                ],
+            ),
+            ClassLayout::new(
+                SYSTEM_REGISTER_CLASS,
+                &[&[Member::Constructor(SYSTEM_REGISTER_CONSTRUCTOR)]],
+            ),
+        ]
+    }
+
     /// Write the common fields of the given instruction to the given object.
-    fn write_common(&self, dst: &mut Obj, detail: &InsnDetail, insn: &Insn) -> JResult<()> {
+    fn write_common(
+        &self,
+        dst: &mut Obj,
+        detail: &InsnDetail,
+        insn: &Insn,
+        thumb: bool,
+    ) -> JResult<()> {
         dst.set_str("mnemonic", insn.mnemonic().unwrap())?;
         dst.set_str("operand", insn.op_str().unwrap())?;
 
         dst.set_int("instructionId", insn.id().0 as i32)?;
         dst.set_int("size", insn.len() as i32)?;
+        dst.set_byte_array("bytes", insn.bytes())?;
+
+        dst.set_int("encoding", encoding(insn.bytes(), thumb) as i32)?;
 
         dst.set_long("address", insn.address() as i64)?;
 
@@ -73,5 +320,31 @@ trait ArmInstructionWriter<Shift, OpMem, OpType, ArchInsnDetail>: InstructionWri
     }
 }
 
+/// Get the encoding word of a 4 byte instruction, or 0 for any other size.
+/// 32-bit Thumb-2 instructions are stored as two little-endian halfwords, the
+/// first of which holds the high 16 bits of the encoding.
+pub fn encoding(bytes: &[u8], thumb: bool) -> u32 {
+    match <[u8; 4]>::try_from(bytes) {
+        Ok([b0, b1, b2, b3]) if thumb => u32::from_le_bytes([b2, b3, b0, b1]),
+        Ok(bytes) => u32::from_le_bytes(bytes),
+        Err(_) => 0,
+    }
+}
+
 pub mod arm32;
 pub mod arm64;
+
+#[cfg(test)]
+mod tests {
+    use super::encoding;
+
+    #[test]
+    fn encoding_words() {
+        // ldr r0, [r1, #4]
+        assert_eq!(encoding(&[0x04, 0x00, 0x91, 0xe5], false), 0xe5910004);
+        // ldr.w r0, [r1, #4]
+        assert_eq!(encoding(&[0xd1, 0xf8, 0x04, 0x00], true), 0xf8d10004);
+        // movs r0, #1
+        assert_eq!(encoding(&[0x01, 0x20], true), 0);
+    }
+}
@@ -0,0 +1,280 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use std::error::Error;
+use std::sync::MutexGuard;
+
+use capstone::arch::arm::ArmOperandType;
+use capstone::arch::arm64::Arm64OperandType;
+use capstone::arch::mips::MipsOperand;
+use capstone::arch::ppc::PpcOperand;
+use capstone::arch::sparc::SparcOperand;
+use capstone::arch::x86::X86OperandType;
+use capstone::arch::ArchOperand;
+use capstone::{Capstone, Insn, RegId};
+use jni::objects::{JObject, JValue};
+use jni::sys::{jint, jlong};
+use jni::JNIEnv;
+
+use crate::obj::boxing::{boxed_double, boxed_int, boxed_long};
+use crate::obj::obj::Obj;
+use crate::util::{AutoObject, JObjectResult, JResult};
+use crate::writer::layout::{ClassLayout, Member, INSN_FIELDS, OPERAND_FIELDS};
+use crate::writer::InstructionWriter;
+
+/// Architecture-neutral view of an operand.
+enum GenericOperand {
+    Invalid,
+    Reg(RegId),
+    Imm(i64),
+    Mem(GenericMem),
+    Fp(f64),
+    Other(Option<i64>),
+}
+
+/// Architecture-neutral view of a memory operand.
+struct GenericMem {
+    base: RegId,
+    index: RegId,
+    scale: i32,
+    disp: i64,
+}
+
+impl GenericMem {
+    fn new(base: RegId, index: RegId, scale: i32, disp: i64) -> GenericMem {
+        GenericMem {
+            base,
+            index,
+            scale,
+            disp,
+        }
+    }
+}
+
+impl From<&ArchOperand> for GenericOperand {
+    fn from(op: &ArchOperand) -> Self {
+        match op {
+            ArchOperand::ArmOperand(op) => match op.op_type {
+                ArmOperandType::Invalid => GenericOperand::Invalid,
+                ArmOperandType::Reg(v) => GenericOperand::Reg(v),
+                ArmOperandType::Imm(v) => GenericOperand::Imm(v as i64),
+                ArmOperandType::Mem(ref v) => GenericOperand::Mem(GenericMem::new(
+                    v.base(),
+                    v.index(),
+                    v.scale(),
+                    v.disp() as i64,
+                )),
+                ArmOperandType::Fp(v) => GenericOperand::Fp(v),
+                ArmOperandType::Cimm(v) => GenericOperand::Other(Some(v as i64)),
+                ArmOperandType::Pimm(v) => GenericOperand::Other(Some(v as i64)),
+                ArmOperandType::Setend(v) => GenericOperand::Other(Some(v as i64)),
+                ArmOperandType::SysReg(v) => GenericOperand::Other(Some(v.0 as i64)),
+            },
+            ArchOperand::Arm64Operand(op) => match op.op_type {
+                Arm64OperandType::Invalid => GenericOperand::Invalid,
+                Arm64OperandType::Reg(v) => GenericOperand::Reg(v),
+                Arm64OperandType::Imm(v) => GenericOperand::Imm(v),
+                Arm64OperandType::Mem(ref v) => {
+                    GenericOperand::Mem(GenericMem::new(v.base(), v.index(), 1, v.disp() as i64))
+                }
+                Arm64OperandType::Fp(v) => GenericOperand::Fp(v),
+                Arm64OperandType::Cimm(v) => GenericOperand::Other(Some(v)),
+                Arm64OperandType::RegMrs(v) => GenericOperand::Other(Some(v as i64)),
+                Arm64OperandType::RegMsr(v) => GenericOperand::Other(Some(v as i64)),
+                Arm64OperandType::Pstate(v) => GenericOperand::Other(Some(v as i64)),
+                Arm64OperandType::Sys(v) => GenericOperand::Other(Some(v as i64)),
+                Arm64OperandType::Prefetch(v) => GenericOperand::Other(Some(v as i64)),
+                Arm64OperandType::Barrier(v) => GenericOperand::Other(Some(v as i64)),
+            },
+            ArchOperand::X86Operand(op) => match op.op_type {
+                X86OperandType::Invalid => GenericOperand::Invalid,
+                X86OperandType::Reg(v) => GenericOperand::Reg(v),
+                X86OperandType::Imm(v) => GenericOperand::Imm(v),
+                X86OperandType::Mem(ref v) => {
+                    GenericOperand::Mem(GenericMem::new(v.base(), v.index(), v.scale(), v.disp()))
+                }
+            },
+            ArchOperand::MipsOperand(op) => match op {
+                MipsOperand::Reg(v) => GenericOperand::Reg(*v),
+                MipsOperand::Imm(v) => GenericOperand::Imm(*v),
+                MipsOperand::Mem(v) => {
+                    GenericOperand::Mem(GenericMem::new(v.base(), RegId(0), 1, v.disp()))
+                }
+                MipsOperand::Invalid => GenericOperand::Invalid,
+            },
+            ArchOperand::PpcOperand(op) => match op {
+                PpcOperand::Reg(v) => GenericOperand::Reg(*v),
+                PpcOperand::Imm(v) => GenericOperand::Imm(*v),
+                PpcOperand::Mem(v) => {
+                    GenericOperand::Mem(GenericMem::new(v.base(), RegId(0), 1, v.disp() as i64))
+                }
+                PpcOperand::Invalid => GenericOperand::Invalid,
+                _ => GenericOperand::Other(None),
+            },
+            ArchOperand::SparcOperand(op) => match op {
+                SparcOperand::Reg(v) => GenericOperand::Reg(*v),
+                SparcOperand::Imm(v) => GenericOperand::Imm(*v),
+                SparcOperand::Mem(v) => {
+                    GenericOperand::Mem(GenericMem::new(v.base(), v.index(), 1, v.disp() as i64))
+                }
+                SparcOperand::Invalid => GenericOperand::Invalid,
+            },
+            _ => GenericOperand::Other(None),
+        }
+    }
+}
+
+/// Writer for instructions of any architecture that does not have a specialized writer.
+pub struct GenericWriter;
+
+impl GenericWriter {
+    /// Create a memory operand Java object.
+    fn create_memory_operand<'jni>(
+        &self,
+        env: &mut JNIEnv<'jni>,
+        operand: &GenericMem,
+    ) -> JObjectResult<'jni> {
+        let obj = env.new_object(
+            self.get_memory_operand_class(),
+            "(IIIJ)V",
+            &[
+                JValue::Int(operand.base.0 as jint),
+                JValue::Int(operand.index.0 as jint),
+                JValue::Int(operand.scale),
+                JValue::Long(operand.disp as jlong),
+            ],
+        )?;
+        Ok(env.auto_local(obj))
+    }
+
+    /// Write the value of the given operand to the given object.
+    fn write_operand_value(&self, dst: &mut Obj, op: &GenericOperand) -> JResult<()> {
+        let env = dst.env();
+
+        let value = match op {
+            GenericOperand::Invalid => None,
+            GenericOperand::Reg(v) => Some((1, Some(boxed_int(env, v.0 as i32)?))),
+            GenericOperand::Imm(v) => Some((2, Some(boxed_long(env, *v)?))),
+            GenericOperand::Mem(v) => Some((3, Some(self.create_memory_operand(env, v)?))),
+            GenericOperand::Fp(v) => Some((4, Some(boxed_double(env, *v)?))),
+            GenericOperand::Other(Some(v)) => Some((5, Some(boxed_long(env, *v)?))),
+            GenericOperand::Other(None) => Some((5, None)),
+        };
+
+        match value {
+            Some((op_type, Some(value))) => {
+                dst.set_byte("operandType", op_type)?;
+                dst.set_object("rawOperandValue", "Ljava/lang/Object;", &value)?;
+            }
+            Some((op_type, None)) => dst.set_byte("operandType", op_type)?,
+            None => dst.set_byte("operandType", 0)?,
+        }
+
+        Ok(())
+    }
+
+    /// Create a list of operand objects for the given operands.
+    fn create_operands<'jni>(
+        &self,
+        env: &mut JNIEnv<'jni>,
+        operands: &[ArchOperand],
+    ) -> JResult<Vec<AutoObject<'jni>>> {
+        let mut objects = Vec::new();
+
+        for op in operands {
+            let object = {
+                let obj = env.new_object(self.get_operand_class(), "()V", &[])?;
+                env.auto_local(obj)
+            };
+
+            let mut op_dst = Obj::from(env, &object);
+            self.write_operand_value(&mut op_dst, &GenericOperand::from(op))?;
+
+            objects.push(object);
+        }
+
+        Ok(objects)
+    }
+
+    /// Get the class name of the memory operand object.
+    fn get_memory_operand_class(&self) -> &str {
+        "Lorg/native4j/capstone/insn/CsMemOperandGeneric;"
+    }
+
+    /// Get the class name of the operand object.
+    fn get_operand_class(&self) -> &str {
+        "Lorg/native4j/capstone/insn/CsOperandGeneric;"
+    }
+}
+
+impl InstructionWriter for GenericWriter {
+    fn write<'jni, 'a>(
+        &self,
+        env: &'a mut JNIEnv<'jni>,
+        insn_object: &'a JObject<'jni>,
+        insn: &Insn,
+        capstone: &MutexGuard<'a, Capstone>,
+    ) -> Result<(), Box<dyn Error>> {
+        let detail = capstone
+            .insn_detail(insn)
+            .expect("detail mode should be on");
+
+        let mut dst = Obj::from(env, insn_object);
+
+        dst.set_str("mnemonic", insn.mnemonic().unwrap())?;
+        dst.set_str("operand", insn.op_str().unwrap())?;
+
+        dst.set_int("instructionId", insn.id().0 as i32)?;
+        dst.set_int("size", insn.len() as i32)?;
+        dst.set_byte_array("bytes", insn.bytes())?;
+
+        dst.set_long("address", insn.address() as i64)?;
+
+        let regs_read: Vec<i16> = detail.regs_read().iter().map(|r| r.0 as i16).collect();
+        dst.set_short_array("regsRead", &regs_read)?;
+
+        let regs_write: Vec<i16> = detail.regs_write().iter().map(|r| r.0 as i16).collect();
+        dst.set_short_array("regsWrite", &regs_write)?;
+
+        let groups: Vec<i16> = detail.groups().iter().map(|g| g.0 as i16).collect();
+        dst.set_short_array("groups", &groups)?;
+
+        let operands = {
+            let arch_detail = detail.arch_detail();
+            let operands = arch_detail.operands();
+            self.create_operands(dst.env(), &operands)?
+        };
+        dst.set_object_array("operands", self.get_operand_class(), &operands)?;
+
+        Ok(())
+    }
+
+    fn get_instruction_class(&self) -> &'static str {
+        "Lorg/native4j/capstone/insn/CsInsnGeneric;"
+    }
+
+    fn get_layout(&self) -> Vec<ClassLayout<'_>> {
+        vec![
+            ClassLayout::new(
+                self.get_instruction_class(),
+                &[
+                    INSN_FIELDS,
+                    &[Member::Field(
+                        "operands",
+                        "[Lorg/native4j/capstone/insn/CsOperandGeneric;",
+                    )],
+                ],
+            ),
+            ClassLayout::new(
+                self.get_operand_class(),
+                &[OPERAND_FIELDS, &[Member::Constructor("()V")]],
+            ),
+            ClassLayout::new(
+                self.get_memory_operand_class(),
+                &[&[Member::Constructor("(IIIJ)V")]],
+            ),
+        ]
+    }
+}
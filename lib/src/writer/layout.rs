@@ -0,0 +1,143 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use std::sync::OnceLock;
+
+use jni::objects::JClass;
+use jni::JNIEnv;
+
+/// A member of a Java class that is accessed from native code.
+#[derive(Clone, Copy)]
+pub enum Member {
+    /// A field with its name and type signature.
+    Field(&'static str, &'static str),
+    /// A constructor with its signature.
+    Constructor(&'static str),
+}
+
+/// The members of a Java class that are accessed from native code.
+pub struct ClassLayout<'a> {
+    pub class: &'a str,
+    pub members: Vec<Member>,
+}
+
+impl<'a> ClassLayout<'a> {
+    /// Create a layout from groups of members.
+    pub fn new(class: &'a str, members: &[&[Member]]) -> ClassLayout<'a> {
+        ClassLayout {
+            class,
+            members: members.concat(),
+        }
+    }
+}
+
+/// Fields written to every instruction object.
+pub const INSN_FIELDS: &[Member] = &[
+    Member::Field("mnemonic", "Ljava/lang/String;"),
+    Member::Field("operand", "Ljava/lang/String;"),
+    Member::Field("instructionId", "I"),
+    Member::Field("size", "I"),
+    Member::Field("bytes", "[B"),
+    Member::Field("address", "J"),
+    Member::Field("regsRead", "[S"),
+    Member::Field("regsWrite", "[S"),
+    Member::Field("groups", "[S"),
+];
+
+/// Fields written to every operand object.
+pub const OPERAND_FIELDS: &[Member] = &[
+    Member::Field("operandType", "B"),
+    Member::Field("rawOperandValue", "Ljava/lang/Object;"),
+];
+
+/// Check that every class and member of the given layouts exists. All mismatches are
+/// reported together, one per line.
+pub fn validate(env: &mut JNIEnv, layouts: &[ClassLayout]) -> Result<(), String> {
+    let mut errors = Vec::new();
+
+    for layout in layouts {
+        let name = display_name(layout.class);
+        let class = match env.find_class(layout.class) {
+            Ok(class) => env.auto_local(class),
+            Err(_) => {
+                clear_exception(env);
+                errors.push(format!("class {} not found", name));
+                continue;
+            }
+        };
+
+        for member in &layout.members {
+            if !has_member(env, &class, member) {
+                errors.push(match member {
+                    Member::Field(field, sig) => {
+                        format!("{}.{}: no field of type {}", name, field, sig)
+                    }
+                    Member::Constructor(sig) => {
+                        format!("{}: no constructor with signature {}", name, sig)
+                    }
+                });
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "Java classes do not match the native library:\n  {}",
+        errors.join("\n  ")
+    ))
+}
+
+/// Validate the layouts on the first call and return the cached result afterwards.
+pub fn validate_once(
+    cache: &OnceLock<Result<(), String>>,
+    env: &mut JNIEnv,
+    layouts: impl FnOnce() -> Vec<ClassLayout<'static>>,
+) -> Result<(), String> {
+    cache.get_or_init(|| validate(env, &layouts())).clone()
+}
+
+/// Check whether a class has the given member.
+fn has_member(env: &mut JNIEnv, class: &JClass, member: &Member) -> bool {
+    let result = match member {
+        Member::Field(name, sig) => env.get_field_id(class, name, sig).map(|_| ()),
+        Member::Constructor(sig) => env.get_method_id(class, "<init>", sig).map(|_| ()),
+    };
+    if result.is_err() {
+        clear_exception(env);
+    }
+    result.is_ok()
+}
+
+/// Clear the exception thrown by a failed lookup.
+fn clear_exception(env: &mut JNIEnv) {
+    if env.exception_check().unwrap_or(false) {
+        let _ = env.exception_clear();
+    }
+}
+
+/// Get the Java name of a class given as a descriptor or internal name.
+fn display_name(class: &str) -> String {
+    class
+        .strip_prefix('L')
+        .and_then(|class| class.strip_suffix(';'))
+        .unwrap_or(class)
+        .replace('/', ".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_class_names() {
+        assert_eq!(
+            display_name("Lorg/native4j/capstone/insn/CsInsn;"),
+            "org.native4j.capstone.insn.CsInsn"
+        );
+        assert_eq!(display_name("java/lang/Long"), "java.lang.Long");
+    }
+}
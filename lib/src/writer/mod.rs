@@ -10,14 +10,20 @@ use capstone::{Capstone, Insn};
 use jni::objects::JObject;
 use jni::JNIEnv;
 
+use crate::analysis::jumptable::JumpTable;
+use crate::analysis::pcrel::PcRelReference;
 use crate::capstone::mode::CapstoneMode;
 use crate::writer::arm::{arm32, arm64};
+use crate::writer::generic::GenericWriter;
+use crate::writer::layout::ClassLayout;
 
 /// Creates a new instruction writer for the given mode.
 pub fn create_writer<'a>(mode: &CapstoneMode) -> &'a dyn InstructionWriter {
     let writer: &dyn InstructionWriter = match mode {
-        CapstoneMode::ARM32 => &arm32::Arm32Writer {},
+        CapstoneMode::ARM32 => &arm32::Arm32Writer { thumb: false },
+        CapstoneMode::Thumb => &arm32::Arm32Writer { thumb: true },
         CapstoneMode::ARM64 => &arm64::Arm64Writer {},
+        CapstoneMode::X86_32 | CapstoneMode::X86_64 => &GenericWriter {},
     };
     writer
 }
@@ -33,8 +39,46 @@ pub trait InstructionWriter {
         capstone: &MutexGuard<'a, Capstone>,
     ) -> Result<(), Box<dyn Error>>;
 
+    /// Write the PC-relative reference of an instruction to the given Java object.
+    /// Writers that do not support PC-relative analysis ignore it.
+    fn write_reference<'jni>(
+        &self,
+        _env: &mut JNIEnv<'jni>,
+        _insn_object: &JObject<'jni>,
+        _reference: Option<&PcRelReference>,
+    ) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Write the jump table of an indirect branch to the given Java object.
+    /// Writers that do not support jump table analysis ignore it.
+    fn write_jump_table<'jni>(
+        &self,
+        _env: &mut JNIEnv<'jni>,
+        _insn_object: &JObject<'jni>,
+        _table: Option<&JumpTable>,
+    ) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Write the symbolized operand string of an instruction to the given Java object.
+    /// Writers that do not support symbolization ignore it.
+    fn write_symbolized_operand<'jni>(
+        &self,
+        _env: &mut JNIEnv<'jni>,
+        _insn_object: &JObject<'jni>,
+        _operand: Option<&str>,
+    ) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
     /// Returns the name of the Java class that this writer handles.
     fn get_instruction_class(&self) -> &'static str;
+
+    /// Returns the fields and constructors of the Java classes that this writer accesses.
+    fn get_layout(&self) -> Vec<ClassLayout<'_>>;
 }
 
 pub mod arm;
+pub mod generic;
+pub mod layout;
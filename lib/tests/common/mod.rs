@@ -0,0 +1,230 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+//! Embedded JVM for testing the JNI entry points without Gradle.
+//!
+//! The Java sources are compiled with `javac` into the Cargo temporary directory and loaded
+//! into a JVM created through the invocation API. The native methods are registered directly
+//! from this test binary, so no shared library has to be built or extracted. Set
+//! `CSJ_CLASSPATH` to use already compiled classes instead, e.g. the Gradle output.
+#![allow(dead_code)]
+
+use std::ffi::c_void;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+use jni::objects::{JObject, JValue};
+use jni::{AttachGuard, InitArgsBuilder, JNIEnv, JNIVersion, JavaVM, NativeMethod};
+
+/// Annotations used by the Java sources, compiled as stubs when no classpath is given.
+const ANNOTATIONS: &[&str] = &["Nullable", "NotNull", "Unmodifiable"];
+
+static JVM: OnceLock<JavaVM> = OnceLock::new();
+
+/// Get the `javac` of `JAVA_HOME`, or the one on the `PATH`.
+fn javac() -> PathBuf {
+    match std::env::var_os("JAVA_HOME") {
+        Some(home) => Path::new(&home).join("bin").join("javac"),
+        None => PathBuf::from("javac"),
+    }
+}
+
+/// Collect the `.java` files below `dir`.
+fn sources(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            sources(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "java") {
+            files.push(path);
+        }
+    }
+}
+
+/// A textual replacement applied to a Java source file before compiling it.
+pub struct Patch {
+    /// Path of the file relative to the source root.
+    pub file: &'static str,
+    pub from: &'static str,
+    pub to: &'static str,
+}
+
+/// Compile the Java library into the `name` directory of the Cargo temporary directory,
+/// applying `patches` to the sources first, and return its class directory.
+fn compile_classes(name: &str, patches: &[Patch]) -> PathBuf {
+    let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let stubs = root.join("stubs");
+    let patched = root.join("patched");
+    let classes = root.join("classes");
+    let _ = std::fs::remove_dir_all(&root);
+
+    let annotations = stubs.join("org/jetbrains/annotations");
+    std::fs::create_dir_all(&annotations).unwrap();
+    for name in ANNOTATIONS {
+        let source = format!(
+            "package org.jetbrains.annotations;\npublic @interface {} {{}}\n",
+            name
+        );
+        std::fs::write(annotations.join(format!("{}.java", name)), source).unwrap();
+    }
+
+    let mut files = Vec::new();
+    sources(&stubs, &mut files);
+    let source_root =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../native4j-capstone/src/main/java");
+    sources(&source_root, &mut files);
+
+    for patch in patches {
+        let original = source_root.join(patch.file);
+        let source = std::fs::read_to_string(&original).unwrap();
+        assert!(
+            source.contains(patch.from),
+            "{} does not contain the patched text",
+            patch.file
+        );
+
+        let file = patched.join(patch.file);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(&file, source.replace(patch.from, patch.to)).unwrap();
+        files.retain(|path| *path != original);
+        files.push(file);
+    }
+
+    let status = Command::new(javac())
+        .arg("-nowarn")
+        .arg("-d")
+        .arg(&classes)
+        .args(&files)
+        .status()
+        .expect("javac should be installed");
+    assert!(status.success(), "failed to compile the Java sources");
+    classes
+}
+
+/// Build a native method from a JNI entry point of the library.
+macro_rules! native {
+    ($name:literal, $sig:literal, $function:ident) => {
+        NativeMethod {
+            name: $name.into(),
+            sig: $sig.into(),
+            fn_ptr: capstone_java::$function as *mut c_void,
+        }
+    };
+}
+
+/// Register the native methods of `Capstone`. `NativeUtil` is marked as loaded first, so
+/// the static initializer of `Capstone` does not look for a packaged library.
+fn register_natives(env: &mut JNIEnv) {
+    env.set_static_field(
+        "org/native4j/capstone/NativeUtil",
+        ("org/native4j/capstone/NativeUtil", "loaded", "Z"),
+        JValue::Bool(1),
+    )
+    .unwrap();
+
+    let methods = [
+        native!(
+            "init",
+            "(Lorg/native4j/capstone/CapstoneMode;)Ljava/lang/String;",
+            Java_org_native4j_capstone_Capstone_init
+        ),
+        native!(
+            "shutdown",
+            "()Ljava/lang/String;",
+            Java_org_native4j_capstone_Capstone_shutdown
+        ),
+        native!(
+            "disassemble",
+            "(Lorg/native4j/capstone/insn/CapstoneResult;[BIJ)Ljava/lang/String;",
+            Java_org_native4j_capstone_Capstone_disassemble
+        ),
+        native!(
+            "renderListing",
+            "(Lorg/native4j/capstone/listing/Listing;[BJLorg/native4j/capstone/listing/ListingOptions;Ljava/lang/String;)Ljava/lang/String;",
+            Java_org_native4j_capstone_Capstone_renderListing
+        ),
+        native!(
+            "getRegName",
+            "(I)Ljava/lang/String;",
+            Java_org_native4j_capstone_Capstone_getRegName
+        ),
+    ];
+    env.register_native_methods("org/native4j/capstone/Capstone", &methods)
+        .unwrap();
+}
+
+/// Get the shared JVM, creating it on first use.
+pub fn jvm() -> &'static JavaVM {
+    start_jvm(|| match std::env::var_os("CSJ_CLASSPATH") {
+        Some(classpath) => PathBuf::from(classpath),
+        None => compile_classes("jvm", &[]),
+    })
+}
+
+/// Get the shared JVM, creating it on first use with the Java library compiled from
+/// patched sources. A test binary must use either this or `jvm`.
+pub fn patched_jvm(patches: &[Patch]) -> &'static JavaVM {
+    start_jvm(|| compile_classes("patched-jvm", patches))
+}
+
+/// Get the shared JVM, creating it on first use with the classes in `classpath`.
+fn start_jvm(classpath: impl FnOnce() -> PathBuf) -> &'static JavaVM {
+    JVM.get_or_init(|| {
+        let classpath = classpath();
+        let args = InitArgsBuilder::new()
+            .version(JNIVersion::V8)
+            .option(format!("-Djava.class.path={}", classpath.display()))
+            .option("-Xcheck:jni")
+            .build()
+            .unwrap();
+        let vm = JavaVM::new(args).unwrap();
+        register_natives(&mut vm.attach_current_thread().unwrap());
+        vm
+    })
+}
+
+/// Attach the current thread to the shared JVM.
+pub fn attach() -> AttachGuard<'static> {
+    jvm().attach_current_thread().unwrap()
+}
+
+/// Create a `Capstone` instance for the `CapstoneMode` constant with the given name.
+pub fn new_capstone<'local>(env: &mut JNIEnv<'local>, mode: &str) -> JObject<'local> {
+    let mode = env
+        .get_static_field(
+            "org/native4j/capstone/CapstoneMode",
+            mode,
+            "Lorg/native4j/capstone/CapstoneMode;",
+        )
+        .unwrap()
+        .l()
+        .unwrap();
+    env.new_object(
+        "org/native4j/capstone/Capstone",
+        "(Lorg/native4j/capstone/CapstoneMode;)V",
+        &[JValue::Object(&mode)],
+    )
+    .unwrap()
+}
+
+/// Get an object field.
+pub fn object<'local>(
+    env: &mut JNIEnv<'local>,
+    obj: &JObject,
+    field: &str,
+    sig: &str,
+) -> JObject<'local> {
+    env.get_field(obj, field, sig).unwrap().l().unwrap()
+}
+
+/// Get a string field, `None` if it is null.
+pub fn string(env: &mut JNIEnv, obj: &JObject, field: &str) -> Option<String> {
+    let value = object(env, obj, field, "Ljava/lang/String;");
+    if value.is_null() {
+        return None;
+    }
+    Some(env.get_string(&value.into()).unwrap().into())
+}
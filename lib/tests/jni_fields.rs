@@ -0,0 +1,221 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use jni::objects::{JObject, JObjectArray, JValue};
+use jni::JNIEnv;
+
+mod common;
+
+/// `stp x29, x30, [sp, #-0x10]!`, `bl #0xedc`, `ret` at 0x1000.
+const CODE: &[u8] = &[
+    0xfd, 0x7b, 0xbf, 0xa9, 0xb6, 0xff, 0xff, 0x97, 0xc0, 0x03, 0x5f, 0xd6,
+];
+
+/// Disassemble `CODE` and return the populated instruction objects.
+fn disassemble<'local>(env: &mut JNIEnv<'local>, capstone: &JObject) -> Vec<JObject<'local>> {
+    let result = env
+        .new_object("org/native4j/capstone/insn/CapstoneResult", "()V", &[])
+        .unwrap();
+    let bytes = env.byte_array_from_slice(CODE).unwrap();
+    env.call_method(
+        capstone,
+        "disassembleAll",
+        "(Lorg/native4j/capstone/insn/CapstoneResult;[BJ)V",
+        &[
+            JValue::Object(&result),
+            JValue::Object(&bytes),
+            JValue::Long(0x1000),
+        ],
+    )
+    .unwrap();
+
+    let count = env
+        .get_field(&result, "instructionCount", "I")
+        .unwrap()
+        .i()
+        .unwrap();
+    let array: JObjectArray = common::object(
+        env,
+        &result,
+        "instructions",
+        "[Lorg/native4j/capstone/insn/CsInsn;",
+    )
+    .into();
+    (0..count)
+        .map(|i| env.get_object_array_element(&array, i).unwrap())
+        .collect()
+}
+
+fn close(env: &mut JNIEnv, capstone: &JObject) {
+    env.call_method(capstone, "close", "()V", &[]).unwrap();
+}
+
+#[test]
+fn populates_arm64_instructions() {
+    let mut env = common::attach();
+    let capstone = common::new_capstone(&mut env, "ARM64");
+    let insns = disassemble(&mut env, &capstone);
+    assert_eq!(insns.len(), 3);
+
+    let stp = &insns[0];
+    assert!(env
+        .is_instance_of(stp, "org/native4j/capstone/insn/arm/CsInsnArm64")
+        .unwrap());
+    assert_eq!(
+        common::string(&mut env, stp, "mnemonic").as_deref(),
+        Some("stp")
+    );
+    assert_eq!(
+        common::string(&mut env, stp, "operand").as_deref(),
+        Some("x29, x30, [sp, #-0x10]!")
+    );
+    assert_eq!(
+        env.get_field(stp, "address", "J").unwrap().j().unwrap(),
+        0x1000
+    );
+    assert_eq!(env.get_field(stp, "size", "I").unwrap().i().unwrap(), 4);
+    assert!(env
+        .get_field(stp, "writebackRequired", "Z")
+        .unwrap()
+        .z()
+        .unwrap());
+
+    // The third operand is the memory operand '[sp, #-0x10]!'
+    let operands: JObjectArray = common::object(
+        &mut env,
+        stp,
+        "operands",
+        "[Lorg/native4j/capstone/insn/arm/CsOperandArm64;",
+    )
+    .into();
+    assert_eq!(env.get_array_length(&operands).unwrap(), 3);
+    let memory = env.get_object_array_element(&operands, 2).unwrap();
+    assert_eq!(
+        env.get_field(&memory, "operandType", "B")
+            .unwrap()
+            .b()
+            .unwrap(),
+        3
+    );
+    let value = common::object(&mut env, &memory, "rawOperandValue", "Ljava/lang/Object;");
+    assert!(env
+        .is_instance_of(&value, "org/native4j/capstone/insn/arm/CsMemOperandArm64")
+        .unwrap());
+
+    let bl = &insns[1];
+    assert!(env.get_field(bl, "isCall", "Z").unwrap().z().unwrap());
+    let target = common::object(&mut env, bl, "branchTarget", "Ljava/lang/Long;");
+    let target = env.call_method(&target, "longValue", "()J", &[]).unwrap();
+    assert_eq!(target.j().unwrap(), 0xedc);
+    assert!(common::string(&mut env, bl, "symbolizedOperand").is_none());
+
+    let ret = &insns[2];
+    assert!(env.get_field(ret, "isReturn", "Z").unwrap().z().unwrap());
+    assert!(common::object(&mut env, ret, "branchTarget", "Ljava/lang/Long;").is_null());
+
+    close(&mut env, &capstone);
+}
+
+#[test]
+fn resolves_register_names() {
+    let mut env = common::attach();
+    let capstone = common::new_capstone(&mut env, "ARM64");
+    let insns = disassemble(&mut env, &capstone);
+
+    // Empty arrays are written as null
+    let regs_write = common::object(&mut env, &insns[0], "regsWrite", "[S");
+    assert!(regs_write.is_null());
+
+    let operands: JObjectArray = common::object(
+        &mut env,
+        &insns[0],
+        "operands",
+        "[Lorg/native4j/capstone/insn/arm/CsOperandArm64;",
+    )
+    .into();
+    let first = env.get_object_array_element(&operands, 0).unwrap();
+    let reg = common::object(&mut env, &first, "rawOperandValue", "Ljava/lang/Object;");
+    let reg = env
+        .call_method(&reg, "intValue", "()I", &[])
+        .unwrap()
+        .i()
+        .unwrap();
+
+    let name = env
+        .call_method(
+            &capstone,
+            "getRegName",
+            "(I)Ljava/lang/String;",
+            &[JValue::Int(reg)],
+        )
+        .unwrap()
+        .l()
+        .unwrap();
+    let name: String = env.get_string(&name.into()).unwrap().into();
+    assert_eq!(name, "fp");
+
+    close(&mut env, &capstone);
+}
+
+#[test]
+fn formats_listings() {
+    let mut env = common::attach();
+    let capstone = common::new_capstone(&mut env, "ARM64");
+
+    let options = env
+        .new_object("org/native4j/capstone/listing/ListingOptions", "()V", &[])
+        .unwrap();
+    env.set_field(&options, "addressWidth", "I", JValue::Int(4))
+        .unwrap();
+    let bytes = env.byte_array_from_slice(CODE).unwrap();
+    let text = env
+        .call_method(
+            &capstone,
+            "formatListing",
+            "([BJLorg/native4j/capstone/listing/ListingOptions;)Ljava/lang/String;",
+            &[
+                JValue::Object(&bytes),
+                JValue::Long(0x1000),
+                JValue::Object(&options),
+            ],
+        )
+        .unwrap()
+        .l()
+        .unwrap();
+    let text: String = env.get_string(&text.into()).unwrap().into();
+    assert!(text.starts_with("1000:\ta9bf7bfd \tstp\tx29, x30, [sp, #-0x10]!\n"));
+    assert!(text.ends_with("1008:\td65f03c0 \tret\n"));
+
+    close(&mut env, &capstone);
+}
+
+#[test]
+fn reports_errors_as_exceptions() {
+    let mut env = common::attach();
+    let result = env.new_object(
+        "org/native4j/capstone/Capstone",
+        "(Lorg/native4j/capstone/CapstoneMode;)V",
+        &[JValue::Object(&JObject::null())],
+    );
+    assert!(result.is_err());
+
+    let exception = env.exception_occurred().unwrap();
+    env.exception_clear().unwrap();
+    assert!(env
+        .is_instance_of(
+            &exception,
+            "org/native4j/capstone/exception/CapstoneException"
+        )
+        .unwrap());
+}
+
+#[test]
+fn validates_class_layout_of_every_mode() {
+    let mut env = common::attach();
+    for mode in ["ARM32", "ARM64", "THUMB", "X86_32", "X86_64"] {
+        let capstone = common::new_capstone(&mut env, mode);
+        close(&mut env, &capstone);
+    }
+}
@@ -0,0 +1,84 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE file.
+ */
+use jni::objects::{JString, JValue};
+use jni::JNIEnv;
+
+use common::Patch;
+
+mod common;
+
+/// Java sources changed so they no longer match the layout the native library expects.
+const PATCHES: &[Patch] = &[
+    Patch {
+        file: "org/native4j/capstone/insn/arm/CsInsnArm.java",
+        from: "public int encoding;",
+        to: "public long encoding;",
+    },
+    Patch {
+        file: "org/native4j/capstone/analysis/FunctionRange.java",
+        from: "public boolean returns;",
+        to: "public int returns;",
+    },
+];
+
+/// Construct a `Capstone` in the given mode and return the message of the exception
+/// thrown by its constructor, without the source location prefix.
+fn init_error(env: &mut JNIEnv, mode: &str) -> String {
+    let mode = env
+        .get_static_field(
+            "org/native4j/capstone/CapstoneMode",
+            mode,
+            "Lorg/native4j/capstone/CapstoneMode;",
+        )
+        .unwrap()
+        .l()
+        .unwrap();
+    let result = env.new_object(
+        "org/native4j/capstone/Capstone",
+        "(Lorg/native4j/capstone/CapstoneMode;)V",
+        &[JValue::Object(&mode)],
+    );
+    assert!(result.is_err());
+
+    let exception = env.exception_occurred().unwrap();
+    env.exception_clear().unwrap();
+    assert!(env
+        .is_instance_of(
+            &exception,
+            "org/native4j/capstone/exception/CapstoneException"
+        )
+        .unwrap());
+
+    let message: JString = env
+        .call_method(&exception, "getMessage", "()Ljava/lang/String;", &[])
+        .unwrap()
+        .l()
+        .unwrap()
+        .into();
+    let message: String = env.get_string(&message).unwrap().into();
+    message.split_once(": ").unwrap().1.to_string()
+}
+
+#[test]
+fn rejects_mismatched_classes() {
+    let mut env = common::patched_jvm(PATCHES)
+        .attach_current_thread()
+        .unwrap();
+
+    let expected = "Java classes do not match the native library:\n  \
+                    org.native4j.capstone.insn.arm.CsInsnArm64.encoding: no field of type I\n  \
+                    org.native4j.capstone.analysis.FunctionRange.returns: no field of type Z";
+    assert_eq!(init_error(&mut env, "ARM64"), expected);
+    // The cached result is reported again
+    assert_eq!(init_error(&mut env, "ARM64"), expected);
+
+    // Other modes only check their own instruction classes
+    assert_eq!(
+        init_error(&mut env, "X86_64"),
+        "Java classes do not match the native library:\n  \
+         org.native4j.capstone.analysis.FunctionRange.returns: no field of type Z"
+    );
+}
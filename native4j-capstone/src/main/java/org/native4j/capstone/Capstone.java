@@ -6,8 +6,19 @@
  */
 package org.native4j.capstone;
 
+import java.nio.file.Path;
+
+import org.native4j.capstone.analysis.ControlFlowGraph;
+import org.native4j.capstone.analysis.FunctionTable;
+import org.native4j.capstone.analysis.XrefIndex;
 import org.native4j.capstone.exception.CapstoneException;
 import org.native4j.capstone.insn.CapstoneResult;
+import org.native4j.capstone.insn.NameTable;
+import org.native4j.capstone.insn.RegisterTable;
+import org.native4j.capstone.json.JsonExport;
+import org.native4j.capstone.listing.Listing;
+import org.native4j.capstone.listing.ListingOptions;
+import org.native4j.capstone.symbol.SymbolResolver;
 
 /**
  * Wraps a native Capstone instance. This class is thread-safe. <br>
@@ -22,6 +33,8 @@ public class Capstone implements AutoCloseable {
     @SuppressWarnings("ALL")
     private long _CsHandle = 0;
     private final CapstoneMode mode;
+    private volatile NameTable names;
+    private volatile RegisterTable registers;
 
     /**
      * Initialize a new Capstone instance.
@@ -82,6 +95,216 @@ public class Capstone implements AutoCloseable {
         check(disassemble(result, bytes, count, address));
     }
 
+    /**
+     * Disassemble all instructions in the given byte array and render their
+     * operands with symbol names, e.g. {@code bl memcpy} or
+     * {@code adrp x0, g_table@PAGE}. The rendered operands are stored in
+     * {@code symbolizedOperand}, the raw operands are unchanged. Only supported
+     * for ARM modes.
+     *
+     * @param result
+     *                 The result object to populate
+     * @param bytes
+     *                 The bytes to disassemble
+     * @param address
+     *                 The address of the first instruction
+     * @param resolver
+     *                 The symbol resolver, a
+     *                 {@link org.native4j.capstone.symbol.SymbolTable} avoids a
+     *                 callback per address
+     */
+    public void disassembleSymbolized(CapstoneResult result, byte[] bytes, long address, SymbolResolver resolver) {
+        check(disassembleWithSymbols(result, bytes, address, resolver));
+    }
+
+    /**
+     * Disassemble the instructions reachable from the given entry points by
+     * following direct branches and calls. Unlike a linear sweep, data that is
+     * only reachable by falling through past a terminating instruction is never
+     * decoded. Instructions are ordered by address. Only supported for ARM modes.
+     *
+     * @param result
+     *                The result object to populate
+     * @param bytes
+     *                The bytes to disassemble
+     * @param address
+     *                The address of the first byte
+     * @param entries
+     *                The addresses to start disassembling at
+     */
+    public void disassembleRecursive(CapstoneResult result, byte[] bytes, long address, long... entries) {
+        check(disassembleRecursive(result, entries, bytes, address));
+    }
+
+    /**
+     * Recover the control-flow graph reachable from the given entry points by
+     * recursive-descent disassembly. Only supported for ARM modes.
+     *
+     * @param bytes
+     *                The bytes to disassemble
+     * @param address
+     *                The address of the first byte
+     * @param entries
+     *                The addresses to start disassembling at
+     *
+     * @return The control-flow graph
+     */
+    public ControlFlowGraph buildControlFlowGraph(byte[] bytes, long address, long... entries) {
+        ControlFlowGraph graph = new ControlFlowGraph();
+        check(buildCfg(graph, bytes, address, entries));
+        return graph;
+    }
+
+    /**
+     * Detect function boundaries. Functions start at the entry points, at the
+     * targets of direct calls and at prologues following a function boundary,
+     * and extend to the last instruction reachable from their start. Only
+     * supported for ARM modes.
+     *
+     * @param bytes
+     *                The bytes to analyze
+     * @param address
+     *                The address of the first byte
+     * @param entries
+     *                Known function entry points, may be empty
+     *
+     * @return The detected functions
+     */
+    public FunctionTable findFunctions(byte[] bytes, long address, long... entries) {
+        FunctionTable table = new FunctionTable();
+        check(detectFunctions(table, bytes, address, entries));
+        return table;
+    }
+
+    /**
+     * Build an index of the code and data references made by the instructions
+     * in the given bytes. If entry points are given, only the instructions
+     * reachable from them are considered, otherwise the whole buffer is
+     * disassembled. Only supported for ARM modes.
+     *
+     * @param bytes
+     *                The bytes to analyze
+     * @param address
+     *                The address of the first byte
+     * @param entries
+     *                The addresses to start disassembling at, may be empty
+     *
+     * @return The cross-reference index
+     */
+    public XrefIndex buildXrefIndex(byte[] bytes, long address, long... entries) {
+        XrefIndex index = new XrefIndex();
+        check(buildXrefs(index, bytes, address, entries));
+        return index;
+    }
+
+    /**
+     * Render an {@code objdump}-style listing of the given bytes, with the
+     * address, bytes and text of each instruction and a header for every symbol
+     * start. Bytes that do not decode are listed as undefined.
+     *
+     * @param bytes
+     *                The bytes to disassemble
+     * @param address
+     *                The address of the first byte
+     * @param options
+     *                The layout of the listing
+     *
+     * @return The listing text
+     */
+    public String formatListing(byte[] bytes, long address, ListingOptions options) {
+        Listing listing = new Listing();
+        check(renderListing(listing, bytes, address, options, null));
+        return listing.getText();
+    }
+
+    /**
+     * Render an {@code objdump}-style listing of the given bytes to a file. See
+     * {@link #formatListing(byte[], long, ListingOptions)}.
+     *
+     * @param path
+     *                The file to write, replaced if it exists
+     * @param bytes
+     *                The bytes to disassemble
+     * @param address
+     *                The address of the first byte
+     * @param options
+     *                The layout of the listing
+     */
+    public void writeListing(Path path, byte[] bytes, long address, ListingOptions options) {
+        check(renderListing(new Listing(), bytes, address, options, path.toString()));
+    }
+
+    /**
+     * Disassemble all instructions in the given bytes and export them as a JSON
+     * document of the form
+     * {@code {"schemaVersion": 1, "mode": "ARM64", "instructions": [...]}}.
+     * Instruction fields are named after the fields of the instruction classes,
+     * with registers and groups given by name. Architecture detail is only
+     * included for ARM modes.
+     *
+     * @param bytes
+     *                The bytes to disassemble
+     * @param address
+     *                The address of the first instruction
+     *
+     * @return The JSON text
+     *
+     * @see JsonExport#SCHEMA_VERSION
+     */
+    public String exportJson(byte[] bytes, long address) {
+        JsonExport export = new JsonExport();
+        check(exportJson(export, bytes, address, null));
+        return export.getText();
+    }
+
+    /**
+     * Export the instructions in the given bytes as a JSON document to a file.
+     * See {@link #exportJson(byte[], long)}.
+     *
+     * @param path
+     *                The file to write, replaced if it exists
+     * @param bytes
+     *                The bytes to disassemble
+     * @param address
+     *                The address of the first instruction
+     */
+    public void writeJson(Path path, byte[] bytes, long address) {
+        check(exportJson(new JsonExport(), bytes, address, path.toString()));
+    }
+
+    /**
+     * Get the names of all instructions, registers and groups of this mode. The
+     * table is loaded in a single native call on first use and cached.
+     *
+     * @return The name table
+     */
+    public NameTable getNameTable() {
+        NameTable table = names;
+        if (table == null) {
+            table = new NameTable();
+            check(loadNames(table));
+            names = table;
+        }
+        return table;
+    }
+
+    /**
+     * Get the size, class and aliasing of all registers of this mode, e.g. that
+     * {@code w0} is the low half of {@code x0}. The table is loaded in a single
+     * native call on first use and cached. Only supported for ARM modes.
+     *
+     * @return The register table
+     */
+    public RegisterTable getRegisterTable() {
+        RegisterTable table = registers;
+        if (table == null) {
+            table = new RegisterTable();
+            check(loadRegisters(table));
+            registers = table;
+        }
+        return table;
+    }
+
     /**
      * Initializes the native Capstone instance.
      *
@@ -104,6 +327,56 @@ public class Capstone implements AutoCloseable {
      */
     private native String disassemble(CapstoneResult result, byte[] bytes, int count, long address);
 
+    /**
+     * See {@link #disassembleSymbolized(CapstoneResult, byte[], long, SymbolResolver)}
+     */
+    private native String disassembleWithSymbols(CapstoneResult result, byte[] bytes, long address,
+            SymbolResolver resolver);
+
+    /**
+     * See {@link #formatListing(byte[], long, ListingOptions)}, writes to
+     * {@code path} instead of {@code listing} if it is not {@code null}
+     */
+    private native String renderListing(Listing listing, byte[] bytes, long address, ListingOptions options,
+            String path);
+
+    /**
+     * See {@link #exportJson(byte[], long)}, writes to {@code path} instead of
+     * {@code export} if it is not {@code null}
+     */
+    private native String exportJson(JsonExport export, byte[] bytes, long address, String path);
+
+    /**
+     * See {@link #disassembleRecursive(CapstoneResult, byte[], long, long...)},
+     * takes the entry points first so it does not clash with the varargs method
+     */
+    private native String disassembleRecursive(CapstoneResult result, long[] entries, byte[] bytes, long address);
+
+    /**
+     * See {@link #buildControlFlowGraph(byte[], long, long...)}
+     */
+    private native String buildCfg(ControlFlowGraph graph, byte[] bytes, long address, long[] entries);
+
+    /**
+     * See {@link #findFunctions(byte[], long, long...)}
+     */
+    private native String detectFunctions(FunctionTable table, byte[] bytes, long address, long[] entries);
+
+    /**
+     * See {@link #buildXrefIndex(byte[], long, long...)}
+     */
+    private native String buildXrefs(XrefIndex index, byte[] bytes, long address, long[] entries);
+
+    /**
+     * See {@link #getNameTable()}
+     */
+    private native String loadNames(NameTable table);
+
+    /**
+     * See {@link #getRegisterTable()}
+     */
+    private native String loadRegisters(RegisterTable table);
+
     /**
      * Get the name of an instruction.
      * 
@@ -111,6 +384,8 @@ public class Capstone implements AutoCloseable {
      *               The instruction id
      * 
      * @return The instruction name or {@code null} if the instruction id is invalid
+     *
+     * @see #getNameTable()
      */
     public native String getInsnName(int insnId);
 
@@ -121,6 +396,8 @@ public class Capstone implements AutoCloseable {
      *              The register id
      * 
      * @return The register name or {@code null} if the register id is invalid
+     *
+     * @see #getNameTable()
      */
     public native String getRegName(int regId);
 
@@ -131,6 +408,8 @@ public class Capstone implements AutoCloseable {
      *                The group id
      *
      * @return The group name or {@code null} if the group id is invalid
+     *
+     * @see #getNameTable()
      */
     public native String getGroupName(short groupId);
 
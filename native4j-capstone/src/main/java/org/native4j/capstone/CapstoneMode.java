@@ -11,5 +11,11 @@ package org.native4j.capstone;
  */
 public enum CapstoneMode {
     ARM32,
-    ARM64
+    ARM64,
+    X86_32,
+    X86_64,
+    /**
+     * 32-bit ARM in Thumb state.
+     */
+    THUMB
 }
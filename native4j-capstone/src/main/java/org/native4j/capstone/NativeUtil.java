@@ -15,14 +15,23 @@ import java.nio.file.Path;
 /**
  * Native utility methods.
  */
-class NativeUtil {
+public final class NativeUtil {
+    private static boolean loaded;
+
+    private NativeUtil() {
+    }
+
     /**
-     * Load the native Capstone bindings.
+     * Load the native Capstone bindings. Does nothing if they are already
+     * loaded.
      * 
      * @throws IOException
      *                     The bindings could not be loaded
      */
-    static void loadBindings() throws IOException {
+    public static synchronized void loadBindings() throws IOException {
+        if (loaded)
+            return;
+
         String arch = System.getProperty("os.arch");
         String os = System.getProperty("os.name").toLowerCase();
 
@@ -48,6 +57,7 @@ class NativeUtil {
         }
 
         System.load(tempDirectory.resolve(libraryName).toString());
+        loaded = true;
     }
 
     /**
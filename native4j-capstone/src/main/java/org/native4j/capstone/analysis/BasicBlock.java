@@ -0,0 +1,25 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.analysis;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * A straight-line sequence of instructions with a single entry and exit.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public class BasicBlock {
+    public long startAddress;
+    /**
+     * Address following the last instruction of the block.
+     */
+    public long endAddress;
+    public int instructionCount;
+    public FlowEdge[] successors;
+    public FlowEdge[] predecessors;
+}
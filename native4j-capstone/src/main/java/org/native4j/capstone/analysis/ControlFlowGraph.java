@@ -0,0 +1,80 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.analysis;
+
+import java.util.Arrays;
+import java.util.Collections;
+import java.util.List;
+
+import org.jetbrains.annotations.Nullable;
+import org.jetbrains.annotations.Unmodifiable;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * Control-flow graph recovered by recursive-descent disassembly.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public final class ControlFlowGraph {
+    /* Set directly via JNI. Do not modify. */
+    private BasicBlock[] blocks;
+    /* Set directly via JNI. Do not modify. */
+    private FlowEdge[] edges;
+
+    public ControlFlowGraph() {
+    }
+
+    /**
+     * Returns the basic blocks of the graph, ordered by address.
+     *
+     * @return An unmodifiable list of basic blocks
+     */
+    @Unmodifiable
+    public List<BasicBlock> getBlocks() {
+        return blocks == null ? Collections.emptyList() : Collections.unmodifiableList(Arrays.asList(blocks));
+    }
+
+    /**
+     * Returns every edge of the graph.
+     *
+     * @return An unmodifiable list of edges
+     */
+    @Unmodifiable
+    public List<FlowEdge> getEdges() {
+        return edges == null ? Collections.emptyList() : Collections.unmodifiableList(Arrays.asList(edges));
+    }
+
+    /**
+     * Find the basic block containing the given address.
+     *
+     * @param address
+     *                The address to look up
+     *
+     * @return The block or {@code null} if no block contains the address
+     */
+    @Nullable
+    public BasicBlock getBlock(long address) {
+        if (blocks == null)
+            return null;
+
+        int low = 0;
+        int high = blocks.length - 1;
+        while (low <= high) {
+            int mid = (low + high) >>> 1;
+            BasicBlock block = blocks[mid];
+            if (Long.compareUnsigned(address, block.startAddress) < 0) {
+                high = mid - 1;
+            } else if (Long.compareUnsigned(address, block.endAddress) >= 0) {
+                low = mid + 1;
+            } else {
+                return block;
+            }
+        }
+        return null;
+    }
+}
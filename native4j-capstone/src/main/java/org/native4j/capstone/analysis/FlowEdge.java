@@ -0,0 +1,32 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.analysis;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * A control-flow edge between two basic blocks.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public class FlowEdge {
+    public static final int FALLTHROUGH = 0;
+    public static final int TAKEN = 1;
+    public static final int CALL = 2;
+    public static final int INDIRECT = 3;
+
+    public byte kind;
+    public BasicBlock source;
+    public BasicBlock target;
+
+    /* Invoked by JNI */
+    public FlowEdge(byte kind, BasicBlock source, BasicBlock target) {
+        this.kind = kind;
+        this.source = source;
+        this.target = target;
+    }
+}
@@ -0,0 +1,35 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.analysis;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * The address range of a function recovered from a code buffer.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public class FunctionRange {
+    public long startAddress;
+    /**
+     * Address following the last instruction of the function.
+     */
+    public long endAddress;
+    public int instructionCount;
+    /**
+     * The function is an entry point or the target of a direct call.
+     */
+    public boolean isCallTarget;
+    /**
+     * The function starts with a prologue.
+     */
+    public boolean hasPrologue;
+    /**
+     * A return instruction is reachable from the start of the function.
+     */
+    public boolean returns;
+}
@@ -0,0 +1,68 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.analysis;
+
+import java.util.Arrays;
+import java.util.Collections;
+import java.util.List;
+
+import org.jetbrains.annotations.Nullable;
+import org.jetbrains.annotations.Unmodifiable;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * Functions detected in a code buffer.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public final class FunctionTable {
+    /* Set directly via JNI. Do not modify. */
+    private FunctionRange[] functions;
+
+    public FunctionTable() {
+    }
+
+    /**
+     * Returns the detected functions, ordered by address.
+     *
+     * @return An unmodifiable list of functions
+     */
+    @Unmodifiable
+    public List<FunctionRange> getFunctions() {
+        return functions == null ? Collections.emptyList() : Collections.unmodifiableList(Arrays.asList(functions));
+    }
+
+    /**
+     * Find the function containing the given address.
+     *
+     * @param address
+     *                The address to look up
+     *
+     * @return The function or {@code null} if no function contains the address
+     */
+    @Nullable
+    public FunctionRange getFunction(long address) {
+        if (functions == null)
+            return null;
+
+        int low = 0;
+        int high = functions.length - 1;
+        while (low <= high) {
+            int mid = (low + high) >>> 1;
+            FunctionRange function = functions[mid];
+            if (Long.compareUnsigned(address, function.startAddress) < 0) {
+                high = mid - 1;
+            } else if (Long.compareUnsigned(address, function.endAddress) >= 0) {
+                low = mid + 1;
+            } else {
+                return function;
+            }
+        }
+        return null;
+    }
+}
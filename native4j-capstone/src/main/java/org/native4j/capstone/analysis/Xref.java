@@ -0,0 +1,55 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.analysis;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * A reference from an instruction to an address.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public class Xref {
+    /**
+     * Target of a direct call.
+     */
+    public static final int CALL = 0;
+    /**
+     * Target of a direct branch.
+     */
+    public static final int JUMP = 1;
+    /**
+     * Known target of an indirect branch, e.g. a jump table case.
+     */
+    public static final int INDIRECT = 2;
+    /**
+     * Address read by a PC-relative load.
+     */
+    public static final int READ = 3;
+    /**
+     * Address materialized in a register, e.g. by {@code adr} or an
+     * {@code adrp} pair.
+     */
+    public static final int ADDRESS = 4;
+
+    /**
+     * Address of the referencing instruction.
+     */
+    public long source;
+    /**
+     * The referenced address.
+     */
+    public long target;
+    public byte kind;
+
+    /* Invoked by JNI */
+    public Xref(long source, long target, byte kind) {
+        this.source = source;
+        this.target = target;
+        this.kind = kind;
+    }
+}
@@ -0,0 +1,94 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.analysis;
+
+import java.util.Arrays;
+import java.util.Collections;
+import java.util.List;
+import java.util.function.ToLongFunction;
+
+import org.jetbrains.annotations.Unmodifiable;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * Cross-references of a disassembled buffer, queryable by target address or
+ * by source instruction.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public final class XrefIndex {
+    /* Set directly via JNI. Do not modify. */
+    private Xref[] byTarget;
+    /* Set directly via JNI. Do not modify. */
+    private Xref[] bySource;
+
+    public XrefIndex() {
+    }
+
+    /**
+     * Returns every reference, ordered by target address.
+     *
+     * @return An unmodifiable list of references
+     */
+    @Unmodifiable
+    public List<Xref> getReferences() {
+        return byTarget == null ? Collections.emptyList() : Collections.unmodifiableList(Arrays.asList(byTarget));
+    }
+
+    /**
+     * Find the references to the given address.
+     *
+     * @param target
+     *               The referenced address
+     *
+     * @return An unmodifiable list of references, ordered by source address
+     */
+    @Unmodifiable
+    public List<Xref> getReferencesTo(long target) {
+        return range(byTarget, target, xref -> xref.target);
+    }
+
+    /**
+     * Find the references made by the instruction at the given address.
+     *
+     * @param source
+     *               The address of the instruction
+     *
+     * @return An unmodifiable list of references, ordered by target address
+     */
+    @Unmodifiable
+    public List<Xref> getReferencesFrom(long source) {
+        return range(bySource, source, xref -> xref.source);
+    }
+
+    private static List<Xref> range(Xref[] xrefs, long key, ToLongFunction<Xref> keyOf) {
+        if (xrefs == null)
+            return Collections.emptyList();
+
+        int from = lowerBound(xrefs, key, keyOf);
+        int to = from;
+        while (to < xrefs.length && keyOf.applyAsLong(xrefs[to]) == key) {
+            to++;
+        }
+        return Collections.unmodifiableList(Arrays.asList(xrefs).subList(from, to));
+    }
+
+    private static int lowerBound(Xref[] xrefs, long key, ToLongFunction<Xref> keyOf) {
+        int low = 0;
+        int high = xrefs.length;
+        while (low < high) {
+            int mid = (low + high) >>> 1;
+            if (Long.compareUnsigned(keyOf.applyAsLong(xrefs[mid]), key) < 0) {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        return low;
+    }
+}
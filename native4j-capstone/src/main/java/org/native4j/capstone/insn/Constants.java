@@ -19,4 +19,11 @@ public class Constants {
     public static final int CS_GRP_IRET = 5;
     public static final int CS_GRP_PRIVILEGE = 6;
     public static final int CS_GRP_BRANCH_RELATIVE = 7;
+    /* Generic operand types */
+    public static final int GENERIC_OP_INVALID = 0;
+    public static final int GENERIC_OP_REG = 1;
+    public static final int GENERIC_OP_IMM = 2;
+    public static final int GENERIC_OP_MEM = 3;
+    public static final int GENERIC_OP_FP = 4;
+    public static final int GENERIC_OP_OTHER = 5;
 }
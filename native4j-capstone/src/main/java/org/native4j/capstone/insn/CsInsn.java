@@ -6,8 +6,16 @@
  */
 package org.native4j.capstone.insn;
 
+import org.native4j.capstone.annotation.JNIClass;
+
 /**
  * Base class for all Capstone instructions.
  */
+@JNIClass
+@SuppressWarnings("unused")
 public class CsInsn {
+    /**
+     * The encoded bytes of the instruction.
+     */
+    public byte[] bytes;
 }
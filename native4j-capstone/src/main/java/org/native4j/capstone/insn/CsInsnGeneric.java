@@ -0,0 +1,27 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.insn;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * Architecture-neutral instruction, used by modes that do not have a
+ * specialized instruction class.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public class CsInsnGeneric extends CsInsn {
+    public String mnemonic;
+    public String operand;
+    public int instructionId;
+    public int size;
+    public long address;
+    public short[] regsRead;
+    public short[] regsWrite;
+    public short[] groups;
+    public CsOperandGeneric[] operands;
+}
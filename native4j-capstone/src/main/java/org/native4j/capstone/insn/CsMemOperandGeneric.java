@@ -0,0 +1,29 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.insn;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * Architecture-neutral memory operand.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public class CsMemOperandGeneric {
+    public int base;
+    public int index;
+    public int scale;
+    public long displacement;
+
+    /* Invoked by JNI */
+    public CsMemOperandGeneric(int base, int index, int scale, long displacement) {
+        this.base = base;
+        this.index = index;
+        this.scale = scale;
+        this.displacement = displacement;
+    }
+}
@@ -0,0 +1,57 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.insn;
+
+import static org.native4j.capstone.insn.Constants.*;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * Architecture-neutral operand data.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public class CsOperandGeneric {
+    public byte operandType;
+    public Object rawOperandValue;
+
+    public int getReg() {
+        assertType(GENERIC_OP_REG);
+        return (int) rawOperandValue;
+    }
+
+    public long getImm() {
+        assertType(GENERIC_OP_IMM);
+        return (long) rawOperandValue;
+    }
+
+    public CsMemOperandGeneric getMem() {
+        assertType(GENERIC_OP_MEM);
+        return (CsMemOperandGeneric) rawOperandValue;
+    }
+
+    public double getFp() {
+        assertType(GENERIC_OP_FP);
+        return (double) rawOperandValue;
+    }
+
+    /**
+     * Get the raw value of an operand that has no generic representation.
+     *
+     * @return The raw value or {@code null} if it is not known
+     */
+    public Long getOther() {
+        assertType(GENERIC_OP_OTHER);
+        return (Long) rawOperandValue;
+    }
+
+    private void assertType(int type) {
+        if (operandType != type) {
+            throw new IllegalStateException("Invalid operand type");
+        }
+    }
+}
@@ -0,0 +1,181 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.insn;
+
+import java.util.Arrays;
+import java.util.Collections;
+import java.util.HashMap;
+import java.util.List;
+import java.util.Locale;
+import java.util.Map;
+
+import org.jetbrains.annotations.Nullable;
+import org.jetbrains.annotations.Unmodifiable;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * The names of all instructions, registers and groups of a mode, indexed by
+ * id. Lookups do not cross JNI, so this is much faster than the name methods
+ * of {@link org.native4j.capstone.Capstone} when many names are needed. This
+ * class is thread-safe.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public final class NameTable {
+    /* Set directly via JNI. Do not modify. */
+    private String[] instructions;
+    private String[] registers;
+    private String[] groups;
+
+    private volatile Map<String, Integer> instructionIds;
+    private volatile Map<String, Integer> registerIds;
+    private volatile Map<String, Integer> groupIds;
+
+    public NameTable() {
+    }
+
+    /**
+     * Get the name of an instruction.
+     *
+     * @param insnId
+     *               The instruction id
+     *
+     * @return The instruction name or {@code null} if the instruction id is invalid
+     */
+    @Nullable
+    public String getInsnName(int insnId) {
+        return lookup(instructions, insnId);
+    }
+
+    /**
+     * Get the name of a register.
+     *
+     * @param regId
+     *              The register id
+     *
+     * @return The register name or {@code null} if the register id is invalid
+     */
+    @Nullable
+    public String getRegName(int regId) {
+        return lookup(registers, regId);
+    }
+
+    /**
+     * Get the name of an instruction group.
+     *
+     * @param groupId
+     *                The group id
+     *
+     * @return The group name or {@code null} if the group id is invalid
+     */
+    @Nullable
+    public String getGroupName(int groupId) {
+        return lookup(groups, groupId);
+    }
+
+    /**
+     * Get the id of an instruction by name, ignoring case.
+     *
+     * @param name
+     *             The instruction name, e.g. {@code "add"}
+     *
+     * @return The instruction id or {@code 0}, the invalid id, if there is no
+     *         such instruction
+     */
+    public int getInsnId(String name) {
+        Map<String, Integer> ids = instructionIds;
+        if (ids == null)
+            instructionIds = ids = index(instructions);
+        return ids.getOrDefault(name.toLowerCase(Locale.ROOT), 0);
+    }
+
+    /**
+     * Get the id of a register by name, ignoring case.
+     *
+     * @param name
+     *             The register name, e.g. {@code "x0"}
+     *
+     * @return The register id or {@code 0}, the invalid id, if there is no such
+     *         register
+     */
+    public int getRegId(String name) {
+        Map<String, Integer> ids = registerIds;
+        if (ids == null)
+            registerIds = ids = index(registers);
+        return ids.getOrDefault(name.toLowerCase(Locale.ROOT), 0);
+    }
+
+    /**
+     * Get the id of an instruction group by name, ignoring case.
+     *
+     * @param name
+     *             The group name, e.g. {@code "jump"}
+     *
+     * @return The group id or {@code 0}, the invalid id, if there is no such
+     *         group
+     */
+    public int getGroupId(String name) {
+        Map<String, Integer> ids = groupIds;
+        if (ids == null)
+            groupIds = ids = index(groups);
+        return ids.getOrDefault(name.toLowerCase(Locale.ROOT), 0);
+    }
+
+    /**
+     * Returns the instruction names, indexed by id. Invalid ids are
+     * {@code null}.
+     *
+     * @return An unmodifiable list of names
+     */
+    @Unmodifiable
+    public List<String> getInsnNames() {
+        return view(instructions);
+    }
+
+    /**
+     * Returns the register names, indexed by id. Invalid ids are {@code null}.
+     *
+     * @return An unmodifiable list of names
+     */
+    @Unmodifiable
+    public List<String> getRegNames() {
+        return view(registers);
+    }
+
+    /**
+     * Returns the group names, indexed by id. Invalid ids are {@code null}.
+     *
+     * @return An unmodifiable list of names
+     */
+    @Unmodifiable
+    public List<String> getGroupNames() {
+        return view(groups);
+    }
+
+    @Nullable
+    private static String lookup(String[] names, int id) {
+        if (names == null || id < 0 || id >= names.length)
+            return null;
+        return names[id];
+    }
+
+    private static List<String> view(String[] names) {
+        return names == null ? Collections.emptyList() : Collections.unmodifiableList(Arrays.asList(names));
+    }
+
+    private static Map<String, Integer> index(String[] names) {
+        Map<String, Integer> ids = new HashMap<>();
+        if (names != null) {
+            for (int id = 0; id < names.length; id++) {
+                if (names[id] != null)
+                    ids.putIfAbsent(names[id].toLowerCase(Locale.ROOT), id);
+            }
+        }
+        return Collections.unmodifiableMap(ids);
+    }
+}
@@ -0,0 +1,61 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.insn;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * Size, class and aliasing of a register.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public class RegisterInfo {
+    public static final int CLASS_GENERAL = 1;
+    public static final int CLASS_STACK_POINTER = 2;
+    public static final int CLASS_PROGRAM_COUNTER = 3;
+    /**
+     * A register that always reads as zero, like {@code xzr}.
+     */
+    public static final int CLASS_ZERO = 4;
+    /**
+     * A status register holding condition flags.
+     */
+    public static final int CLASS_FLAGS = 5;
+    /**
+     * A system or floating-point control register.
+     */
+    public static final int CLASS_SYSTEM = 6;
+    /**
+     * A floating-point or Advanced SIMD register.
+     */
+    public static final int CLASS_SIMD = 7;
+    public static final int CLASS_SCALABLE_VECTOR = 8;
+    public static final int CLASS_PREDICATE = 9;
+
+    public int id;
+    /**
+     * Size in bits. SVE registers are {@code 0} since their size depends on the
+     * CPU.
+     */
+    public int size;
+    public byte registerClass;
+    /**
+     * The smallest other register containing this one, e.g. {@code x0} for
+     * {@code w0}, or {@code 0} if there is none.
+     */
+    public int parent;
+    /**
+     * The widest register containing this one, or this register itself.
+     */
+    public int fullRegister;
+    /**
+     * Every register contained in this one, in id order.
+     */
+    public int[] subRegisters;
+    public boolean isSimd;
+    public boolean isFlags;
+}
@@ -0,0 +1,70 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.insn;
+
+import org.jetbrains.annotations.Nullable;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * The metadata of all registers of a mode, indexed by id. Lookups do not
+ * cross JNI. This class is thread-safe.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public final class RegisterTable {
+    /* Set directly via JNI. Do not modify. */
+    private RegisterInfo[] registers;
+
+    public RegisterTable() {
+    }
+
+    /**
+     * Get the metadata of a register.
+     *
+     * @param regId
+     *              The register id
+     *
+     * @return The register metadata or {@code null} if the register id is
+     *         invalid
+     */
+    @Nullable
+    public RegisterInfo get(int regId) {
+        if (registers == null || regId < 0 || regId >= registers.length)
+            return null;
+        return registers[regId];
+    }
+
+    /**
+     * Get the widest register containing a register, e.g. {@code x0} for
+     * {@code w0}.
+     *
+     * @param regId
+     *              The register id
+     *
+     * @return The id of the full register, or {@code regId} if it is invalid
+     */
+    public int getFullRegister(int regId) {
+        RegisterInfo info = get(regId);
+        return info == null ? regId : info.fullRegister;
+    }
+
+    /**
+     * Check whether writing one register may change the other, e.g.
+     * {@code s0} and {@code v0}.
+     *
+     * @param regA
+     *             The first register id
+     * @param regB
+     *             The second register id
+     *
+     * @return {@code true} if the registers share storage
+     */
+    public boolean overlaps(int regA, int regB) {
+        return regA == regB || getFullRegister(regA) == getFullRegister(regB);
+    }
+}
@@ -8,9 +8,20 @@ package org.native4j.capstone.insn.arm;
 
 /**
  * ARM capstone constants.
+ *
+ * Generated by gen_constants, do not edit.
  */
 @SuppressWarnings("ALL")
 public final class ArmConstants {
+    /* Condition flags */
+    public static final int ARM_FLAG_N = 8;
+    public static final int ARM_FLAG_Z = 4;
+    public static final int ARM_FLAG_C = 2;
+    public static final int ARM_FLAG_V = 1;
+    /* Memory addressing modes */
+    public static final int ARM_MEM_OFFSET = 0;
+    public static final int ARM_MEM_PRE_INDEXED = 1;
+    public static final int ARM_MEM_POST_INDEXED = 2;
     /* ARM32 operand types */
     public static final int ARM_OP_INVALID = 0;
     public static final int ARM_OP_REG = 1;
@@ -90,6 +101,126 @@ public final class ArmConstants {
     public static final int ARM_GRP_V6M = 158;
     public static final int ARM_GRP_VIRTUALIZATION = 159;
     public static final int ARM_GRP_ENDING = 160;
+    /* ARM32 registers */
+    public static final int ARM_REG_INVALID = 0;
+    public static final int ARM_REG_APSR = 1;
+    public static final int ARM_REG_APSR_NZCV = 2;
+    public static final int ARM_REG_CPSR = 3;
+    public static final int ARM_REG_FPEXC = 4;
+    public static final int ARM_REG_FPINST = 5;
+    public static final int ARM_REG_FPSCR = 6;
+    public static final int ARM_REG_FPSCR_NZCV = 7;
+    public static final int ARM_REG_FPSID = 8;
+    public static final int ARM_REG_ITSTATE = 9;
+    public static final int ARM_REG_LR = 10;
+    public static final int ARM_REG_R14 = 10;
+    public static final int ARM_REG_PC = 11;
+    public static final int ARM_REG_R15 = 11;
+    public static final int ARM_REG_SP = 12;
+    public static final int ARM_REG_R13 = 12;
+    public static final int ARM_REG_SPSR = 13;
+    public static final int ARM_REG_D0 = 14;
+    public static final int ARM_REG_D1 = 15;
+    public static final int ARM_REG_D2 = 16;
+    public static final int ARM_REG_D3 = 17;
+    public static final int ARM_REG_D4 = 18;
+    public static final int ARM_REG_D5 = 19;
+    public static final int ARM_REG_D6 = 20;
+    public static final int ARM_REG_D7 = 21;
+    public static final int ARM_REG_D8 = 22;
+    public static final int ARM_REG_D9 = 23;
+    public static final int ARM_REG_D10 = 24;
+    public static final int ARM_REG_D11 = 25;
+    public static final int ARM_REG_D12 = 26;
+    public static final int ARM_REG_D13 = 27;
+    public static final int ARM_REG_D14 = 28;
+    public static final int ARM_REG_D15 = 29;
+    public static final int ARM_REG_D16 = 30;
+    public static final int ARM_REG_D17 = 31;
+    public static final int ARM_REG_D18 = 32;
+    public static final int ARM_REG_D19 = 33;
+    public static final int ARM_REG_D20 = 34;
+    public static final int ARM_REG_D21 = 35;
+    public static final int ARM_REG_D22 = 36;
+    public static final int ARM_REG_D23 = 37;
+    public static final int ARM_REG_D24 = 38;
+    public static final int ARM_REG_D25 = 39;
+    public static final int ARM_REG_D26 = 40;
+    public static final int ARM_REG_D27 = 41;
+    public static final int ARM_REG_D28 = 42;
+    public static final int ARM_REG_D29 = 43;
+    public static final int ARM_REG_D30 = 44;
+    public static final int ARM_REG_D31 = 45;
+    public static final int ARM_REG_FPINST2 = 46;
+    public static final int ARM_REG_MVFR0 = 47;
+    public static final int ARM_REG_MVFR1 = 48;
+    public static final int ARM_REG_MVFR2 = 49;
+    public static final int ARM_REG_Q0 = 50;
+    public static final int ARM_REG_Q1 = 51;
+    public static final int ARM_REG_Q2 = 52;
+    public static final int ARM_REG_Q3 = 53;
+    public static final int ARM_REG_Q4 = 54;
+    public static final int ARM_REG_Q5 = 55;
+    public static final int ARM_REG_Q6 = 56;
+    public static final int ARM_REG_Q7 = 57;
+    public static final int ARM_REG_Q8 = 58;
+    public static final int ARM_REG_Q9 = 59;
+    public static final int ARM_REG_Q10 = 60;
+    public static final int ARM_REG_Q11 = 61;
+    public static final int ARM_REG_Q12 = 62;
+    public static final int ARM_REG_Q13 = 63;
+    public static final int ARM_REG_Q14 = 64;
+    public static final int ARM_REG_Q15 = 65;
+    public static final int ARM_REG_R0 = 66;
+    public static final int ARM_REG_R1 = 67;
+    public static final int ARM_REG_R2 = 68;
+    public static final int ARM_REG_R3 = 69;
+    public static final int ARM_REG_R4 = 70;
+    public static final int ARM_REG_R5 = 71;
+    public static final int ARM_REG_R6 = 72;
+    public static final int ARM_REG_R7 = 73;
+    public static final int ARM_REG_R8 = 74;
+    public static final int ARM_REG_SB = 75;
+    public static final int ARM_REG_R9 = 75;
+    public static final int ARM_REG_SL = 76;
+    public static final int ARM_REG_R10 = 76;
+    public static final int ARM_REG_FP = 77;
+    public static final int ARM_REG_R11 = 77;
+    public static final int ARM_REG_IP = 78;
+    public static final int ARM_REG_R12 = 78;
+    public static final int ARM_REG_S0 = 79;
+    public static final int ARM_REG_S1 = 80;
+    public static final int ARM_REG_S2 = 81;
+    public static final int ARM_REG_S3 = 82;
+    public static final int ARM_REG_S4 = 83;
+    public static final int ARM_REG_S5 = 84;
+    public static final int ARM_REG_S6 = 85;
+    public static final int ARM_REG_S7 = 86;
+    public static final int ARM_REG_S8 = 87;
+    public static final int ARM_REG_S9 = 88;
+    public static final int ARM_REG_S10 = 89;
+    public static final int ARM_REG_S11 = 90;
+    public static final int ARM_REG_S12 = 91;
+    public static final int ARM_REG_S13 = 92;
+    public static final int ARM_REG_S14 = 93;
+    public static final int ARM_REG_S15 = 94;
+    public static final int ARM_REG_S16 = 95;
+    public static final int ARM_REG_S17 = 96;
+    public static final int ARM_REG_S18 = 97;
+    public static final int ARM_REG_S19 = 98;
+    public static final int ARM_REG_S20 = 99;
+    public static final int ARM_REG_S21 = 100;
+    public static final int ARM_REG_S22 = 101;
+    public static final int ARM_REG_S23 = 102;
+    public static final int ARM_REG_S24 = 103;
+    public static final int ARM_REG_S25 = 104;
+    public static final int ARM_REG_S26 = 105;
+    public static final int ARM_REG_S27 = 106;
+    public static final int ARM_REG_S28 = 107;
+    public static final int ARM_REG_S29 = 108;
+    public static final int ARM_REG_S30 = 109;
+    public static final int ARM_REG_S31 = 110;
+    public static final int ARM_REG_ENDING = 111;
     /* ARM32 instructions */
     public static final int ARM_INS_INVALID = 0;
     public static final int ARM_INS_ADC = 1;
@@ -606,10 +737,12 @@ public final class ArmConstants {
     public static final int ARM64_GRP_INVALID = 0;
     public static final int ARM64_GRP_JUMP = 1;
     public static final int ARM64_GRP_CALL = 2;
+    public static final int ARM64_GRP_RETURN = 3;
     public static final int ARM64_GRP_RET = 3;
     public static final int ARM64_GRP_INT = 4;
     public static final int ARM64_GRP_PRIVILEGE = 6;
     public static final int ARM64_GRP_BRANCH_RELATIVE = 7;
+    public static final int ARM64_GRP_POINTER_AUTHENTICATION = 8;
     public static final int ARM64_GRP_PAC = 8;
     public static final int ARM64_GRP_CRYPTO = 128;
     public static final int ARM64_GRP_FPARMV8 = 129;
@@ -629,6 +762,319 @@ public final class ArmConstants {
     public static final int ARM64_GRP_V8_3A = 143;
     public static final int ARM64_GRP_V8_4A = 144;
     public static final int ARM64_GRP_ENDING = 145;
+    /* ARM64 registers */
+    public static final int ARM64_REG_INVALID = 0;
+    public static final int ARM64_REG_FFR = 1;
+    public static final int ARM64_REG_FP = 2;
+    public static final int ARM64_REG_X29 = 2;
+    public static final int ARM64_REG_LR = 3;
+    public static final int ARM64_REG_X30 = 3;
+    public static final int ARM64_REG_NZCV = 4;
+    public static final int ARM64_REG_SP = 5;
+    public static final int ARM64_REG_WSP = 6;
+    public static final int ARM64_REG_WZR = 7;
+    public static final int ARM64_REG_XZR = 8;
+    public static final int ARM64_REG_B0 = 9;
+    public static final int ARM64_REG_B1 = 10;
+    public static final int ARM64_REG_B2 = 11;
+    public static final int ARM64_REG_B3 = 12;
+    public static final int ARM64_REG_B4 = 13;
+    public static final int ARM64_REG_B5 = 14;
+    public static final int ARM64_REG_B6 = 15;
+    public static final int ARM64_REG_B7 = 16;
+    public static final int ARM64_REG_B8 = 17;
+    public static final int ARM64_REG_B9 = 18;
+    public static final int ARM64_REG_B10 = 19;
+    public static final int ARM64_REG_B11 = 20;
+    public static final int ARM64_REG_B12 = 21;
+    public static final int ARM64_REG_B13 = 22;
+    public static final int ARM64_REG_B14 = 23;
+    public static final int ARM64_REG_B15 = 24;
+    public static final int ARM64_REG_B16 = 25;
+    public static final int ARM64_REG_B17 = 26;
+    public static final int ARM64_REG_B18 = 27;
+    public static final int ARM64_REG_B19 = 28;
+    public static final int ARM64_REG_B20 = 29;
+    public static final int ARM64_REG_B21 = 30;
+    public static final int ARM64_REG_B22 = 31;
+    public static final int ARM64_REG_B23 = 32;
+    public static final int ARM64_REG_B24 = 33;
+    public static final int ARM64_REG_B25 = 34;
+    public static final int ARM64_REG_B26 = 35;
+    public static final int ARM64_REG_B27 = 36;
+    public static final int ARM64_REG_B28 = 37;
+    public static final int ARM64_REG_B29 = 38;
+    public static final int ARM64_REG_B30 = 39;
+    public static final int ARM64_REG_B31 = 40;
+    public static final int ARM64_REG_D0 = 41;
+    public static final int ARM64_REG_D1 = 42;
+    public static final int ARM64_REG_D2 = 43;
+    public static final int ARM64_REG_D3 = 44;
+    public static final int ARM64_REG_D4 = 45;
+    public static final int ARM64_REG_D5 = 46;
+    public static final int ARM64_REG_D6 = 47;
+    public static final int ARM64_REG_D7 = 48;
+    public static final int ARM64_REG_D8 = 49;
+    public static final int ARM64_REG_D9 = 50;
+    public static final int ARM64_REG_D10 = 51;
+    public static final int ARM64_REG_D11 = 52;
+    public static final int ARM64_REG_D12 = 53;
+    public static final int ARM64_REG_D13 = 54;
+    public static final int ARM64_REG_D14 = 55;
+    public static final int ARM64_REG_D15 = 56;
+    public static final int ARM64_REG_D16 = 57;
+    public static final int ARM64_REG_D17 = 58;
+    public static final int ARM64_REG_D18 = 59;
+    public static final int ARM64_REG_D19 = 60;
+    public static final int ARM64_REG_D20 = 61;
+    public static final int ARM64_REG_D21 = 62;
+    public static final int ARM64_REG_D22 = 63;
+    public static final int ARM64_REG_D23 = 64;
+    public static final int ARM64_REG_D24 = 65;
+    public static final int ARM64_REG_D25 = 66;
+    public static final int ARM64_REG_D26 = 67;
+    public static final int ARM64_REG_D27 = 68;
+    public static final int ARM64_REG_D28 = 69;
+    public static final int ARM64_REG_D29 = 70;
+    public static final int ARM64_REG_D30 = 71;
+    public static final int ARM64_REG_D31 = 72;
+    public static final int ARM64_REG_H0 = 73;
+    public static final int ARM64_REG_H1 = 74;
+    public static final int ARM64_REG_H2 = 75;
+    public static final int ARM64_REG_H3 = 76;
+    public static final int ARM64_REG_H4 = 77;
+    public static final int ARM64_REG_H5 = 78;
+    public static final int ARM64_REG_H6 = 79;
+    public static final int ARM64_REG_H7 = 80;
+    public static final int ARM64_REG_H8 = 81;
+    public static final int ARM64_REG_H9 = 82;
+    public static final int ARM64_REG_H10 = 83;
+    public static final int ARM64_REG_H11 = 84;
+    public static final int ARM64_REG_H12 = 85;
+    public static final int ARM64_REG_H13 = 86;
+    public static final int ARM64_REG_H14 = 87;
+    public static final int ARM64_REG_H15 = 88;
+    public static final int ARM64_REG_H16 = 89;
+    public static final int ARM64_REG_H17 = 90;
+    public static final int ARM64_REG_H18 = 91;
+    public static final int ARM64_REG_H19 = 92;
+    public static final int ARM64_REG_H20 = 93;
+    public static final int ARM64_REG_H21 = 94;
+    public static final int ARM64_REG_H22 = 95;
+    public static final int ARM64_REG_H23 = 96;
+    public static final int ARM64_REG_H24 = 97;
+    public static final int ARM64_REG_H25 = 98;
+    public static final int ARM64_REG_H26 = 99;
+    public static final int ARM64_REG_H27 = 100;
+    public static final int ARM64_REG_H28 = 101;
+    public static final int ARM64_REG_H29 = 102;
+    public static final int ARM64_REG_H30 = 103;
+    public static final int ARM64_REG_H31 = 104;
+    public static final int ARM64_REG_P0 = 105;
+    public static final int ARM64_REG_P1 = 106;
+    public static final int ARM64_REG_P2 = 107;
+    public static final int ARM64_REG_P3 = 108;
+    public static final int ARM64_REG_P4 = 109;
+    public static final int ARM64_REG_P5 = 110;
+    public static final int ARM64_REG_P6 = 111;
+    public static final int ARM64_REG_P7 = 112;
+    public static final int ARM64_REG_P8 = 113;
+    public static final int ARM64_REG_P9 = 114;
+    public static final int ARM64_REG_P10 = 115;
+    public static final int ARM64_REG_P11 = 116;
+    public static final int ARM64_REG_P12 = 117;
+    public static final int ARM64_REG_P13 = 118;
+    public static final int ARM64_REG_P14 = 119;
+    public static final int ARM64_REG_P15 = 120;
+    public static final int ARM64_REG_Q0 = 121;
+    public static final int ARM64_REG_Q1 = 122;
+    public static final int ARM64_REG_Q2 = 123;
+    public static final int ARM64_REG_Q3 = 124;
+    public static final int ARM64_REG_Q4 = 125;
+    public static final int ARM64_REG_Q5 = 126;
+    public static final int ARM64_REG_Q6 = 127;
+    public static final int ARM64_REG_Q7 = 128;
+    public static final int ARM64_REG_Q8 = 129;
+    public static final int ARM64_REG_Q9 = 130;
+    public static final int ARM64_REG_Q10 = 131;
+    public static final int ARM64_REG_Q11 = 132;
+    public static final int ARM64_REG_Q12 = 133;
+    public static final int ARM64_REG_Q13 = 134;
+    public static final int ARM64_REG_Q14 = 135;
+    public static final int ARM64_REG_Q15 = 136;
+    public static final int ARM64_REG_Q16 = 137;
+    public static final int ARM64_REG_Q17 = 138;
+    public static final int ARM64_REG_Q18 = 139;
+    public static final int ARM64_REG_Q19 = 140;
+    public static final int ARM64_REG_Q20 = 141;
+    public static final int ARM64_REG_Q21 = 142;
+    public static final int ARM64_REG_Q22 = 143;
+    public static final int ARM64_REG_Q23 = 144;
+    public static final int ARM64_REG_Q24 = 145;
+    public static final int ARM64_REG_Q25 = 146;
+    public static final int ARM64_REG_Q26 = 147;
+    public static final int ARM64_REG_Q27 = 148;
+    public static final int ARM64_REG_Q28 = 149;
+    public static final int ARM64_REG_Q29 = 150;
+    public static final int ARM64_REG_Q30 = 151;
+    public static final int ARM64_REG_Q31 = 152;
+    public static final int ARM64_REG_S0 = 153;
+    public static final int ARM64_REG_S1 = 154;
+    public static final int ARM64_REG_S2 = 155;
+    public static final int ARM64_REG_S3 = 156;
+    public static final int ARM64_REG_S4 = 157;
+    public static final int ARM64_REG_S5 = 158;
+    public static final int ARM64_REG_S6 = 159;
+    public static final int ARM64_REG_S7 = 160;
+    public static final int ARM64_REG_S8 = 161;
+    public static final int ARM64_REG_S9 = 162;
+    public static final int ARM64_REG_S10 = 163;
+    public static final int ARM64_REG_S11 = 164;
+    public static final int ARM64_REG_S12 = 165;
+    public static final int ARM64_REG_S13 = 166;
+    public static final int ARM64_REG_S14 = 167;
+    public static final int ARM64_REG_S15 = 168;
+    public static final int ARM64_REG_S16 = 169;
+    public static final int ARM64_REG_S17 = 170;
+    public static final int ARM64_REG_S18 = 171;
+    public static final int ARM64_REG_S19 = 172;
+    public static final int ARM64_REG_S20 = 173;
+    public static final int ARM64_REG_S21 = 174;
+    public static final int ARM64_REG_S22 = 175;
+    public static final int ARM64_REG_S23 = 176;
+    public static final int ARM64_REG_S24 = 177;
+    public static final int ARM64_REG_S25 = 178;
+    public static final int ARM64_REG_S26 = 179;
+    public static final int ARM64_REG_S27 = 180;
+    public static final int ARM64_REG_S28 = 181;
+    public static final int ARM64_REG_S29 = 182;
+    public static final int ARM64_REG_S30 = 183;
+    public static final int ARM64_REG_S31 = 184;
+    public static final int ARM64_REG_W0 = 185;
+    public static final int ARM64_REG_W1 = 186;
+    public static final int ARM64_REG_W2 = 187;
+    public static final int ARM64_REG_W3 = 188;
+    public static final int ARM64_REG_W4 = 189;
+    public static final int ARM64_REG_W5 = 190;
+    public static final int ARM64_REG_W6 = 191;
+    public static final int ARM64_REG_W7 = 192;
+    public static final int ARM64_REG_W8 = 193;
+    public static final int ARM64_REG_W9 = 194;
+    public static final int ARM64_REG_W10 = 195;
+    public static final int ARM64_REG_W11 = 196;
+    public static final int ARM64_REG_W12 = 197;
+    public static final int ARM64_REG_W13 = 198;
+    public static final int ARM64_REG_W14 = 199;
+    public static final int ARM64_REG_W15 = 200;
+    public static final int ARM64_REG_W16 = 201;
+    public static final int ARM64_REG_W17 = 202;
+    public static final int ARM64_REG_W18 = 203;
+    public static final int ARM64_REG_W19 = 204;
+    public static final int ARM64_REG_W20 = 205;
+    public static final int ARM64_REG_W21 = 206;
+    public static final int ARM64_REG_W22 = 207;
+    public static final int ARM64_REG_W23 = 208;
+    public static final int ARM64_REG_W24 = 209;
+    public static final int ARM64_REG_W25 = 210;
+    public static final int ARM64_REG_W26 = 211;
+    public static final int ARM64_REG_W27 = 212;
+    public static final int ARM64_REG_W28 = 213;
+    public static final int ARM64_REG_W29 = 214;
+    public static final int ARM64_REG_W30 = 215;
+    public static final int ARM64_REG_X0 = 216;
+    public static final int ARM64_REG_X1 = 217;
+    public static final int ARM64_REG_X2 = 218;
+    public static final int ARM64_REG_X3 = 219;
+    public static final int ARM64_REG_X4 = 220;
+    public static final int ARM64_REG_X5 = 221;
+    public static final int ARM64_REG_X6 = 222;
+    public static final int ARM64_REG_X7 = 223;
+    public static final int ARM64_REG_X8 = 224;
+    public static final int ARM64_REG_X9 = 225;
+    public static final int ARM64_REG_X10 = 226;
+    public static final int ARM64_REG_X11 = 227;
+    public static final int ARM64_REG_X12 = 228;
+    public static final int ARM64_REG_X13 = 229;
+    public static final int ARM64_REG_X14 = 230;
+    public static final int ARM64_REG_X15 = 231;
+    public static final int ARM64_REG_X16 = 232;
+    public static final int ARM64_REG_X17 = 233;
+    public static final int ARM64_REG_X18 = 234;
+    public static final int ARM64_REG_X19 = 235;
+    public static final int ARM64_REG_X20 = 236;
+    public static final int ARM64_REG_X21 = 237;
+    public static final int ARM64_REG_X22 = 238;
+    public static final int ARM64_REG_X23 = 239;
+    public static final int ARM64_REG_X24 = 240;
+    public static final int ARM64_REG_X25 = 241;
+    public static final int ARM64_REG_X26 = 242;
+    public static final int ARM64_REG_X27 = 243;
+    public static final int ARM64_REG_X28 = 244;
+    public static final int ARM64_REG_Z0 = 245;
+    public static final int ARM64_REG_Z1 = 246;
+    public static final int ARM64_REG_Z2 = 247;
+    public static final int ARM64_REG_Z3 = 248;
+    public static final int ARM64_REG_Z4 = 249;
+    public static final int ARM64_REG_Z5 = 250;
+    public static final int ARM64_REG_Z6 = 251;
+    public static final int ARM64_REG_Z7 = 252;
+    public static final int ARM64_REG_Z8 = 253;
+    public static final int ARM64_REG_Z9 = 254;
+    public static final int ARM64_REG_Z10 = 255;
+    public static final int ARM64_REG_Z11 = 256;
+    public static final int ARM64_REG_Z12 = 257;
+    public static final int ARM64_REG_Z13 = 258;
+    public static final int ARM64_REG_Z14 = 259;
+    public static final int ARM64_REG_Z15 = 260;
+    public static final int ARM64_REG_Z16 = 261;
+    public static final int ARM64_REG_Z17 = 262;
+    public static final int ARM64_REG_Z18 = 263;
+    public static final int ARM64_REG_Z19 = 264;
+    public static final int ARM64_REG_Z20 = 265;
+    public static final int ARM64_REG_Z21 = 266;
+    public static final int ARM64_REG_Z22 = 267;
+    public static final int ARM64_REG_Z23 = 268;
+    public static final int ARM64_REG_Z24 = 269;
+    public static final int ARM64_REG_Z25 = 270;
+    public static final int ARM64_REG_Z26 = 271;
+    public static final int ARM64_REG_Z27 = 272;
+    public static final int ARM64_REG_Z28 = 273;
+    public static final int ARM64_REG_Z29 = 274;
+    public static final int ARM64_REG_Z30 = 275;
+    public static final int ARM64_REG_Z31 = 276;
+    public static final int ARM64_REG_V0 = 277;
+    public static final int ARM64_REG_V1 = 278;
+    public static final int ARM64_REG_V2 = 279;
+    public static final int ARM64_REG_V3 = 280;
+    public static final int ARM64_REG_V4 = 281;
+    public static final int ARM64_REG_V5 = 282;
+    public static final int ARM64_REG_V6 = 283;
+    public static final int ARM64_REG_V7 = 284;
+    public static final int ARM64_REG_V8 = 285;
+    public static final int ARM64_REG_V9 = 286;
+    public static final int ARM64_REG_V10 = 287;
+    public static final int ARM64_REG_V11 = 288;
+    public static final int ARM64_REG_V12 = 289;
+    public static final int ARM64_REG_V13 = 290;
+    public static final int ARM64_REG_V14 = 291;
+    public static final int ARM64_REG_V15 = 292;
+    public static final int ARM64_REG_V16 = 293;
+    public static final int ARM64_REG_V17 = 294;
+    public static final int ARM64_REG_V18 = 295;
+    public static final int ARM64_REG_V19 = 296;
+    public static final int ARM64_REG_V20 = 297;
+    public static final int ARM64_REG_V21 = 298;
+    public static final int ARM64_REG_V22 = 299;
+    public static final int ARM64_REG_V23 = 300;
+    public static final int ARM64_REG_V24 = 301;
+    public static final int ARM64_REG_V25 = 302;
+    public static final int ARM64_REG_V26 = 303;
+    public static final int ARM64_REG_V27 = 304;
+    public static final int ARM64_REG_V28 = 305;
+    public static final int ARM64_REG_V29 = 306;
+    public static final int ARM64_REG_V30 = 307;
+    public static final int ARM64_REG_V31 = 308;
+    public static final int ARM64_REG_ENDING = 309;
     /* ARM64 instructions */
     public static final int ARM64_INS_INVALID = 0;
     public static final int ARM64_INS_ABS = 1;
@@ -1581,4 +2027,4 @@ public final class ArmConstants {
     public static final int ARM64_INS_AT = 948;
     public static final int ARM64_INS_TLBI = 949;
     public static final int ARM64_INS_ENDING = 950;
-}
\ No newline at end of file
+}
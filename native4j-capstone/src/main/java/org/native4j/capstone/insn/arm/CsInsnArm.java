@@ -17,10 +17,73 @@ import org.native4j.capstone.insn.CsInsn;
 public abstract class CsInsnArm extends CsInsn {
     public String mnemonic;
     public String operand;
+    /**
+     * The operands with branch targets and PC-relative addresses replaced by
+     * symbol names, e.g. {@code memcpy} or {@code g_table@PAGE}, or {@code null}
+     * if the instruction was not disassembled with a symbol resolver.
+     */
+    public String symbolizedOperand;
     public int instructionId;
     public int size;
+    /**
+     * The raw little-endian encoding word, or {@code 0} if the instruction is not
+     * 4 bytes wide. For 32-bit Thumb-2 instructions the first halfword forms the
+     * high 16 bits.
+     */
+    public int encoding;
     public long address;
     public short[] regsRead;
     public short[] regsWrite;
     public short[] groups;
+    /* Control-flow classification, computed natively */
+    public boolean isBranch;
+    public boolean isConditional;
+    public boolean isCall;
+    public boolean isReturn;
+    public boolean isIndirect;
+    public boolean isPcRelative;
+    public boolean isPrivileged;
+    public boolean isInterrupt;
+    public boolean isTerminator;
+    /**
+     * Condition flags read by the instruction, as a mask of the
+     * {@code ARM_FLAG_*} constants in {@link ArmConstants}. This includes the
+     * flags tested by its condition code.
+     */
+    public byte flagsRead;
+    /**
+     * Condition flags the instruction may write, as a mask of the
+     * {@code ARM_FLAG_*} constants in {@link ArmConstants}.
+     */
+    public byte flagsWritten;
+    /**
+     * Absolute target of a direct branch, or {@code null} if the instruction is
+     * not a direct branch.
+     */
+    public Long branchTarget;
+    /**
+     * Whether the branch switches between the ARM and Thumb instruction sets.
+     */
+    public boolean isInterworking;
+    /**
+     * Absolute address materialized relative to the PC (literal pool loads,
+     * {@code adr}, {@code adrp} and the instructions consuming its page), or
+     * {@code null} if there is none.
+     */
+    public Long referencedAddress;
+    /**
+     * Value loaded from {@link #referencedAddress} if it lies within the
+     * disassembled bytes, otherwise {@code null}.
+     */
+    public Long referencedValue;
+    /**
+     * Address of the jump table used by an indirect branch, or {@code null} if
+     * no table was recognized.
+     */
+    public Long jumpTableAddress;
+    /**
+     * Case targets read from the jump table in table order, or {@code null} if
+     * no table was recognized.
+     */
+    public long[] jumpTableTargets;
 }
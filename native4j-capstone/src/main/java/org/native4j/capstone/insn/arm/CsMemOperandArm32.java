@@ -18,6 +18,34 @@ public class CsMemOperandArm32 {
     public int index;
     public int scale;
     public int displacement;
+    /**
+     * The addressing mode, one of the {@code ARM_MEM_*} constants in
+     * {@link ArmConstants}.
+     */
+    public byte addressingMode;
+    /**
+     * Whether the access writes the updated address back to the base register.
+     */
+    public boolean baseUpdated;
+    /**
+     * The immediate added to the base register after a post-indexed access.
+     */
+    public long postIndexImmediate;
+    /**
+     * The register added to the base register after a post-indexed access, or
+     * {@code 0} if there is none.
+     */
+    public int postIndexRegister;
+    /**
+     * The shift of the index register, or of the post-index register, as an
+     * {@code ARM_SFT_*} constant.
+     */
+    public byte indexShiftType;
+    public int indexShiftValue;
+    /**
+     * Whether the index or post-index register is subtracted from the base.
+     */
+    public boolean isSubtracted;
 
     /* Invoked by JNI */
     public CsMemOperandArm32(int base, int index, int scale, int displacement) {
@@ -17,6 +17,35 @@ public class CsMemOperandArm64 {
     public int base;
     public int index;
     public int displacement;
+    /**
+     * The addressing mode, one of the {@code ARM_MEM_*} constants in
+     * {@link ArmConstants}.
+     */
+    public byte addressingMode;
+    /**
+     * Whether the access writes the updated address back to the base register.
+     */
+    public boolean baseUpdated;
+    /**
+     * The immediate added to the base register after a post-indexed access.
+     */
+    public long postIndexImmediate;
+    /**
+     * The register added to the base register after a post-indexed access, or
+     * {@code 0} if there is none.
+     */
+    public int postIndexRegister;
+    /**
+     * The shift of the index register, or of the post-index register, as an
+     * {@code ARM64_SFT_*} constant.
+     */
+    public byte indexShiftType;
+    public int indexShiftValue;
+    /**
+     * The extender of the index register, in the encoding of
+     * {@link CsOperandArm64#ext}.
+     */
+    public byte indexExtend;
 
     /* Invoked by JNI */
     public CsMemOperandArm64(int base, int index, int displacement) {
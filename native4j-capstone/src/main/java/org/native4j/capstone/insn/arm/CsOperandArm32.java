@@ -8,6 +8,8 @@ package org.native4j.capstone.insn.arm;
 
 import static org.native4j.capstone.insn.arm.ArmConstants.*;
 
+import org.jetbrains.annotations.Nullable;
+
 import org.native4j.capstone.annotation.JNIClass;
 
 /**
@@ -22,6 +24,12 @@ public class CsOperandArm32 {
     public long shiftValue;
     public byte operandType;
     public Object rawOperandValue;
+    /**
+     * The system register accessed through this operand, or {@code null} if
+     * the operand does not name one.
+     */
+    @Nullable
+    public SystemRegister systemRegister;
 
     public int getReg() {
         assertType(ARM_OP_REG);
@@ -33,9 +41,9 @@ public class CsOperandArm32 {
         return (int) rawOperandValue;
     }
 
-    public CsMemOperandArm64 getMem() {
+    public CsMemOperandArm32 getMem() {
         assertType(ARM_OP_MEM);
-        return (CsMemOperandArm64) rawOperandValue;
+        return (CsMemOperandArm32) rawOperandValue;
     }
 
     public double getFp() {
@@ -8,6 +8,8 @@ package org.native4j.capstone.insn.arm;
 
 import static org.native4j.capstone.insn.arm.ArmConstants.*;
 
+import org.jetbrains.annotations.Nullable;
+
 import org.native4j.capstone.annotation.JNIClass;
 
 /**
@@ -23,6 +25,12 @@ public class CsOperandArm64 {
     public byte ext;
     public byte operandType;
     public Object rawOperandValue;
+    /**
+     * The system register accessed through this operand, or {@code null} if
+     * the operand does not name one.
+     */
+    @Nullable
+    public SystemRegister systemRegister;
 
     public int getReg() {
         assertType(ARM64_OP_REG);
@@ -51,12 +59,12 @@ public class CsOperandArm64 {
 
     public int getRegMrs() {
         assertType(ARM64_OP_REGMRS);
-        return getReg();
+        return (int) rawOperandValue;
     }
 
     public int getRegMsr() {
         assertType(ARM64_OP_REGMSR);
-        return getReg();
+        return (int) rawOperandValue;
     }
 
     public short getPstate() {
@@ -66,7 +74,7 @@ public class CsOperandArm64 {
 
     public long getSys() {
         assertType(ARM64_OP_SYS);
-        return (int) rawOperandValue & 0xffffffffL;
+        return (long) rawOperandValue;
     }
 
     public byte getPrefetch() {
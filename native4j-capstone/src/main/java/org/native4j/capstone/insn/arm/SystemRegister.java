@@ -0,0 +1,73 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.insn.arm;
+
+import org.jetbrains.annotations.Nullable;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * A system register accessed by an instruction: an AArch64 {@code mrs} or
+ * {@code msr} register, an AArch32 coprocessor register of {@code mrc},
+ * {@code mcr}, {@code mrrc} or {@code mcrr}, or an AArch32 special register.
+ * Fields that do not apply to the kind of access are {@code -1}.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public class SystemRegister {
+    /**
+     * The coprocessor number of an AArch32 coprocessor access.
+     */
+    public int coprocessor;
+    /**
+     * The {@code op0} field of an AArch64 register.
+     */
+    public int op0;
+    /**
+     * The {@code op1} field, or {@code opc1} of a coprocessor access.
+     */
+    public int op1;
+    public int crn;
+    public int crm;
+    /**
+     * The {@code op2} field, or {@code opc2} of a coprocessor access.
+     */
+    public int op2;
+    /**
+     * The architectural name, e.g. {@code TTBR0_EL1}, or {@code null} if the
+     * register is not known.
+     */
+    @Nullable
+    public String name;
+
+    /* Invoked by JNI */
+    public SystemRegister(int coprocessor, int op0, int op1, int crn, int crm, int op2, @Nullable String name) {
+        this.coprocessor = coprocessor;
+        this.op0 = op0;
+        this.op1 = op1;
+        this.crn = crn;
+        this.crm = crm;
+        this.op2 = op2;
+        this.name = name;
+    }
+
+    /**
+     * Returns the architectural name, or the generic name of the encoding if
+     * the register is not known, e.g. {@code S3_0_C15_C2_0} or
+     * {@code p15, 0, c1, c0, 0}.
+     */
+    @Override
+    public String toString() {
+        if (name != null)
+            return name;
+        if (coprocessor < 0)
+            return "S" + op0 + "_" + op1 + "_C" + crn + "_C" + crm + "_" + op2;
+        if (crn < 0)
+            return "p" + coprocessor + ", " + op1 + ", c" + crm;
+        return "p" + coprocessor + ", " + op1 + ", c" + crn + ", c" + crm + ", " + op2;
+    }
+}
@@ -0,0 +1,37 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.json;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * The text of an exported JSON document.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public final class JsonExport {
+    /**
+     * Version of the document layout. Changes when a field is renamed, removed
+     * or changes meaning. Adding fields does not change the version.
+     */
+    public static final int SCHEMA_VERSION = 1;
+
+    /* Set directly via JNI. Do not modify. */
+    private String text;
+
+    public JsonExport() {
+    }
+
+    /**
+     * Returns the exported document.
+     *
+     * @return The JSON text
+     */
+    public String getText() {
+        return text == null ? "" : text;
+    }
+}
@@ -0,0 +1,31 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.listing;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * The text of a rendered listing.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public final class Listing {
+    /* Set directly via JNI. Do not modify. */
+    private String text;
+
+    public Listing() {
+    }
+
+    /**
+     * Returns the rendered text.
+     *
+     * @return The listing, one instruction per line
+     */
+    public String getText() {
+        return text == null ? "" : text;
+    }
+}
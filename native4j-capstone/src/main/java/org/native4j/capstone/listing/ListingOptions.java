@@ -0,0 +1,38 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.listing;
+
+import org.jetbrains.annotations.Nullable;
+
+import org.native4j.capstone.annotation.JNIClass;
+import org.native4j.capstone.symbol.SymbolResolver;
+
+/**
+ * Layout options of an {@code objdump}-style listing.
+ */
+@JNIClass
+public final class ListingOptions {
+    /**
+     * Number of bytes printed as one little-endian group, {@code 0} for the
+     * instruction unit of the mode: 4 for ARM, 2 for Thumb and 1 for x86.
+     */
+    public int byteGrouping = 0;
+    /**
+     * Minimum number of hex digits of an address.
+     */
+    public int addressWidth = 8;
+    /**
+     * Append comments with the addresses and symbols of branch targets and
+     * PC-relative references.
+     */
+    public boolean comments = true;
+    /**
+     * Names symbol headers and resolved targets, or {@code null} for none.
+     */
+    @Nullable
+    public SymbolResolver symbols = null;
+}
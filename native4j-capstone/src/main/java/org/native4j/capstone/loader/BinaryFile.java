@@ -0,0 +1,249 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.loader;
+
+import java.util.Arrays;
+import java.util.Collections;
+import java.util.HashMap;
+import java.util.List;
+import java.util.Map;
+
+import org.jetbrains.annotations.Nullable;
+import org.jetbrains.annotations.Unmodifiable;
+
+import org.native4j.capstone.Capstone;
+import org.native4j.capstone.CapstoneMode;
+import org.native4j.capstone.exception.CapstoneException;
+import org.native4j.capstone.insn.CapstoneResult;
+import org.native4j.capstone.symbol.SymbolTable;
+
+/**
+ * A binary file with its sections and symbols.
+ */
+@SuppressWarnings("unused")
+public abstract class BinaryFile {
+    /* Set directly via JNI. Do not modify. */
+    private CapstoneMode mode;
+    private long entryPoint;
+    private Section[] sections;
+    private Symbol[] symbols;
+
+    private final byte[] data;
+
+    protected BinaryFile(byte[] data) {
+        this.data = data;
+    }
+
+    /**
+     * Returns the disassembly mode selected from the file header.
+     *
+     * @return The mode or {@code null} if the architecture is not supported
+     */
+    @Nullable
+    public CapstoneMode getMode() {
+        return mode;
+    }
+
+    /**
+     * Returns the disassembly mode for the code of a symbol. Functions in
+     * 32-bit ARM files are decoded in the state of the symbol, whatever state
+     * the entry point is in.
+     *
+     * @param symbol
+     *               The symbol
+     *
+     * @return The mode or {@code null} if the architecture is not supported
+     */
+    @Nullable
+    public CapstoneMode getMode(Symbol symbol) {
+        if (mode == CapstoneMode.ARM32 || mode == CapstoneMode.THUMB)
+            return symbol.isThumb ? CapstoneMode.THUMB : CapstoneMode.ARM32;
+        return mode;
+    }
+
+    public long getEntryPoint() {
+        return entryPoint;
+    }
+
+    /**
+     * Returns the sections, in file order.
+     *
+     * @return An unmodifiable list of sections
+     */
+    @Unmodifiable
+    public List<Section> getSections() {
+        return sections == null ? Collections.emptyList() : Collections.unmodifiableList(Arrays.asList(sections));
+    }
+
+    /**
+     * Find a section by name.
+     *
+     * @param name
+     *             The section name
+     *
+     * @return The first section with the name or {@code null} if there is none
+     */
+    @Nullable
+    public Section getSection(String name) {
+        for (Section section : getSections()) {
+            if (section.name.equals(name))
+                return section;
+        }
+        return null;
+    }
+
+    /**
+     * Returns the symbols, in file order.
+     *
+     * @return An unmodifiable list of symbols
+     */
+    @Unmodifiable
+    public List<Symbol> getSymbols() {
+        return symbols == null ? Collections.emptyList() : Collections.unmodifiableList(Arrays.asList(symbols));
+    }
+
+    /**
+     * Find a defined symbol by name.
+     *
+     * @param name
+     *             The symbol name
+     *
+     * @return The first defined symbol with the name or {@code null} if there
+     *         is none
+     */
+    @Nullable
+    public Symbol getSymbol(String name) {
+        for (Symbol symbol : getSymbols()) {
+            if (symbol.isDefined && symbol.name.equals(name))
+                return symbol;
+        }
+        return null;
+    }
+
+    /**
+     * Returns a symbol table of the defined, named symbols for symbolized
+     * disassembly. If several symbols share an address, the first one is used.
+     *
+     * @return The symbol table
+     */
+    public SymbolTable getSymbolTable() {
+        Map<Long, String> table = new HashMap<>();
+        for (Symbol symbol : getSymbols()) {
+            if (symbol.isDefined && !symbol.name.isEmpty())
+                table.putIfAbsent(symbol.address, symbol.name);
+        }
+        return new SymbolTable(table);
+    }
+
+    /**
+     * Returns the file contents of a section.
+     *
+     * @param section
+     *                The section
+     *
+     * @return A copy of the section bytes
+     */
+    public byte[] getBytes(Section section) {
+        return Arrays.copyOfRange(data, (int) section.offset, (int) (section.offset + section.fileSize));
+    }
+
+    /**
+     * Returns the file contents at the given virtual address.
+     *
+     * @param address
+     *                The virtual address
+     * @param size
+     *                The number of bytes
+     *
+     * @return A copy of the bytes
+     *
+     * @throws CapstoneException
+     *                           The range is not contained in a section
+     */
+    public byte[] getBytes(long address, long size) {
+        for (Section section : getSections()) {
+            long start = address - section.address;
+            if (section.fileSize > 0 && Long.compareUnsigned(start, section.fileSize) < 0
+                    && Long.compareUnsigned(start + size, section.fileSize) <= 0) {
+                int offset = (int) (section.offset + start);
+                return Arrays.copyOfRange(data, offset, offset + (int) size);
+            }
+        }
+        throw new CapstoneException(String.format("no section contains 0x%x-0x%x", address, address + size));
+    }
+
+    /**
+     * Disassemble all instructions of a section.
+     *
+     * @param name
+     *             The section name
+     *
+     * @return The disassembled instructions
+     *
+     * @throws CapstoneException
+     *                           The section does not exist or the architecture is
+     *                           not supported
+     */
+    public CapstoneResult disassembleSection(String name) {
+        Section section = getSection(name);
+        if (section == null)
+            throw new CapstoneException("no section named " + name);
+        return disassemble(requireMode(mode), getBytes(section), section.address);
+    }
+
+    /**
+     * Disassemble all instructions of a symbol, in Thumb mode if the symbol is a
+     * Thumb function.
+     *
+     * @param name
+     *             The symbol name
+     *
+     * @return The disassembled instructions
+     *
+     * @throws CapstoneException
+     *                           The symbol does not exist or the architecture is
+     *                           not supported
+     */
+    public CapstoneResult disassembleSymbol(String name) {
+        Symbol symbol = getSymbol(name);
+        if (symbol == null)
+            throw new CapstoneException("no symbol named " + name);
+        return disassemble(requireMode(getMode(symbol)), getBytes(symbol.address, symbol.size), symbol.address);
+    }
+
+    /**
+     * Disassemble all instructions in the given address range.
+     *
+     * @param address
+     *                The address of the first instruction
+     * @param size
+     *                The number of bytes to disassemble
+     *
+     * @return The disassembled instructions
+     *
+     * @throws CapstoneException
+     *                           The range is not contained in a section or the
+     *                           architecture is not supported
+     */
+    public CapstoneResult disassembleRange(long address, long size) {
+        return disassemble(requireMode(mode), getBytes(address, size), address);
+    }
+
+    private static CapstoneMode requireMode(CapstoneMode mode) {
+        if (mode == null)
+            throw new CapstoneException("unsupported architecture");
+        return mode;
+    }
+
+    private static CapstoneResult disassemble(CapstoneMode mode, byte[] bytes, long address) {
+        try (Capstone capstone = new Capstone(mode)) {
+            CapstoneResult result = new CapstoneResult();
+            capstone.disassembleAll(result, bytes, address);
+            return result;
+        }
+    }
+}
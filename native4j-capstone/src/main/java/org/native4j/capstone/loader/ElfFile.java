@@ -0,0 +1,132 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.loader;
+
+import java.io.IOException;
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.util.Arrays;
+import java.util.Collections;
+import java.util.List;
+
+import org.jetbrains.annotations.Unmodifiable;
+
+import org.native4j.capstone.NativeUtil;
+import org.native4j.capstone.annotation.JNIClass;
+import org.native4j.capstone.exception.CapstoneException;
+
+/**
+ * An ELF executable, shared object or relocatable file. ELF32 and ELF64 files
+ * of either byte order are parsed, but only little-endian files have a
+ * disassembly mode, {@link #getMode()} returns {@code null} for big-endian ones.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public final class ElfFile extends BinaryFile {
+    public static final int EM_386 = 3;
+    public static final int EM_ARM = 40;
+    public static final int EM_X86_64 = 62;
+    public static final int EM_AARCH64 = 183;
+
+    /* Set directly via JNI. Do not modify. */
+    private int machine;
+    private int fileType;
+    private boolean is64Bit;
+    private boolean isBigEndian;
+    private Segment[] segments;
+
+    private ElfFile(byte[] data) {
+        super(data);
+    }
+
+    /**
+     * Parse an ELF file.
+     *
+     * @param data
+     *             The file contents
+     *
+     * @return The parsed file
+     *
+     * @throws CapstoneException
+     *                           The data is not a valid ELF file
+     */
+    public static ElfFile load(byte[] data) {
+        ElfFile file = new ElfFile(data);
+        String error = file.parse(data);
+        if (error != null)
+            throw new CapstoneException(error);
+        return file;
+    }
+
+    /**
+     * Read and parse an ELF file.
+     *
+     * @param path
+     *             The file path
+     *
+     * @return The parsed file
+     *
+     * @throws IOException
+     *                           The file could not be read
+     * @throws CapstoneException
+     *                           The file is not a valid ELF file
+     */
+    public static ElfFile load(Path path) throws IOException {
+        return load(Files.readAllBytes(path));
+    }
+
+    /**
+     * Returns the {@code e_machine} field of the header.
+     *
+     * @return The machine
+     */
+    public int getMachine() {
+        return machine;
+    }
+
+    /**
+     * Returns the {@code e_type} field of the header.
+     *
+     * @return The object file type
+     */
+    public int getFileType() {
+        return fileType;
+    }
+
+    public boolean is64Bit() {
+        return is64Bit;
+    }
+
+    public boolean isBigEndian() {
+        return isBigEndian;
+    }
+
+    /**
+     * Returns the program headers, in file order.
+     *
+     * @return An unmodifiable list of segments
+     */
+    @Unmodifiable
+    public List<Segment> getSegments() {
+        return segments == null ? Collections.emptyList() : Collections.unmodifiableList(Arrays.asList(segments));
+    }
+
+    /**
+     * Parses the file and populates this instance.
+     *
+     * @return {@code null} if successful, otherwise an error message
+     */
+    private native String parse(byte[] data);
+
+    static {
+        try {
+            NativeUtil.loadBindings();
+        } catch (Exception e) {
+            throw new RuntimeException(e);
+        }
+    }
+}
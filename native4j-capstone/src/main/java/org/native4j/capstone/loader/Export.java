@@ -0,0 +1,35 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.loader;
+
+import org.jetbrains.annotations.Nullable;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * An entry of the export directory of a PE image.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public class Export {
+    /**
+     * The export name, or {@code null} if only exported by ordinal.
+     */
+    @Nullable
+    public String name;
+    public int ordinal;
+    /**
+     * The exported address, without the Thumb bit. 0 for forwarded exports.
+     */
+    public long address;
+    /**
+     * The target of a forwarded export, e.g. {@code NTDLL.RtlAllocateHeap}, or
+     * {@code null}.
+     */
+    @Nullable
+    public String forwarder;
+}
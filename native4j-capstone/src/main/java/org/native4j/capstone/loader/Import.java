@@ -0,0 +1,36 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.loader;
+
+import org.jetbrains.annotations.Nullable;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * A function imported by a PE image.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public class Import {
+    /**
+     * The name of the library, e.g. {@code KERNEL32.dll}.
+     */
+    public String library;
+    /**
+     * The imported name, or {@code null} if imported by ordinal.
+     */
+    @Nullable
+    public String name;
+    /**
+     * The ordinal for imports by ordinal, otherwise the name hint.
+     */
+    public int ordinal;
+    /**
+     * The address of the import address table slot.
+     */
+    public long address;
+}
@@ -0,0 +1,172 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.loader;
+
+import java.io.IOException;
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.util.Arrays;
+import java.util.Collections;
+import java.util.List;
+
+import org.jetbrains.annotations.Unmodifiable;
+
+import org.native4j.capstone.NativeUtil;
+import org.native4j.capstone.annotation.JNIClass;
+import org.native4j.capstone.exception.CapstoneException;
+
+/**
+ * A Mach-O executable, dynamic library or bundle. For universal (fat) binaries
+ * a single slice is selected by its CPU type. Section names are qualified with
+ * their segment, e.g. {@code __TEXT,__text}.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public final class MachOFile extends BinaryFile {
+    /**
+     * Select the preferred supported slice, in the order ARM64, ARM, x86-64 and
+     * x86.
+     */
+    public static final int CPU_TYPE_ANY = -1;
+    public static final int CPU_TYPE_X86 = 7;
+    public static final int CPU_TYPE_X86_64 = 0x01000007;
+    public static final int CPU_TYPE_ARM = 12;
+    public static final int CPU_TYPE_ARM64 = 0x0100000C;
+
+    /* Set directly via JNI. Do not modify. */
+    private int cpuType;
+    private int cpuSubtype;
+    private int fileType;
+    private boolean is64Bit;
+    private long sliceOffset;
+    private long[] functionStarts;
+    private Segment[] segments;
+
+    private MachOFile(byte[] data) {
+        super(data);
+    }
+
+    /**
+     * Parse a Mach-O file, selecting the preferred slice of a fat binary.
+     *
+     * @param data
+     *             The file contents
+     *
+     * @return The parsed file
+     *
+     * @throws CapstoneException
+     *                           The data is not a valid Mach-O file
+     */
+    public static MachOFile load(byte[] data) {
+        return load(data, CPU_TYPE_ANY);
+    }
+
+    /**
+     * Parse a Mach-O file.
+     *
+     * @param data
+     *                The file contents
+     * @param cpuType
+     *                The CPU type of the slice to select from a fat binary, or
+     *                {@link #CPU_TYPE_ANY}
+     *
+     * @return The parsed file
+     *
+     * @throws CapstoneException
+     *                           The data is not a valid Mach-O file or has no
+     *                           slice for the CPU type
+     */
+    public static MachOFile load(byte[] data, int cpuType) {
+        MachOFile file = new MachOFile(data);
+        String error = file.parse(data, cpuType);
+        if (error != null)
+            throw new CapstoneException(error);
+        return file;
+    }
+
+    /**
+     * Read and parse a Mach-O file, selecting the preferred slice of a fat
+     * binary.
+     *
+     * @param path
+     *             The file path
+     *
+     * @return The parsed file
+     *
+     * @throws IOException
+     *                           The file could not be read
+     * @throws CapstoneException
+     *                           The file is not a valid Mach-O file
+     */
+    public static MachOFile load(Path path) throws IOException {
+        return load(Files.readAllBytes(path));
+    }
+
+    public int getCpuType() {
+        return cpuType;
+    }
+
+    public int getCpuSubtype() {
+        return cpuSubtype;
+    }
+
+    /**
+     * Returns the {@code filetype} field of the header.
+     *
+     * @return The file type
+     */
+    public int getFileType() {
+        return fileType;
+    }
+
+    public boolean is64Bit() {
+        return is64Bit;
+    }
+
+    /**
+     * Returns the offset of the selected slice in a fat binary.
+     *
+     * @return The slice offset, 0 for single-architecture files
+     */
+    public long getSliceOffset() {
+        return sliceOffset;
+    }
+
+    /**
+     * Returns the function start addresses of {@code LC_FUNCTION_STARTS}.
+     *
+     * @return A copy of the addresses, ordered by address
+     */
+    public long[] getFunctionStarts() {
+        return functionStarts == null ? new long[0] : functionStarts.clone();
+    }
+
+    /**
+     * Returns the segment load commands, in file order.
+     *
+     * @return An unmodifiable list of segments
+     */
+    @Unmodifiable
+    public List<Segment> getSegments() {
+        return segments == null ? Collections.emptyList() : Collections.unmodifiableList(Arrays.asList(segments));
+    }
+
+    /**
+     * Parses the file and populates this instance.
+     *
+     * @return {@code null} if successful, otherwise an error message
+     */
+    private native String parse(byte[] data, int cpuType);
+
+    static {
+        try {
+            NativeUtil.loadBindings();
+        } catch (Exception e) {
+            throw new RuntimeException(e);
+        }
+    }
+}
@@ -0,0 +1,196 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.loader;
+
+import java.io.IOException;
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.util.Arrays;
+import java.util.Collections;
+import java.util.List;
+
+import org.jetbrains.annotations.Nullable;
+import org.jetbrains.annotations.Unmodifiable;
+
+import org.native4j.capstone.CapstoneMode;
+import org.native4j.capstone.NativeUtil;
+import org.native4j.capstone.annotation.JNIClass;
+import org.native4j.capstone.exception.CapstoneException;
+import org.native4j.capstone.insn.CapstoneResult;
+
+/**
+ * A PE executable or DLL. Sections are mapped at {@code ImageBase + RVA} and
+ * have no type. Named exports are exposed as defined symbols and named imports
+ * as undefined symbols at their import address table slot.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public final class PeFile extends BinaryFile {
+    public static final int IMAGE_FILE_MACHINE_I386 = 0x14C;
+    public static final int IMAGE_FILE_MACHINE_ARM = 0x1C0;
+    public static final int IMAGE_FILE_MACHINE_ARMNT = 0x1C4;
+    public static final int IMAGE_FILE_MACHINE_AMD64 = 0x8664;
+    public static final int IMAGE_FILE_MACHINE_ARM64 = 0xAA64;
+
+    /* Set directly via JNI. Do not modify. */
+    private int machine;
+    private int characteristics;
+    private boolean is64Bit;
+    private long imageBase;
+    private Export[] exports;
+    private Import[] imports;
+    private RuntimeFunction[] runtimeFunctions;
+
+    private PeFile(byte[] data) {
+        super(data);
+    }
+
+    /**
+     * Parse a PE image.
+     *
+     * @param data
+     *             The file contents
+     *
+     * @return The parsed file
+     *
+     * @throws CapstoneException
+     *                           The data is not a valid PE image
+     */
+    public static PeFile load(byte[] data) {
+        PeFile file = new PeFile(data);
+        String error = file.parse(data);
+        if (error != null)
+            throw new CapstoneException(error);
+        return file;
+    }
+
+    /**
+     * Read and parse a PE image.
+     *
+     * @param path
+     *             The file path
+     *
+     * @return The parsed file
+     *
+     * @throws IOException
+     *                           The file could not be read
+     * @throws CapstoneException
+     *                           The file is not a valid PE image
+     */
+    public static PeFile load(Path path) throws IOException {
+        return load(Files.readAllBytes(path));
+    }
+
+    /**
+     * Returns the {@code Machine} field of the COFF header.
+     *
+     * @return The machine
+     */
+    public int getMachine() {
+        return machine;
+    }
+
+    /**
+     * Returns the {@code Characteristics} field of the COFF header.
+     *
+     * @return The characteristics
+     */
+    public int getCharacteristics() {
+        return characteristics;
+    }
+
+    /**
+     * Check whether the image uses the PE32+ optional header.
+     *
+     * @return {@code true} for PE32+ images
+     */
+    public boolean is64Bit() {
+        return is64Bit;
+    }
+
+    public long getImageBase() {
+        return imageBase;
+    }
+
+    /**
+     * Returns the disassembly mode for the code of a symbol. ARMv7 images are
+     * always Thumb-2, so this is the mode of the file.
+     *
+     * @param symbol
+     *               The symbol
+     *
+     * @return The mode or {@code null} if the architecture is not supported
+     */
+    @Nullable
+    @Override
+    public CapstoneMode getMode(Symbol symbol) {
+        return getMode();
+    }
+
+    /**
+     * Returns the exports, ordered by ordinal.
+     *
+     * @return An unmodifiable list of exports
+     */
+    @Unmodifiable
+    public List<Export> getExports() {
+        return exports == null ? Collections.emptyList() : Collections.unmodifiableList(Arrays.asList(exports));
+    }
+
+    /**
+     * Returns the imports, in import directory order.
+     *
+     * @return An unmodifiable list of imports
+     */
+    @Unmodifiable
+    public List<Import> getImports() {
+        return imports == null ? Collections.emptyList() : Collections.unmodifiableList(Arrays.asList(imports));
+    }
+
+    /**
+     * Returns the {@code .pdata} function table. On ARM64 and x64 every
+     * non-leaf function has an entry, so this gives the function ranges
+     * directly.
+     *
+     * @return An unmodifiable list of functions, ordered by address
+     */
+    @Unmodifiable
+    public List<RuntimeFunction> getRuntimeFunctions() {
+        return runtimeFunctions == null ? Collections.emptyList()
+                : Collections.unmodifiableList(Arrays.asList(runtimeFunctions));
+    }
+
+    /**
+     * Disassemble all instructions of a {@code .pdata} function.
+     *
+     * @param function
+     *                 The function
+     *
+     * @return The disassembled instructions
+     *
+     * @throws CapstoneException
+     *                           The architecture is not supported
+     */
+    public CapstoneResult disassembleFunction(RuntimeFunction function) {
+        return disassembleRange(function.startAddress, function.endAddress - function.startAddress);
+    }
+
+    /**
+     * Parses the file and populates this instance.
+     *
+     * @return {@code null} if successful, otherwise an error message
+     */
+    private native String parse(byte[] data);
+
+    static {
+        try {
+            NativeUtil.loadBindings();
+        } catch (Exception e) {
+            throw new RuntimeException(e);
+        }
+    }
+}
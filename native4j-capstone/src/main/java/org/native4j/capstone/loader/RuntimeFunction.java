@@ -0,0 +1,27 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.loader;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * An entry of the {@code .pdata} function table of a PE image.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public class RuntimeFunction {
+    public long startAddress;
+    /**
+     * Address following the last instruction of the function.
+     */
+    public long endAddress;
+    /**
+     * The address of the unwind information, or 0 if it is packed into the
+     * entry.
+     */
+    public long unwindAddress;
+}
@@ -0,0 +1,41 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.loader;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * A section of a binary file.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public class Section {
+    public String name;
+    /**
+     * The format-specific section type.
+     */
+    public int type;
+    /**
+     * The format-specific section flags.
+     */
+    public long flags;
+    /**
+     * The virtual address the section is loaded at.
+     */
+    public long address;
+    public long offset;
+    public long size;
+    /**
+     * Number of bytes the section occupies in the file, 0 for sections that are
+     * only allocated at runtime.
+     */
+    public long fileSize;
+    /**
+     * The section contains executable code.
+     */
+    public boolean isExecutable;
+}
@@ -0,0 +1,36 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.loader;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * A loadable segment of a binary file.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public class Segment {
+    /**
+     * The segment name, or {@code null} if the format does not name segments.
+     */
+    public String name;
+    /**
+     * The format-specific segment type.
+     */
+    public int type;
+    /**
+     * The format-specific segment permissions.
+     */
+    public int flags;
+    public long offset;
+    /**
+     * The virtual address the segment is loaded at.
+     */
+    public long address;
+    public long fileSize;
+    public long memorySize;
+}
@@ -0,0 +1,37 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.loader;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * A symbol of a binary file.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public class Symbol {
+    public String name;
+    /**
+     * The address of the symbol, without the Thumb bit.
+     */
+    public long address;
+    public long size;
+    /**
+     * The format-specific symbol type.
+     */
+    public int type;
+    public boolean isFunction;
+    /**
+     * The symbol is an ARM function in Thumb state.
+     */
+    public boolean isThumb;
+    public boolean isGlobal;
+    /**
+     * The symbol is defined in this file rather than imported.
+     */
+    public boolean isDefined;
+}
@@ -0,0 +1,29 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.symbol;
+
+import org.jetbrains.annotations.Nullable;
+
+/**
+ * Supplies symbol names for symbolized disassembly. Resolvers are called
+ * before the instructions are rendered and may use the calling
+ * {@link org.native4j.capstone.Capstone} instance.
+ */
+@FunctionalInterface
+public interface SymbolResolver {
+    /**
+     * Get the name of the symbol at exactly the given address.
+     *
+     * @param address
+     *                The address to look up
+     *
+     * @return The symbol name or {@code null} if there is no symbol at the
+     *         address
+     */
+    @Nullable
+    String resolve(long address);
+}
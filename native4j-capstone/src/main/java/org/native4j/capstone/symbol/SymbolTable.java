@@ -0,0 +1,62 @@
+/*
+ * Copyright 2023 The Native4J Authors
+ *
+ * Use of this source code is governed by the MIT license found in the LICENSE
+ * file.
+ */
+package org.native4j.capstone.symbol;
+
+import java.util.Arrays;
+import java.util.Map;
+import java.util.TreeMap;
+
+import org.jetbrains.annotations.Nullable;
+
+import org.native4j.capstone.annotation.JNIClass;
+
+/**
+ * An immutable table of symbol names by address. Tables are read natively in
+ * one pass, so they are faster than a {@link SymbolResolver} callback.
+ */
+@JNIClass
+@SuppressWarnings("unused")
+public final class SymbolTable implements SymbolResolver {
+    /* Read directly via JNI */
+    private final long[] addresses;
+    private final String[] names;
+
+    /**
+     * Create a table from the given symbols.
+     *
+     * @param symbols
+     *                Symbol names by address
+     */
+    public SymbolTable(Map<Long, String> symbols) {
+        TreeMap<Long, String> sorted = new TreeMap<>(symbols);
+        addresses = new long[sorted.size()];
+        names = new String[sorted.size()];
+
+        int i = 0;
+        for (Map.Entry<Long, String> entry : sorted.entrySet()) {
+            addresses[i] = entry.getKey();
+            names[i] = entry.getValue();
+            i++;
+        }
+    }
+
+    @Nullable
+    @Override
+    public String resolve(long address) {
+        int index = Arrays.binarySearch(addresses, address);
+        return index < 0 ? null : names[index];
+    }
+
+    /**
+     * Returns the number of symbols in the table.
+     *
+     * @return The number of symbols
+     */
+    public int size() {
+        return addresses.length;
+    }
+}
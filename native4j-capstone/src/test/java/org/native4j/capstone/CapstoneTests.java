@@ -6,17 +6,52 @@
  */
 package org.native4j.capstone;
 
+import java.io.IOException;
+import java.nio.ByteBuffer;
+import java.nio.ByteOrder;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.Path;
 import java.util.ArrayList;
+import java.util.Arrays;
 import java.util.List;
+import java.util.Map;
 import java.util.concurrent.atomic.AtomicBoolean;
 
 import org.junit.jupiter.api.Test;
 
 import static org.junit.jupiter.api.Assertions.*;
 
+import org.native4j.capstone.analysis.BasicBlock;
+import org.native4j.capstone.analysis.ControlFlowGraph;
+import org.native4j.capstone.analysis.FlowEdge;
+import org.native4j.capstone.analysis.FunctionRange;
+import org.native4j.capstone.analysis.FunctionTable;
+import org.native4j.capstone.analysis.Xref;
+import org.native4j.capstone.analysis.XrefIndex;
 import org.native4j.capstone.exception.CapstoneException;
 import org.native4j.capstone.insn.CapstoneResult;
+import org.native4j.capstone.insn.Constants;
+import org.native4j.capstone.insn.CsInsnGeneric;
+import org.native4j.capstone.insn.NameTable;
+import org.native4j.capstone.insn.RegisterInfo;
+import org.native4j.capstone.insn.RegisterTable;
+import org.native4j.capstone.insn.arm.ArmConstants;
+import org.native4j.capstone.insn.arm.CsInsnArm32;
 import org.native4j.capstone.insn.arm.CsInsnArm64;
+import org.native4j.capstone.insn.arm.CsMemOperandArm32;
+import org.native4j.capstone.insn.arm.CsMemOperandArm64;
+import org.native4j.capstone.insn.arm.SystemRegister;
+import org.native4j.capstone.json.JsonExport;
+import org.native4j.capstone.listing.ListingOptions;
+import org.native4j.capstone.loader.ElfFile;
+import org.native4j.capstone.loader.Import;
+import org.native4j.capstone.loader.MachOFile;
+import org.native4j.capstone.loader.PeFile;
+import org.native4j.capstone.loader.RuntimeFunction;
+import org.native4j.capstone.loader.Section;
+import org.native4j.capstone.loader.Symbol;
+import org.native4j.capstone.symbol.SymbolTable;
 
 public class CapstoneTests {
     private static final byte[] code = new byte[] { (byte) 0xFD, 0x7B, (byte) 0xBF, (byte) 0xA9, (byte) 0xFD, 0x03,
@@ -33,6 +68,8 @@ public class CapstoneTests {
         assertEquals(first.operand, "x29, x30, [sp, #-0x10]!");
         assertEquals(first.address, 0x1000);
         assertEquals(first.conditionCodes, 0);
+        assertArrayEquals(first.bytes, Arrays.copyOfRange(code, 0, 4));
+        assertEquals(first.encoding, 0xA9BF7BFD);
 
         assertTrue(first.writebackRequired);
         assertFalse(first.updatesFlags);
@@ -44,6 +81,12 @@ public class CapstoneTests {
         assertEquals(first.operands[1].getReg(), 3);
         assertEquals(first.operands[2].getMem().displacement, -0x10);
 
+        // adrp x0, #0x1000; add x0, x0, #0x790
+        assertEquals(insns[2].referencedAddress, 0x1000L);
+        assertEquals(insns[3].referencedAddress, 0x1790L);
+        assertNull(insns[3].referencedValue);
+        assertNull(first.referencedAddress);
+
         CsInsnArm64 fifth = insns[4];
         assertEquals(fifth.mnemonic, "bl");
         assertEquals(capstone.getInsnName(fifth.instructionId), "bl");
@@ -53,6 +96,18 @@ public class CapstoneTests {
         assertEquals(capstone.getGroupName(fifth.groups[0]), "call");
         assertEquals(capstone.getGroupName(fifth.groups[1]), "jump");
         assertEquals(capstone.getGroupName(fifth.groups[2]), "branch_relative");
+        assertTrue(fifth.isBranch);
+        assertTrue(fifth.isCall);
+        assertTrue(fifth.isPcRelative);
+        assertFalse(fifth.isTerminator);
+        assertEquals(fifth.branchTarget, 3804L);
+        assertNull(first.branchTarget);
+
+        CsInsnArm64 last = insns[insns.length - 1];
+        assertEquals(last.mnemonic, "ret");
+        assertTrue(last.isReturn);
+        assertTrue(last.isIndirect);
+        assertTrue(last.isTerminator);
 
         return true;
     }
@@ -67,6 +122,785 @@ public class CapstoneTests {
         }
     }
 
+    @Test
+    void testFlagEffects() {
+        byte[] flagCode = new byte[] { 0x1F, 0x0C, 0x00, (byte) 0xF1, 0x20, 0x00, (byte) 0x82, (byte) 0x9A, 0x4C,
+                0x00, 0x00, 0x54 };
+        try (Capstone capstone = new Capstone(CapstoneMode.ARM64)) {
+            CapstoneResult result = new CapstoneResult();
+            capstone.disassembleAll(result, flagCode, 0x1000);
+            CsInsnArm64[] insns = result.toArray(CsInsnArm64[].class);
+            int all = ArmConstants.ARM_FLAG_N | ArmConstants.ARM_FLAG_Z | ArmConstants.ARM_FLAG_C
+                    | ArmConstants.ARM_FLAG_V;
+
+            // cmp x0, #3
+            assertEquals(insns[0].flagsRead, 0);
+            assertEquals(insns[0].flagsWritten, all);
+
+            // csel x0, x1, x2, eq
+            assertEquals(insns[1].flagsRead, ArmConstants.ARM_FLAG_Z);
+            assertEquals(insns[1].flagsWritten, 0);
+
+            // b.gt #0x1010
+            assertEquals(insns[2].flagsRead, ArmConstants.ARM_FLAG_N | ArmConstants.ARM_FLAG_Z
+                    | ArmConstants.ARM_FLAG_V);
+        }
+    }
+
+    @Test
+    void testMemoryAddressing() {
+        try (Capstone capstone = new Capstone(CapstoneMode.ARM64)) {
+            CapstoneResult result = new CapstoneResult();
+            capstone.disassembleAll(result, code, 0x1000);
+            CsInsnArm64[] insns = result.toArray(CsInsnArm64[].class);
+
+            // stp x29, x30, [sp, #-0x10]!
+            CsMemOperandArm64 push = insns[0].operands[2].getMem();
+            assertEquals(push.addressingMode, ArmConstants.ARM_MEM_PRE_INDEXED);
+            assertTrue(push.baseUpdated);
+            assertEquals(push.displacement, -0x10);
+
+            // ldp x29, x30, [sp], #0x10
+            CsMemOperandArm64 pop = insns[6].operands[2].getMem();
+            assertEquals(pop.addressingMode, ArmConstants.ARM_MEM_POST_INDEXED);
+            assertEquals(pop.postIndexImmediate, 0x10);
+        }
+
+        // ldr r0, [r1], -r2, lsl #2; ldr r0, [r1, #-4]
+        byte[] armCode = new byte[] { 0x02, 0x01, 0x11, (byte) 0xE6, 0x04, 0x00, 0x11, (byte) 0xE5 };
+        try (Capstone capstone = new Capstone(CapstoneMode.ARM32)) {
+            CapstoneResult result = new CapstoneResult();
+            capstone.disassembleAll(result, armCode, 0x1000);
+            CsInsnArm32[] insns = result.toArray(CsInsnArm32[].class);
+
+            CsMemOperandArm32 postIndexed = insns[0].operands[1].getMem();
+            assertEquals(postIndexed.addressingMode, ArmConstants.ARM_MEM_POST_INDEXED);
+            assertEquals(capstone.getRegName(postIndexed.postIndexRegister), "r2");
+            assertEquals(postIndexed.indexShiftType, ArmConstants.ARM_SFT_LSL);
+            assertEquals(postIndexed.indexShiftValue, 2);
+            assertTrue(postIndexed.isSubtracted);
+
+            CsMemOperandArm32 offset = insns[1].operands[1].getMem();
+            assertEquals(offset.addressingMode, ArmConstants.ARM_MEM_OFFSET);
+            assertFalse(offset.baseUpdated);
+        }
+    }
+
+    @Test
+    void testSystemRegisters() {
+        byte[] sysregCode = new byte[] { 0x00, 0x20, 0x38, (byte) 0xD5, 0x01, 0x10, 0x18, (byte) 0xD5, 0x02,
+                (byte) 0xF2, 0x38, (byte) 0xD5 };
+        try (Capstone capstone = new Capstone(CapstoneMode.ARM64)) {
+            CapstoneResult result = new CapstoneResult();
+            capstone.disassembleAll(result, sysregCode, 0x1000);
+            CsInsnArm64[] insns = result.toArray(CsInsnArm64[].class);
+
+            // mrs x0, ttbr0_el1
+            assertNull(insns[0].operands[0].systemRegister);
+            SystemRegister ttbr0 = insns[0].operands[1].systemRegister;
+            assertEquals("TTBR0_EL1", ttbr0.name);
+            assertEquals(3, ttbr0.op0);
+            assertEquals(2, ttbr0.crn);
+
+            // msr sctlr_el1, x1
+            assertEquals("SCTLR_EL1", insns[1].operands[0].systemRegister.name);
+
+            // mrs x2, s3_0_c15_c2_0
+            SystemRegister unknown = insns[2].operands[1].systemRegister;
+            assertNull(unknown.name);
+            assertEquals("S3_0_C15_C2_0", unknown.toString());
+        }
+
+        byte[] cp15Code = new byte[] { 0x50, 0x0F, 0x11, (byte) 0xEE, 0x12, 0x0F, 0x41, (byte) 0xEC };
+        try (Capstone capstone = new Capstone(CapstoneMode.ARM32)) {
+            CapstoneResult result = new CapstoneResult();
+            capstone.disassembleAll(result, cp15Code, 0x1000);
+            CsInsnArm32[] insns = result.toArray(CsInsnArm32[].class);
+
+            // mrc p15, #0, r0, c1, c0, #2
+            SystemRegister cpacr = insns[0].operands[0].systemRegister;
+            assertEquals("CPACR", cpacr.name);
+            assertEquals(15, cpacr.coprocessor);
+
+            // mcrr p15, #1, r0, r1, c2
+            assertEquals("TTBR1", insns[1].operands[0].systemRegister.name);
+        }
+    }
+
+    @Test
+    void testARM32ControlFlow() {
+        byte[] armCode = new byte[] { 0x10, 0x40, 0x2D, (byte) 0xE9, 0x01, 0x00, 0x00, 0x0A, 0x04, (byte) 0xF0,
+                (byte) 0x9F, (byte) 0xE5, 0x1E, (byte) 0xFF, 0x2F, (byte) 0xE1, 0x10, (byte) 0x80, (byte) 0xBD,
+                (byte) 0xE8, 0x00, 0x00, 0x00, (byte) 0xEB, 0x00, 0x00, 0x00, (byte) 0xFA };
+        try (Capstone capstone = new Capstone(CapstoneMode.ARM32)) {
+            CapstoneResult result = new CapstoneResult();
+            capstone.disassembleAll(result, armCode, 0x1000);
+            CsInsnArm32[] insns = result.toArray(CsInsnArm32[].class);
+            assertEquals(insns.length, 7);
+
+            // push {r4, lr}
+            assertFalse(insns[0].isBranch);
+            assertFalse(insns[0].isTerminator);
+
+            // beq #0x1010
+            assertTrue(insns[1].isBranch);
+            assertTrue(insns[1].isConditional);
+            assertFalse(insns[1].isTerminator);
+            assertEquals(insns[1].branchTarget, 0x1010L);
+
+            // ldr pc, [pc, #4]
+            assertTrue(insns[2].isBranch);
+            assertTrue(insns[2].isIndirect);
+            assertTrue(insns[2].isPcRelative);
+            assertTrue(insns[2].isTerminator);
+            assertNull(insns[2].branchTarget);
+            assertEquals(insns[2].referencedAddress, 0x1014L);
+            assertEquals(insns[2].referencedValue, 0xEB000000L);
+
+            // bx lr
+            assertTrue(insns[3].isReturn);
+            assertTrue(insns[3].isTerminator);
+
+            // pop {r4, pc}
+            assertTrue(insns[4].isReturn);
+            assertTrue(insns[4].isTerminator);
+
+            // bl #0x1020
+            assertTrue(insns[5].isCall);
+            assertFalse(insns[5].isIndirect);
+            assertFalse(insns[5].isTerminator);
+            assertEquals(insns[5].branchTarget, 0x101CL);
+            assertFalse(insns[5].isInterworking);
+
+            // blx #0x1020
+            assertTrue(insns[6].isCall);
+            assertEquals(insns[6].branchTarget, 0x1020L);
+            assertTrue(insns[6].isInterworking);
+        }
+    }
+
+    @Test
+    void testControlFlowGraph() {
+        // push {r4, lr}; beq #0x1010; ldr pc, [pc, #4]; bx lr; pop {r4, pc}
+        byte[] armCode = new byte[] { 0x10, 0x40, 0x2D, (byte) 0xE9, 0x01, 0x00, 0x00, 0x0A, 0x04, (byte) 0xF0,
+                (byte) 0x9F, (byte) 0xE5, 0x1E, (byte) 0xFF, 0x2F, (byte) 0xE1, 0x10, (byte) 0x80, (byte) 0xBD,
+                (byte) 0xE8 };
+        try (Capstone capstone = new Capstone(CapstoneMode.ARM32)) {
+            ControlFlowGraph graph = capstone.buildControlFlowGraph(armCode, 0x1000, 0x1000);
+            List<BasicBlock> blocks = graph.getBlocks();
+            assertEquals(blocks.size(), 3);
+
+            BasicBlock entry = blocks.get(0);
+            assertEquals(entry.startAddress, 0x1000);
+            assertEquals(entry.endAddress, 0x1008);
+            assertEquals(entry.instructionCount, 2);
+            assertEquals(entry.predecessors.length, 0);
+            assertEquals(entry.successors.length, 2);
+
+            FlowEdge taken = entry.successors[0];
+            assertEquals(taken.kind, FlowEdge.TAKEN);
+            assertSame(taken.target, blocks.get(2));
+            assertEquals(taken.target.startAddress, 0x1010);
+
+            FlowEdge fallthrough = entry.successors[1];
+            assertEquals(fallthrough.kind, FlowEdge.FALLTHROUGH);
+            assertSame(fallthrough.target, blocks.get(1));
+            assertSame(fallthrough.target.predecessors[0], fallthrough);
+
+            // 'bx lr' is never reached
+            assertNull(graph.getBlock(0x100C));
+            assertSame(graph.getBlock(0x1004), entry);
+            assertEquals(graph.getEdges().size(), 2);
+        }
+
+        try (Capstone capstone = new Capstone(CapstoneMode.X86_64)) {
+            assertThrows(CapstoneException.class,
+                    () -> capstone.buildControlFlowGraph(new byte[] { (byte) 0xC3 }, 0, 0));
+        }
+    }
+
+    @Test
+    void testRecursiveDisassembly() {
+        // b #0x100c; .word 0xffffffff; .word 0xe7f000f0; bl #0x1018; bx lr; .word 0; mov r0, #1; bx lr
+        byte[] armCode = new byte[] { 0x01, 0x00, 0x00, (byte) 0xEA, (byte) 0xFF, (byte) 0xFF, (byte) 0xFF,
+                (byte) 0xFF, (byte) 0xF0, 0x00, (byte) 0xF0, (byte) 0xE7, 0x01, 0x00, 0x00, (byte) 0xEB, 0x1E,
+                (byte) 0xFF, 0x2F, (byte) 0xE1, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, (byte) 0xA0, (byte) 0xE3, 0x1E,
+                (byte) 0xFF, 0x2F, (byte) 0xE1 };
+        try (Capstone capstone = new Capstone(CapstoneMode.ARM32)) {
+            CapstoneResult result = new CapstoneResult();
+            capstone.disassembleRecursive(result, armCode, 0x1000, 0x1000);
+            CsInsnArm32[] insns = result.toArray(CsInsnArm32[].class);
+
+            long[] addresses = Arrays.stream(insns).mapToLong(insn -> insn.address).toArray();
+            assertArrayEquals(addresses, new long[] { 0x1000, 0x100C, 0x1010, 0x1018, 0x101C });
+            assertEquals(insns[2].mnemonic, "bx");
+            assertEquals(insns[3].mnemonic, "mov");
+        }
+    }
+
+    @Test
+    void testFunctionDetection() {
+        // stp x29, x30, [sp, #-0x10]!; bl #0x1010; ldp x29, x30, [sp], #0x10; ret
+        // mov w0, #1; ret
+        // stp x29, x30, [sp, #-0x20]!; mov x29, sp; ldp x29, x30, [sp], #0x20; ret
+        byte[] arm64Code = new byte[] { (byte) 0xFD, 0x7B, (byte) 0xBF, (byte) 0xA9, 0x03, 0x00, 0x00, (byte) 0x94,
+                (byte) 0xFD, 0x7B, (byte) 0xC1, (byte) 0xA8, (byte) 0xC0, 0x03, 0x5F, (byte) 0xD6, 0x20, 0x00,
+                (byte) 0x80, 0x52, (byte) 0xC0, 0x03, 0x5F, (byte) 0xD6, (byte) 0xFD, 0x7B, (byte) 0xBE, (byte) 0xA9,
+                (byte) 0xFD, 0x03, 0x00, (byte) 0x91, (byte) 0xFD, 0x7B, (byte) 0xC2, (byte) 0xA8, (byte) 0xC0, 0x03,
+                0x5F, (byte) 0xD6 };
+        try (Capstone capstone = new Capstone(CapstoneMode.ARM64)) {
+            FunctionTable table = capstone.findFunctions(arm64Code, 0x1000, 0x1000);
+            List<FunctionRange> functions = table.getFunctions();
+            assertEquals(functions.size(), 3);
+
+            FunctionRange main = functions.get(0);
+            assertEquals(main.startAddress, 0x1000);
+            assertEquals(main.endAddress, 0x1010);
+            assertEquals(main.instructionCount, 4);
+            assertTrue(main.isCallTarget);
+            assertTrue(main.hasPrologue);
+            assertTrue(main.returns);
+
+            // Leaf function only found through the call
+            FunctionRange leaf = functions.get(1);
+            assertEquals(leaf.startAddress, 0x1010);
+            assertEquals(leaf.endAddress, 0x1018);
+            assertTrue(leaf.isCallTarget);
+            assertFalse(leaf.hasPrologue);
+
+            // Function only found through its prologue
+            FunctionRange uncalled = functions.get(2);
+            assertEquals(uncalled.startAddress, 0x1018);
+            assertEquals(uncalled.endAddress, 0x1028);
+            assertFalse(uncalled.isCallTarget);
+            assertTrue(uncalled.hasPrologue);
+
+            assertSame(table.getFunction(0x101C), uncalled);
+            assertNull(table.getFunction(0x1028));
+        }
+    }
+
+    @Test
+    void testJumpTables() {
+        // cmp r0, #2; ldrls pc, [pc, r0, lsl #2]; b #0x1020; .word 0x1018, 0x101c, 0x1020
+        // mov r0, #0; mov r0, #1; bx lr
+        byte[] armCode = new byte[] { 0x02, 0x00, 0x50, (byte) 0xE3, 0x00, (byte) 0xF1, (byte) 0x9F, (byte) 0x97,
+                0x04, 0x00, 0x00, (byte) 0xEA, 0x18, 0x10, 0x00, 0x00, 0x1C, 0x10, 0x00, 0x00, 0x20, 0x10, 0x00, 0x00,
+                0x00, 0x00, (byte) 0xA0, (byte) 0xE3, 0x01, 0x00, (byte) 0xA0, (byte) 0xE3, 0x1E, (byte) 0xFF, 0x2F,
+                (byte) 0xE1 };
+        try (Capstone capstone = new Capstone(CapstoneMode.ARM32)) {
+            CapstoneResult result = new CapstoneResult();
+            capstone.disassembleCount(result, armCode, 3, 0x1000);
+            CsInsnArm32[] insns = result.toArray(CsInsnArm32[].class);
+            assertEquals(insns[1].jumpTableAddress, 0x100CL);
+            assertArrayEquals(insns[1].jumpTableTargets, new long[] { 0x1018, 0x101C, 0x1020 });
+            assertNull(insns[2].jumpTableTargets);
+
+            // The table itself is never decoded
+            capstone.disassembleRecursive(result, armCode, 0x1000, 0x1000);
+            insns = result.toArray(CsInsnArm32[].class);
+            long[] addresses = Arrays.stream(insns).mapToLong(insn -> insn.address).toArray();
+            assertArrayEquals(addresses, new long[] { 0x1000, 0x1004, 0x1008, 0x1018, 0x101C, 0x1020 });
+        }
+
+        // cmp w0, #2; b.hi #0x2024; adr x1, #0x202c; ldrb w0, [x1, w0, uxtw]; adr x2, #0x201c;
+        // add x0, x2, w0, sxtb #2; br x0; mov w0, #10; ret; mov w0, #0; ret; .byte 0, 1, 2
+        byte[] arm64Code = new byte[] { 0x1F, 0x08, 0x00, 0x71, 0x08, 0x01, 0x00, 0x54, 0x21, 0x01, 0x00, 0x10, 0x20,
+                0x48, 0x60, 0x38, 0x62, 0x00, 0x00, 0x10, 0x40, (byte) 0x88, 0x20, (byte) 0x8B, 0x00, 0x00, 0x1F,
+                (byte) 0xD6, 0x40, 0x01, (byte) 0x80, 0x52, (byte) 0xC0, 0x03, 0x5F, (byte) 0xD6, 0x00, 0x00,
+                (byte) 0x80, 0x52, (byte) 0xC0, 0x03, 0x5F, (byte) 0xD6, 0x00, 0x01, 0x02, 0x00 };
+        try (Capstone capstone = new Capstone(CapstoneMode.ARM64)) {
+            CapstoneResult result = new CapstoneResult();
+            capstone.disassembleCount(result, arm64Code, 11, 0x2000);
+            CsInsnArm64[] insns = result.toArray(CsInsnArm64[].class);
+            assertEquals(insns[6].mnemonic, "br");
+            assertEquals(insns[6].jumpTableAddress, 0x202CL);
+            assertArrayEquals(insns[6].jumpTableTargets, new long[] { 0x201C, 0x2020, 0x2024 });
+
+            ControlFlowGraph graph = capstone.buildControlFlowGraph(arm64Code, 0x2000, 0x2000);
+            BasicBlock dispatch = graph.getBlock(0x2018);
+            assertEquals(Arrays.stream(dispatch.successors).filter(edge -> edge.kind == FlowEdge.INDIRECT).count(), 3);
+
+            // cmp w0, #3; b.hs #0x2024: an index equal to the bound leaves the table, so the
+            // padding byte after it is not an entry
+            byte[] lowerCode = arm64Code.clone();
+            lowerCode[1] = 0x0C;
+            lowerCode[4] = 0x02;
+            capstone.disassembleCount(result, lowerCode, 11, 0x2000);
+            insns = result.toArray(CsInsnArm64[].class);
+            assertEquals(insns[1].mnemonic, "b.hs");
+            assertArrayEquals(insns[6].jumpTableTargets, new long[] { 0x201C, 0x2020, 0x2024 });
+        }
+    }
+
+    @Test
+    void testXrefIndex() {
+        try (Capstone capstone = new Capstone(CapstoneMode.ARM64)) {
+            XrefIndex index = capstone.buildXrefIndex(code, 0x1000);
+
+            // bl #0xedc
+            List<Xref> calls = index.getReferencesTo(3804);
+            assertEquals(calls.size(), 1);
+            assertEquals(calls.get(0).source, 0x1010);
+            assertEquals(calls.get(0).kind, Xref.CALL);
+
+            // The adrp page is only recorded once completed by the add
+            assertTrue(index.getReferencesFrom(0x1008).isEmpty());
+            List<Xref> address = index.getReferencesFrom(0x100C);
+            assertEquals(address.size(), 1);
+            assertEquals(address.get(0).target, 0x1790);
+            assertEquals(address.get(0).kind, Xref.ADDRESS);
+
+            assertEquals(index.getReferences().size(), 2);
+        }
+    }
+
+    /**
+     * Build an ARM ELF32 file with a Thumb function 'thumb_fn' at 0x8000 and an
+     * ARM function 'arm_fn' at 0x8004.
+     */
+    private static byte[] buildArmElf() {
+        byte[] text = new byte[] { (byte) 0x80, (byte) 0xB5, (byte) 0x80, (byte) 0xBD, 0x1E, (byte) 0xFF, 0x2F,
+                (byte) 0xE1 };
+        byte[] strtab = "\0thumb_fn\0arm_fn\0".getBytes(StandardCharsets.US_ASCII);
+        byte[] shstrtab = "\0.text\0.symtab\0.strtab\0.shstrtab\0".getBytes(StandardCharsets.US_ASCII);
+        int textOffset = 52;
+        int symtabOffset = textOffset + text.length;
+        int strtabOffset = symtabOffset + 3 * 16;
+        int shstrtabOffset = strtabOffset + strtab.length;
+        int shoff = (shstrtabOffset + shstrtab.length + 3) & ~3;
+
+        ByteBuffer elf = ByteBuffer.allocate(shoff + 5 * 40).order(ByteOrder.LITTLE_ENDIAN);
+        elf.put(new byte[] { 0x7F, 'E', 'L', 'F', 1, 1, 1 });
+        elf.position(16);
+        elf.putShort((short) 2).putShort((short) ElfFile.EM_ARM).putInt(1).putInt(0x8004).putInt(0).putInt(shoff)
+                .putInt(0).putShort((short) 52).putShort((short) 32).putShort((short) 0).putShort((short) 40)
+                .putShort((short) 5).putShort((short) 4);
+        elf.put(text);
+        // Null symbol, then name, value, size, info, other, section index
+        elf.put(new byte[16]);
+        elf.putInt(1).putInt(0x8001).putInt(4).put((byte) 0x12).put((byte) 0).putShort((short) 1);
+        elf.putInt(10).putInt(0x8004).putInt(4).put((byte) 0x12).put((byte) 0).putShort((short) 1);
+        elf.put(strtab).put(shstrtab);
+
+        // Name, type, flags, address, offset, size, link, info, alignment, entry size
+        elf.position(shoff + 40);
+        elf.putInt(1).putInt(1).putInt(6).putInt(0x8000).putInt(textOffset).putInt(text.length).putInt(0).putInt(0)
+                .putInt(4).putInt(0);
+        elf.putInt(7).putInt(2).putInt(0).putInt(0).putInt(symtabOffset).putInt(3 * 16).putInt(3).putInt(1)
+                .putInt(4).putInt(16);
+        elf.putInt(15).putInt(3).putInt(0).putInt(0).putInt(strtabOffset).putInt(strtab.length).putInt(0)
+                .putInt(0).putInt(1).putInt(0);
+        elf.putInt(23).putInt(3).putInt(0).putInt(0).putInt(shstrtabOffset).putInt(shstrtab.length).putInt(0)
+                .putInt(0).putInt(1).putInt(0);
+        return elf.array();
+    }
+
+    @Test
+    void testElfLoader() {
+        ElfFile elf = ElfFile.load(buildArmElf());
+        assertEquals(elf.getMachine(), ElfFile.EM_ARM);
+        assertFalse(elf.is64Bit());
+        assertSame(elf.getMode(), CapstoneMode.ARM32);
+        assertEquals(elf.getEntryPoint(), 0x8004);
+
+        Section text = elf.getSection(".text");
+        assertNotNull(text);
+        assertEquals(text.address, 0x8000);
+        assertTrue(text.isExecutable);
+        assertEquals(elf.getSections().size(), 5);
+
+        Symbol thumb = elf.getSymbol("thumb_fn");
+        assertNotNull(thumb);
+        assertEquals(thumb.address, 0x8000);
+        assertTrue(thumb.isFunction);
+        assertTrue(thumb.isThumb);
+        assertSame(elf.getMode(thumb), CapstoneMode.THUMB);
+
+        // push {r7, lr}; pop {r7, pc}
+        CsInsnArm32[] thumbInsns = elf.disassembleSymbol("thumb_fn").toArray(CsInsnArm32[].class);
+        assertEquals(thumbInsns.length, 2);
+        assertEquals(thumbInsns[0].mnemonic, "push");
+        assertEquals(thumbInsns[1].address, 0x8002);
+
+        // bx lr
+        CsInsnArm32[] armInsns = elf.disassembleSymbol("arm_fn").toArray(CsInsnArm32[].class);
+        assertEquals(armInsns.length, 1);
+        assertEquals(armInsns[0].mnemonic, "bx");
+        assertEquals(armInsns[0].address, 0x8004);
+
+        assertThrows(CapstoneException.class, () -> ElfFile.load(new byte[] { 0x7F, 'E', 'L', 'F', 1, 1 }));
+
+        // With a Thumb entry point the file starts in Thumb state, ARM functions are still ARM
+        ByteBuffer thumbEntry = ByteBuffer.wrap(buildArmElf()).order(ByteOrder.LITTLE_ENDIAN);
+        thumbEntry.putInt(24, 0x8001);
+        ElfFile mixed = ElfFile.load(thumbEntry.array());
+        assertSame(mixed.getMode(), CapstoneMode.THUMB);
+        assertSame(mixed.getMode(mixed.getSymbol("thumb_fn")), CapstoneMode.THUMB);
+        assertSame(mixed.getMode(mixed.getSymbol("arm_fn")), CapstoneMode.ARM32);
+        armInsns = mixed.disassembleSymbol("arm_fn").toArray(CsInsnArm32[].class);
+        assertEquals(armInsns.length, 1);
+        assertEquals(armInsns[0].mnemonic, "bx");
+
+        // A truncated section header table
+        byte[] data = buildArmElf();
+        assertThrows(CapstoneException.class, () -> ElfFile.load(Arrays.copyOf(data, data.length - 20)));
+
+        // A zero section header size with the section count taken from a huge first header
+        ByteBuffer zeroSize = ByteBuffer.wrap(data.clone()).order(ByteOrder.LITTLE_ENDIAN);
+        int shoff = zeroSize.getInt(32);
+        zeroSize.putShort(46, (short) 0).putShort(48, (short) 0).putInt(shoff + 20, 0x7FFFFFFF);
+        assertThrows(CapstoneException.class, () -> ElfFile.load(zeroSize.array()));
+        zeroSize.putShort(46, (short) 40);
+        assertThrows(CapstoneException.class, () -> ElfFile.load(zeroSize.array()));
+
+        // Program headers smaller than an ELF32 program header
+        ByteBuffer smallSegments = ByteBuffer.wrap(data.clone()).order(ByteOrder.LITTLE_ENDIAN);
+        smallSegments.putInt(28, 52).putShort(42, (short) 0).putShort(44, (short) 1000);
+        assertThrows(CapstoneException.class, () -> ElfFile.load(smallSegments.array()));
+    }
+
+    /**
+     * Build a fat binary with a single ARM64 slice whose '__TEXT,__text' section
+     * holds {@link #code} at 0x100000100.
+     */
+    private static byte[] buildFatMachO() {
+        ByteBuffer slice = ByteBuffer.allocate(315).order(ByteOrder.LITTLE_ENDIAN);
+        slice.putInt(0xFEEDFACF).putInt(MachOFile.CPU_TYPE_ARM64).putInt(0).putInt(2).putInt(4).putInt(216).putInt(0)
+                .putInt(0);
+
+        // LC_SEGMENT_64 with a single section
+        slice.putInt(0x19).putInt(152).put(Arrays.copyOf("__TEXT".getBytes(StandardCharsets.US_ASCII), 16));
+        slice.putLong(0x100000000L).putLong(0x1000).putLong(0).putLong(315).putInt(5).putInt(5).putInt(1).putInt(0);
+        slice.put(Arrays.copyOf("__text".getBytes(StandardCharsets.US_ASCII), 16));
+        slice.put(Arrays.copyOf("__TEXT".getBytes(StandardCharsets.US_ASCII), 16));
+        slice.putLong(0x100000100L).putLong(code.length).putInt(256).putInt(2).putInt(0).putInt(0)
+                .putInt(0x80000400).putInt(0).putInt(0).putInt(0);
+
+        // LC_SYMTAB, LC_FUNCTION_STARTS and LC_MAIN
+        slice.putInt(0x2).putInt(24).putInt(292).putInt(1).putInt(308).putInt(7);
+        slice.putInt(0x26).putInt(16).putInt(288).putInt(4);
+        slice.putInt(0x80000028).putInt(24).putLong(0x100).putLong(0);
+
+        slice.position(256);
+        slice.put(code);
+        slice.put(new byte[] { (byte) 0x80, 0x02, 0x00, 0x00 });
+        slice.putInt(1).put((byte) 0x0F).put((byte) 1).putShort((short) 0).putLong(0x100000100L);
+        slice.put("\0_main\0".getBytes(StandardCharsets.US_ASCII));
+
+        ByteBuffer fat = ByteBuffer.allocate(32 + 315).order(ByteOrder.BIG_ENDIAN);
+        fat.putInt(0xCAFEBABE).putInt(1).putInt(MachOFile.CPU_TYPE_ARM64).putInt(0).putInt(32).putInt(315).putInt(5);
+        fat.position(32);
+        fat.put(slice.array());
+        return fat.array();
+    }
+
+    @Test
+    void testMachOLoader() {
+        byte[] data = buildFatMachO();
+        MachOFile macho = MachOFile.load(data);
+        assertEquals(macho.getCpuType(), MachOFile.CPU_TYPE_ARM64);
+        assertSame(macho.getMode(), CapstoneMode.ARM64);
+        assertEquals(macho.getSliceOffset(), 32);
+        assertEquals(macho.getEntryPoint(), 0x100000100L);
+        assertArrayEquals(macho.getFunctionStarts(), new long[] { 0x100000100L });
+        assertEquals(macho.getSegments().get(0).name, "__TEXT");
+
+        Section text = macho.getSection("__TEXT,__text");
+        assertNotNull(text);
+        assertEquals(text.address, 0x100000100L);
+        assertTrue(text.isExecutable);
+
+        Symbol main = macho.getSymbol("_main");
+        assertNotNull(main);
+        assertTrue(main.isFunction);
+        assertEquals(main.size, code.length);
+
+        CsInsnArm64[] insns = macho.disassembleSymbol("_main").toArray(CsInsnArm64[].class);
+        assertEquals(insns.length, 8);
+        assertEquals(insns[0].mnemonic, "stp");
+        assertEquals(insns[0].address, 0x100000100L);
+        assertEquals(macho.disassembleSection("__TEXT,__text").getInstructions().size(), 8);
+
+        assertThrows(CapstoneException.class, () -> MachOFile.load(data, MachOFile.CPU_TYPE_X86_64));
+
+        // Entry point and segment file offsets past the end of the address space
+        ByteBuffer entry = ByteBuffer.wrap(data.clone()).order(ByteOrder.LITTLE_ENDIAN);
+        entry.putLong(32 + 232, -1);
+        assertThrows(CapstoneException.class, () -> MachOFile.load(entry.array()));
+        ByteBuffer fileOffset = ByteBuffer.wrap(data.clone()).order(ByteOrder.LITTLE_ENDIAN);
+        fileOffset.putLong(32 + 72, -1);
+        assertThrows(CapstoneException.class, () -> MachOFile.load(fileOffset.array()));
+    }
+
+    /**
+     * Build an ARM64 PE32+ image with {@link #code} in '.text' at 0x140001000,
+     * exported as 'run', an import of KERNEL32.dll!ExitProcess and a packed
+     * '.pdata' entry for the function.
+     */
+    private static byte[] buildArm64Pe() {
+        ByteBuffer pe = ByteBuffer.allocate(0x700).order(ByteOrder.LITTLE_ENDIAN);
+        pe.put(0, (byte) 'M').put(1, (byte) 'Z').putInt(0x3C, 0x40);
+        pe.put(0x40, (byte) 'P').put(0x41, (byte) 'E');
+
+        // COFF header and PE32+ optional header with the export, import and
+        // exception directories
+        pe.putShort(0x44, (short) PeFile.IMAGE_FILE_MACHINE_ARM64).putShort(0x46, (short) 2)
+                .putShort(0x54, (short) 0xF0).putShort(0x56, (short) 0x22);
+        pe.putShort(0x58, (short) 0x20B).putInt(0x58 + 16, 0x1000).putLong(0x58 + 24, 0x140000000L)
+                .putInt(0x58 + 108, 16);
+        pe.putInt(0x58 + 112, 0x2000).putInt(0x58 + 116, 0x60);
+        pe.putInt(0x58 + 120, 0x2100).putInt(0x58 + 124, 40);
+        pe.putInt(0x58 + 136, 0x2200).putInt(0x58 + 140, 8);
+
+        // .text and .rdata section headers
+        pe.position(0x148);
+        pe.put(Arrays.copyOf(".text".getBytes(StandardCharsets.US_ASCII), 8)).putInt(code.length).putInt(0x1000)
+                .putInt(0x200).putInt(0x200).putLong(0).putInt(0).putInt(0x60000020);
+        pe.put(Arrays.copyOf(".rdata".getBytes(StandardCharsets.US_ASCII), 8)).putInt(0x300).putInt(0x2000)
+                .putInt(0x300).putInt(0x400).putLong(0).putInt(0).putInt(0x40000040);
+        pe.position(0x200);
+        pe.put(code);
+
+        // Export directory at RVA 0x2000
+        int rdata = 0x400 - 0x2000;
+        pe.putInt(rdata + 0x200C, 0x2050).putInt(rdata + 0x2010, 1).putInt(rdata + 0x2014, 1)
+                .putInt(rdata + 0x2018, 1).putInt(rdata + 0x201C, 0x2028).putInt(rdata + 0x2020, 0x202C)
+                .putInt(rdata + 0x2024, 0x2030);
+        pe.putInt(rdata + 0x2028, 0x1000).putInt(rdata + 0x202C, 0x2040).putShort(rdata + 0x2030, (short) 0);
+        pe.position(rdata + 0x2040);
+        pe.put("run\0".getBytes(StandardCharsets.US_ASCII));
+        pe.position(rdata + 0x2050);
+        pe.put("test.dll\0".getBytes(StandardCharsets.US_ASCII));
+
+        // Import descriptor at RVA 0x2100 with lookup and address tables
+        pe.putInt(rdata + 0x2100, 0x2140).putInt(rdata + 0x210C, 0x2180).putInt(rdata + 0x2110, 0x2160);
+        pe.putLong(rdata + 0x2140, 0x2190).putLong(rdata + 0x2160, 0x2190);
+        pe.position(rdata + 0x2180);
+        pe.put("KERNEL32.dll\0".getBytes(StandardCharsets.US_ASCII));
+        pe.putShort(rdata + 0x2190, (short) 5);
+        pe.position(rdata + 0x2192);
+        pe.put("ExitProcess\0".getBytes(StandardCharsets.US_ASCII));
+
+        // Packed .pdata entry, 8 instructions long
+        pe.putInt(rdata + 0x2200, 0x1000).putInt(rdata + 0x2204, (8 << 2) | 1);
+        return pe.array();
+    }
+
+    @Test
+    void testPeLoader() {
+        PeFile pe = PeFile.load(buildArm64Pe());
+        assertEquals(pe.getMachine(), PeFile.IMAGE_FILE_MACHINE_ARM64);
+        assertTrue(pe.is64Bit());
+        assertSame(pe.getMode(), CapstoneMode.ARM64);
+        assertEquals(pe.getEntryPoint(), 0x140001000L);
+
+        Section text = pe.getSection(".text");
+        assertNotNull(text);
+        assertEquals(text.address, 0x140001000L);
+        assertEquals(text.fileSize, code.length);
+
+        List<RuntimeFunction> functions = pe.getRuntimeFunctions();
+        assertEquals(functions.size(), 1);
+        assertEquals(functions.get(0).startAddress, 0x140001000L);
+        assertEquals(functions.get(0).endAddress, 0x140001020L);
+        assertEquals(pe.disassembleFunction(functions.get(0)).getInstructions().size(), 8);
+
+        assertEquals(pe.getExports().size(), 1);
+        assertEquals(pe.getExports().get(0).name, "run");
+        assertEquals(pe.getExports().get(0).ordinal, 1);
+        Symbol run = pe.getSymbol("run");
+        assertNotNull(run);
+        assertEquals(run.size, code.length);
+        CsInsnArm64[] insns = pe.disassembleSymbol("run").toArray(CsInsnArm64[].class);
+        assertEquals(insns[0].mnemonic, "stp");
+        assertEquals(insns[0].address, 0x140001000L);
+
+        Import exit = pe.getImports().get(0);
+        assertEquals(exit.library, "KERNEL32.dll");
+        assertEquals(exit.name, "ExitProcess");
+        assertEquals(exit.ordinal, 5);
+        assertEquals(exit.address, 0x140002160L);
+        assertNull(pe.getSymbol("ExitProcess"));
+
+        // An image base whose sections end past the address space
+        ByteBuffer imageBase = ByteBuffer.wrap(buildArm64Pe()).order(ByteOrder.LITTLE_ENDIAN);
+        imageBase.putLong(0x58 + 24, -0x2000L);
+        assertThrows(CapstoneException.class, () -> PeFile.load(imageBase.array()));
+    }
+
+    @Test
+    void testSymbolizedOperands() {
+        try (Capstone capstone = new Capstone(CapstoneMode.ARM64)) {
+            CapstoneResult result = new CapstoneResult();
+            SymbolTable table = new SymbolTable(Map.of(0xEDCL, "puts", 0x1790L, "g_table"));
+            capstone.disassembleSymbolized(result, code, 0x1000, table);
+            CsInsnArm64[] insns = result.toArray(CsInsnArm64[].class);
+
+            assertEquals(insns[0].symbolizedOperand, insns[0].operand);
+            assertEquals(insns[2].symbolizedOperand, "x0, g_table@PAGE");
+            assertEquals(insns[3].symbolizedOperand, "x0, x0, g_table@PAGEOFF");
+            assertEquals(insns[4].symbolizedOperand, "puts");
+            assertEquals(insns[4].operand, "#0xedc");
+
+            // Any other resolver is called back
+            capstone.disassembleSymbolized(result, code, 0x1000, address -> address == 0xEDC ? "printf" : null);
+            insns = result.toArray(CsInsnArm64[].class);
+            assertEquals(insns[4].symbolizedOperand, "printf");
+            assertEquals(insns[2].symbolizedOperand, "x0, #0x1000");
+
+            // The resolver may call back into the same instance
+            int blId = insns[4].instructionId;
+            capstone.disassembleSymbolized(result, code, 0x1000,
+                    address -> address == 0xEDC ? capstone.getInsnName(blId) : null);
+            assertEquals(result.toArray(CsInsnArm64[].class)[4].symbolizedOperand, "bl");
+
+            capstone.disassembleAll(result, code, 0x1000);
+            assertNull(result.toArray(CsInsnArm64[].class)[4].symbolizedOperand);
+        }
+    }
+
+    @Test
+    void testListing() throws IOException {
+        try (Capstone capstone = new Capstone(CapstoneMode.ARM64)) {
+            byte[] bytes = Arrays.copyOf(code, code.length + 4);
+            Arrays.fill(bytes, code.length, bytes.length, (byte) 0xFF);
+
+            ListingOptions options = new ListingOptions();
+            options.symbols = new SymbolTable(Map.of(0x1000L, "main", 0xEDCL, "puts"));
+            String text = capstone.formatListing(bytes, 0x1000, options);
+            String[] lines = text.split("\n");
+
+            assertEquals("00001000 <main>:", lines[1]);
+            assertEquals("    1000:\ta9bf7bfd \tstp\tx29, x30, [sp, #-0x10]!", lines[2]);
+            assertTrue(text.contains("\tbl\t#0xedc <puts>\n"));
+            assertTrue(text.endsWith(":\tffffffff \t.inst\t0xffffffff ; undefined\n"));
+
+            // The resolver may call back into the same instance
+            options.symbols = address -> address == 0xEDC ? capstone.getGroupName((short) 1) : null;
+            text = capstone.formatListing(bytes, 0x1000, options);
+            assertTrue(text.contains("\tbl\t#0xedc <jump>\n"));
+
+            options.byteGrouping = 1;
+            options.addressWidth = 4;
+            options.symbols = null;
+            text = capstone.formatListing(bytes, 0x1000, options);
+            assertTrue(text.startsWith("1000:\tfd 7b bf a9 \tstp"));
+
+            Path path = Files.createTempFile("listing", ".txt");
+            try {
+                capstone.writeListing(path, bytes, 0x1000, options);
+                assertEquals(text, Files.readString(path));
+            } finally {
+                Files.delete(path);
+            }
+        }
+    }
+
+    @Test
+    void testJsonExport() throws IOException {
+        try (Capstone capstone = new Capstone(CapstoneMode.ARM64)) {
+            String json = capstone.exportJson(code, 0x1000);
+
+            assertTrue(json.startsWith("{\"schemaVersion\":" + JsonExport.SCHEMA_VERSION + ",\"mode\":\"ARM64\","));
+            assertTrue(json.contains("\"mnemonic\":\"stp\",\"operand\":\"x29, x30, [sp, #-0x10]!\""));
+            assertTrue(json.contains("\"operandType\":3,\"value\":{\"base\":\"sp\",\"index\":null,\"disp\":-16,"
+                    + "\"addressingMode\":1,\"baseUpdated\":true,"));
+            assertTrue(json.contains("\"mnemonic\":\"adrp\""));
+            assertTrue(json.contains("\"referencedAddress\":6032"));
+            assertTrue(json.contains("\"isCall\":true"));
+
+            Path path = Files.createTempFile("export", ".json");
+            try {
+                capstone.writeJson(path, code, 0x1000);
+                assertEquals(Files.readString(path), json);
+            } finally {
+                Files.delete(path);
+            }
+        }
+    }
+
+    @Test
+    void testGenericDisassembly() {
+        byte[] x86Code = new byte[] { 0x55, 0x48, (byte) 0x89, (byte) 0xE5, 0x48, (byte) 0x8B, 0x45, (byte) 0xF8,
+                (byte) 0xC3 };
+        try (Capstone capstone = new Capstone(CapstoneMode.X86_64)) {
+            CapstoneResult result = new CapstoneResult();
+            capstone.disassembleAll(result, x86Code, 0x1000);
+            CsInsnGeneric[] insns = result.toArray(CsInsnGeneric[].class);
+            assertEquals(insns.length, 4);
+
+            CsInsnGeneric first = insns[0];
+            assertEquals(first.mnemonic, "push");
+            assertEquals(first.operand, "rbp");
+            assertArrayEquals(first.bytes, new byte[] { 0x55 });
+            assertEquals(first.operands[0].operandType, Constants.GENERIC_OP_REG);
+            assertEquals(capstone.getRegName(first.operands[0].getReg()), "rbp");
+
+            CsInsnGeneric third = insns[2];
+            assertEquals(third.address, 0x1004);
+            assertEquals(third.size, 4);
+            assertEquals(capstone.getRegName(third.operands[1].getMem().base), "rbp");
+            assertEquals(third.operands[1].getMem().displacement, -8);
+
+            CsInsnGeneric last = insns[3];
+            assertEquals(last.mnemonic, "ret");
+            assertEquals(capstone.getGroupName(last.groups[0]), "ret");
+        }
+    }
+
+    @Test
+    void testNameTable() {
+        try (Capstone capstone = new Capstone(CapstoneMode.ARM64)) {
+            NameTable names = capstone.getNameTable();
+            assertSame(names, capstone.getNameTable());
+
+            assertEquals(names.getInsnNames().size(), ArmConstants.ARM64_INS_ENDING);
+            assertNull(names.getInsnName(ArmConstants.ARM64_INS_INVALID));
+            assertNull(names.getInsnName(-1));
+            for (int id = 1; id < ArmConstants.ARM64_INS_ENDING; id += 97) {
+                assertEquals(names.getInsnName(id), capstone.getInsnName(id));
+            }
+            assertEquals(names.getRegName(ArmConstants.ARM64_REG_X0), capstone.getRegName(ArmConstants.ARM64_REG_X0));
+            assertEquals(names.getGroupName(ArmConstants.ARM64_GRP_JUMP), "jump");
+
+            assertEquals(names.getInsnId("stp"), ArmConstants.ARM64_INS_STP);
+            assertEquals(names.getRegId("X0"), ArmConstants.ARM64_REG_X0);
+            assertEquals(names.getGroupId("call"), ArmConstants.ARM64_GRP_CALL);
+            assertEquals(names.getInsnId("nonexistent"), 0);
+        }
+    }
+
+    @Test
+    void testRegisterTable() {
+        try (Capstone capstone = new Capstone(CapstoneMode.ARM64)) {
+            RegisterTable registers = capstone.getRegisterTable();
+            assertSame(registers, capstone.getRegisterTable());
+
+            RegisterInfo w0 = registers.get(ArmConstants.ARM64_REG_W0);
+            assertEquals(w0.size, 32);
+            assertEquals(w0.registerClass, RegisterInfo.CLASS_GENERAL);
+            assertEquals(w0.parent, ArmConstants.ARM64_REG_X0);
+            assertArrayEquals(registers.get(ArmConstants.ARM64_REG_X0).subRegisters,
+                    new int[] { ArmConstants.ARM64_REG_W0 });
+            assertTrue(registers.overlaps(ArmConstants.ARM64_REG_S0, ArmConstants.ARM64_REG_V0));
+            assertFalse(registers.overlaps(ArmConstants.ARM64_REG_S0, ArmConstants.ARM64_REG_X0));
+            assertTrue(registers.get(ArmConstants.ARM64_REG_V0).isSimd);
+            assertNull(registers.get(0));
+        }
+        try (Capstone capstone = new Capstone(CapstoneMode.ARM32)) {
+            RegisterTable registers = capstone.getRegisterTable();
+            assertEquals(ArmConstants.ARM_REG_R13, ArmConstants.ARM_REG_SP);
+            assertEquals(registers.get(ArmConstants.ARM_REG_SP).registerClass, RegisterInfo.CLASS_STACK_POINTER);
+            assertArrayEquals(registers.get(ArmConstants.ARM_REG_D1).subRegisters,
+                    new int[] { ArmConstants.ARM_REG_S2, ArmConstants.ARM_REG_S3 });
+            assertEquals(registers.getFullRegister(ArmConstants.ARM_REG_S2), ArmConstants.ARM_REG_Q0);
+        }
+        try (Capstone capstone = new Capstone(CapstoneMode.X86_64)) {
+            assertThrows(CapstoneException.class, capstone::getRegisterTable);
+        }
+    }
+
     @Test
     void testNullHandle() {
         {